@@ -3,18 +3,20 @@ use std::thread;
 use std::time::{Duration, SystemTime};
 
 use teh_o_prke::decay_heat::DecayHeat;
+use teh_o_prke::reactivity::Reactivity;
 use teh_o_prke::feedback_mechanisms::fission_product_poisons::Xenon135Poisoning;
+use teh_o_prke::zero_power_prke::reactor_power::ReactorPowerModel;
 use teh_o_prke::zero_power_prke::six_group_precursor_prke::six_group_constants::FissioningNuclideType;
 use teh_o_prke::{feedback_mechanisms::SixFactorFormulaFeedback, zero_power_prke::six_group_precursor_prke::SixGroupPRKE};
 use uom::si::area::square_meter;
-use uom::si::energy::{kilojoule, megaelectronvolt};
+use uom::si::energy::kilojoule;
 use uom::si::heat_transfer::watt_per_square_meter_kelvin;
 use uom::si::linear_number_density::per_meter;
 use uom::si::mass::kilogram;
 use uom::si::power::megawatt;
 use uom::si::time::{microsecond, second};
-use uom::si::velocity::meter_per_second;
 use uom::si::volume::cubic_meter;
+use uom::si::volumetric_number_density::per_cubic_meter;
 use uom::si::volumetric_number_rate::per_cubic_meter_second;
 use uom::si::{f64::*, ratio::ratio};
 use uom::si::thermodynamic_temperature::degree_celsius;
@@ -31,12 +33,23 @@ impl FHRSimulatorApp {
 
         // construct a prke six group object
         // probably want to use a u235 group or u233 group
-        // default
-        let mut prke_six_group :SixGroupPRKE = SixGroupPRKE::default();
+        //
+        // start at steady state, so that precursors do not 
+        // have to build up before the plant is usable
+        let neutron_generation_time = Time::new::<second>(2.31e-4);
+        let starting_neutron_population_density = 
+            VolumetricNumberDensity::new::<per_cubic_meter>(1.0);
+        let mut prke_six_group :SixGroupPRKE = SixGroupPRKE::new_at_equilibrium(
+            FissioningNuclideType::U235, 
+            starting_neutron_population_density, 
+            neutron_generation_time);
 
         let prke_timestep = Time::new::<microsecond>(25.0);
-        let reactor_volume = Volume::new::<cubic_meter>(0.5);
-        let macroscopic_fission_xs = LinearNumberDensity::new::<per_meter>(1.0);
+        // P = n v Sigma_f V E_f, with thermal neutrons at 2200 m/s 
+        // and 200 MeV per fission
+        let reactor_power_model = ReactorPowerModel::new_thermal_reactor(
+            LinearNumberDensity::new::<per_meter>(1.0), 
+            Volume::new::<cubic_meter>(0.5)).unwrap();
         let mut pebble_bed_th_struct = 
             PebbleBedThermalHydraulics::new();
         let fhr_state_clone = fhr_state.clone();
@@ -92,8 +105,7 @@ impl FHRSimulatorApp {
                 &mut keff_six_factor,
                 &mut prke_six_group,
                 prke_timestep,
-                reactor_volume,
-                macroscopic_fission_xs,
+                &reactor_power_model,
                 &mut fhr_decay_heat_struct,
                 &mut pebble_bed_th_struct,
                 &mut fhr_xe135_poisoning,
@@ -189,8 +201,7 @@ impl FHRSimulatorApp {
         keff_six_factor: &mut SixFactorFormulaFeedback,
         prke_six_group: &mut SixGroupPRKE,
         prke_timestep: Time,
-        reactor_volume: Volume,
-        macroscopic_fission_xs: LinearNumberDensity,
+        reactor_power_model: &ReactorPowerModel,
         fhr_decay_heat: &mut DecayHeat,
         pebble_bed_th_struct: &mut PebbleBedThermalHydraulics,
         fhr_xe135_poisoning: &mut Xenon135Poisoning,
@@ -284,20 +295,12 @@ impl FHRSimulatorApp {
 
         // we can get a power production 
         // and some of it should go to decay heat
-        // so we need Sigma_f * phi 
-        let neutron_speed: Velocity = Velocity::new::<meter_per_second>(2200.0);
-        let current_neutron_flux: ArealNumberRate = 
-            (current_neutron_pop_density * neutron_speed).into();
 
         // then fission rate
         // should be a number Rate
         // per unit vol
         let fission_rate_density: VolumetricNumberRate = 
-            (current_neutron_flux * macroscopic_fission_xs).into();
-
-        // should be a frequency
-        let fission_rate: Frequency  = 
-            fission_rate_density * reactor_volume;
+            reactor_power_model.get_fission_rate_density(current_neutron_pop_density);
 
         // note: it's convenient here to calc xe135 feedback 
 
@@ -309,12 +312,9 @@ impl FHRSimulatorApp {
                 fissioning_nuclide, 
                 current_neutron_pop_density);
 
-        let power_per_fission = 
-            Energy::new::<megaelectronvolt>(200.0);
-
         // immediate power from fission
         let fission_power_instantaneous: Power = 
-            power_per_fission * fission_rate;
+            reactor_power_model.get_power(current_neutron_pop_density);
 
         // add to decay heat precursors 
         fhr_decay_heat.add_decay_heat_precursor1(
@@ -387,17 +387,17 @@ impl FHRSimulatorApp {
         // reactivity in dollars 
         let beta_delayed_frac_total = prke_six_group.get_total_delayed_fraction();
         let reactivity_dollars: f64 
-            = (reactivity/beta_delayed_frac_total).get::<ratio>();
+            = Reactivity::new(reactivity).get_dollars(beta_delayed_frac_total);
 
         fhr_state_ref.reactivity_dollars = reactivity_dollars;
 
-        let xenon135_feedback_dollars_approx = 
-            (thermal_utilisation_feedback_fractional_chg_from_xenon-1.0)/
-            thermal_utilisation_feedback_fractional_chg_from_xenon/
-            beta_delayed_frac_total;
+        let xenon135_feedback_dollars_approx: f64 = 
+            Xenon135Poisoning::simplified_poison_reactivity_feedback(
+                xe135_mass_conc
+            ).get_dollars(beta_delayed_frac_total);
 
         fhr_state_ref.xenon135_feedback_dollars = 
-            xenon135_feedback_dollars_approx.get::<ratio>();
+            xenon135_feedback_dollars_approx;
 
 
         let debug_settings = false;
@@ -13,7 +13,8 @@ use crate::teh_o_prke_error::TehOPrkeError;
 /// of course x is necessarily less than or equal H
 pub fn obtain_rod_worth_cylinder(cylinder_height: Length,
     insertion_length: Length,
-    rod_worth: Ratio) -> Result<Ratio, TehOPrkeError> {
+    rod_worth: impl Into<Ratio>) -> Result<Ratio, TehOPrkeError> {
+    let rod_worth: Ratio = rod_worth.into();
 
     let mut x_by_h: Ratio = insertion_length/cylinder_height;
 
@@ -95,10 +95,7 @@ impl DecayHeat {
 
         self.decay_heat_precursor1 = e_decay_t_plus_delta_t;
         
-        return (e_decay_t_plus_delta_t - e_decay_t)/timestep;
-
-        
-
+        (e_decay_t_plus_delta_t - e_decay_t)/timestep
     }
     /// basically 
     ///
@@ -120,10 +117,7 @@ impl DecayHeat {
 
         self.decay_heat_precursor2 = e_decay_t_plus_delta_t;
 
-        return (e_decay_t_plus_delta_t - e_decay_t)/timestep;
-
-        
-
+        (e_decay_t_plus_delta_t - e_decay_t)/timestep
     }
     /// basically 
     ///
@@ -145,10 +139,7 @@ impl DecayHeat {
 
         self.decay_heat_precursor3 = e_decay_t_plus_delta_t;
 
-        return (e_decay_t_plus_delta_t - e_decay_t)/timestep;
-
-        
-
+        (e_decay_t_plus_delta_t - e_decay_t)/timestep
     }
     /// basically 
     ///
@@ -170,10 +161,7 @@ impl DecayHeat {
 
         self.decay_heat_precursor4 = e_decay_t_plus_delta_t;
 
-        return (e_decay_t_plus_delta_t - e_decay_t)/timestep;
-
-        
-
+        (e_decay_t_plus_delta_t - e_decay_t)/timestep
     }
     /// basically 
     ///
@@ -195,10 +183,7 @@ impl DecayHeat {
 
         self.decay_heat_precursor5 = e_decay_t_plus_delta_t;
 
-        return (e_decay_t_plus_delta_t - e_decay_t)/timestep;
-
-        
-
+        (e_decay_t_plus_delta_t - e_decay_t)/timestep
     }
     /// basically 
     ///
@@ -220,10 +205,7 @@ impl DecayHeat {
 
         self.decay_heat_precursor6 = e_decay_t_plus_delta_t;
 
-        return (e_decay_t_plus_delta_t - e_decay_t)/timestep;
-
-        
-
+        (e_decay_t_plus_delta_t - e_decay_t)/timestep
     }
     /// basically 
     ///
@@ -245,10 +227,7 @@ impl DecayHeat {
 
         self.decay_heat_precursor7 = e_decay_t_plus_delta_t;
 
-        return (e_decay_t_plus_delta_t - e_decay_t)/timestep;
-
-        
-
+        (e_decay_t_plus_delta_t - e_decay_t)/timestep
     }
 
 }
@@ -8,6 +8,8 @@ use uom::si::volumetric_number_density::per_cubic_centimeter;
 use uom::ConstZero;
 use uom::si::{f64::*, ratio::ratio};
 
+use crate::reactivity::Reactivity;
+use crate::teh_o_prke_error::TehOPrkeError;
 use crate::zero_power_prke::six_group_precursor_prke::six_group_constants::FissioningNuclideType;
 
 
@@ -82,6 +84,46 @@ impl Xenon135Poisoning {
     }
 
 
+    /// returns the iodine 135 yield per thermal fission
+    /// for the fissioning nuclide
+    ///
+    /// Lamarsh table 7.5 only has U233, U235 and Pu239, so the
+    /// other nuclides give an error rather than borrowing the
+    /// U235 yield
+    #[inline]
+    pub fn get_iodine_135_yield(fissioning_nuclide: FissioningNuclideType) 
+        -> Result<Ratio, TehOPrkeError> {
+        match fissioning_nuclide {
+            FissioningNuclideType::U233 => Ok(Self::fp_yield_iodine_135_from_u233_thermal_fission()),
+            FissioningNuclideType::U235 => Ok(Self::fp_yield_iodine_135_from_u235_thermal_fission()),
+            FissioningNuclideType::Pu239 => Ok(Self::fp_yield_iodine_135_from_pu239_thermal_fission()),
+            FissioningNuclideType::U238 
+                | FissioningNuclideType::Pu241 
+                | FissioningNuclideType::Th232 => Err(TehOPrkeError::GenericStringError(
+                    format!("no iodine 135 fission yield for {:?}", fissioning_nuclide)
+                )),
+        }
+    }
+
+    /// returns the (direct) xenon 135 yield per thermal fission
+    /// for the fissioning nuclide
+    ///
+    /// as with iodine 135, only U233, U235 and Pu239 have data
+    #[inline]
+    pub fn get_xe_135_yield(fissioning_nuclide: FissioningNuclideType) 
+        -> Result<Ratio, TehOPrkeError> {
+        match fissioning_nuclide {
+            FissioningNuclideType::U233 => Ok(Self::fp_yield_xe_135_from_u233_thermal_fission()),
+            FissioningNuclideType::U235 => Ok(Self::fp_yield_xe_135_from_u235_thermal_fission()),
+            FissioningNuclideType::Pu239 => Ok(Self::fp_yield_xe_135_from_pu239_thermal_fission()),
+            FissioningNuclideType::U238 
+                | FissioningNuclideType::Pu241 
+                | FissioningNuclideType::Th232 => Err(TehOPrkeError::GenericStringError(
+                    format!("no xenon 135 fission yield for {:?}", fissioning_nuclide)
+                )),
+        }
+    }
+
     /// (dI/dt) = gamma_I * fission rate -  lambda_I * I
     ///
     /// (I ^(t + delta t) - I^t)/ (delta t) = gamma_I * fission_rate - lambda_I I^(t + delta t)
@@ -92,13 +134,9 @@ impl Xenon135Poisoning {
     fn calc_iodine_135_and_return_conc(&mut self,
         timestep: Time,
         fission_rate: VolumetricNumberRate,
-        fissioning_nuclide: FissioningNuclideType) -> VolumetricNumberDensity {
+        fissioning_nuclide: FissioningNuclideType) -> Result<VolumetricNumberDensity, TehOPrkeError> {
 
-        let gamma_i = match fissioning_nuclide {
-            FissioningNuclideType::U233 => Self::fp_yield_iodine_135_from_u233_thermal_fission(),
-            FissioningNuclideType::U235 => Self::fp_yield_iodine_135_from_u235_thermal_fission(),
-            FissioningNuclideType::Pu239 => Self::fp_yield_iodine_135_from_pu239_thermal_fission(),
-        };
+        let gamma_i = Self::get_iodine_135_yield(fissioning_nuclide)?;
 
         let current_iodine_conc = self.iodine_135_num_density;
         let additional_iodine_conc: VolumetricNumberDensity 
@@ -113,7 +151,7 @@ impl Xenon135Poisoning {
 
         self.iodine_135_num_density = new_iodine_conc;
 
-        return new_iodine_conc;
+        Ok(new_iodine_conc)
 
     }
 
@@ -144,6 +182,12 @@ impl Xenon135Poisoning {
     /// delta t * gamma_X * fission rate 
     /// + delta t * lambda_I * I 
     /// + X^t
+    ///
+    /// # Panics
+    ///
+    /// if there is no iodine 135 or xenon 135 yield for the 
+    /// fissioning nuclide (see get_xe_135_yield), use 
+    /// try_calc_xe_135_and_return_num_density to get an error instead
     #[inline]
     pub fn calc_xe_135_and_return_num_density(
         &mut self,
@@ -153,6 +197,32 @@ impl Xenon135Poisoning {
         thermal_neutron_conc: VolumetricNumberDensity,
         ) -> VolumetricNumberDensity {
 
+        self.try_calc_xe_135_and_return_num_density(
+            timestep, 
+            fission_rate, 
+            fissioning_nuclide, 
+            thermal_neutron_conc)
+            .unwrap_or_else(|error| panic!("{:?}", error))
+    }
+
+    /// same as calc_xe_135_and_return_num_density, but returns an
+    /// error if there is no iodine 135 or xenon 135 yield for the 
+    /// fissioning nuclide
+    ///
+    /// the yields are checked before anything is updated, so the
+    /// iodine and xenon concentrations are unchanged on error
+    #[inline]
+    pub fn try_calc_xe_135_and_return_num_density(
+        &mut self,
+        timestep: Time,
+        fission_rate: VolumetricNumberRate,
+        fissioning_nuclide: FissioningNuclideType,
+        thermal_neutron_conc: VolumetricNumberDensity,
+        ) -> Result<VolumetricNumberDensity, TehOPrkeError> {
+
+        Self::get_iodine_135_yield(fissioning_nuclide)?;
+        let gamma_x = Self::get_xe_135_yield(fissioning_nuclide)?;
+
         let lambda_i = Self::iodine_135_decay_const();
         let lambda_x = Self::xe_135_decay_const();
 
@@ -160,17 +230,11 @@ impl Xenon135Poisoning {
             timestep, 
             fission_rate, 
             fissioning_nuclide
-        );
+        )?;
 
         let xe135_addition_rate_from_iodine: VolumetricNumberRate = 
             (iodine_conc * lambda_i).into();
 
-        let gamma_x = match fissioning_nuclide {
-            FissioningNuclideType::U233 => Self::fp_yield_xe_135_from_u233_thermal_fission(),
-            FissioningNuclideType::U235 => Self::fp_yield_xe_135_from_u235_thermal_fission(),
-            FissioningNuclideType::Pu239 => Self::fp_yield_xe_135_from_pu239_thermal_fission(),
-        };
-
         let xe135_addition_rate_from_fission: VolumetricNumberRate = 
             (gamma_x * fission_rate).into();
 
@@ -196,7 +260,7 @@ impl Xenon135Poisoning {
 
         self.xenon_135_num_density = xe_conc_next_timestep;
 
-        return xe_conc_next_timestep;
+        Ok(xe_conc_next_timestep)
     }
 
     /// calculates a feedback based on poison concentration
@@ -268,7 +332,29 @@ impl Xenon135Poisoning {
             (u235_macro_abs_xs + u238_macro_abs_xs)/
             (u235_macro_abs_xs + u238_macro_abs_xs + xe135_macro_xs);
 
-        return change_in_thermal_utilisation_factor;
+        change_in_thermal_utilisation_factor
+    }
+
+    /// reactivity from the poison concentration, using the same 
+    /// thermal utilisation heuristic as 
+    /// simplified_poison_concentration_feedback
+    ///
+    /// with k_new = (f_new/f_old) k_old and k_old close to 1,
+    ///
+    /// delta_rho = 1/k_old - 1/k_new ≈ (f_new/f_old - 1)/(f_new/f_old)
+    #[inline]
+    pub fn simplified_poison_reactivity_feedback(
+        poison_conc: MassConcentration
+    ) -> Reactivity {
+
+        let thermal_utilisation_ratio: Ratio = 
+            Self::simplified_poison_concentration_feedback(poison_conc);
+
+        let delta_rho: Ratio = 
+            (thermal_utilisation_ratio - Ratio::new::<ratio>(1.0))
+            /thermal_utilisation_ratio.get::<ratio>();
+
+        Reactivity::new(delta_rho)
     }
 
     #[inline]
@@ -323,9 +409,71 @@ impl Xenon135Poisoning {
 impl Default for Xenon135Poisoning {
     /// returns a fresh core
     fn default() -> Self {
-        return Self {
+        Self {
             iodine_135_num_density: VolumetricNumberDensity::ZERO, 
             xenon_135_num_density: VolumetricNumberDensity::ZERO, 
         }
     }
 }
+
+
+#[test]
+pub fn xenon_135_yield_is_checked_before_update(){
+
+    use uom::si::time::hour;
+    use uom::si::volumetric_number_density::per_cubic_meter;
+    use uom::si::volumetric_number_rate::per_cubic_meter_second;
+
+    let timestep = Time::new::<hour>(1.0);
+    let fission_rate = VolumetricNumberRate::new::<per_cubic_meter_second>(1.0e18);
+    let thermal_neutron_conc = VolumetricNumberDensity::new::<per_cubic_meter>(1.0e12);
+
+    let mut xenon_poisoning = Xenon135Poisoning::default();
+    let mut xenon_poisoning_reference = Xenon135Poisoning::default();
+
+    // no yields for u238, nothing is updated
+    assert!(xenon_poisoning.try_calc_xe_135_and_return_num_density(
+        timestep, fission_rate, FissioningNuclideType::U238, thermal_neutron_conc).is_err());
+    assert_eq!(xenon_poisoning.iodine_135_num_density, VolumetricNumberDensity::ZERO);
+    assert_eq!(xenon_poisoning.xenon_135_num_density, VolumetricNumberDensity::ZERO);
+
+    // u235 works the same either way
+    let xenon_conc = xenon_poisoning.try_calc_xe_135_and_return_num_density(
+        timestep, fission_rate, FissioningNuclideType::U235, thermal_neutron_conc).unwrap();
+    let xenon_conc_reference = xenon_poisoning_reference.calc_xe_135_and_return_num_density(
+        timestep, fission_rate, FissioningNuclideType::U235, thermal_neutron_conc);
+
+    assert!(xenon_conc.get::<per_cubic_meter>() > 0.0);
+    assert_eq!(xenon_conc, xenon_conc_reference);
+}
+
+#[test]
+pub fn xenon_135_reactivity_feedback_is_negative_and_zero_when_clean(){
+
+    use approx::assert_abs_diff_eq;
+    use uom::si::volumetric_number_density::per_cubic_meter;
+
+    let clean_core = Xenon135Poisoning::default();
+    let clean_reactivity = Xenon135Poisoning::simplified_poison_reactivity_feedback(
+        clean_core.get_current_xe135_conc());
+
+    assert_abs_diff_eq!(clean_reactivity.get_delta_k_over_k(), 0.0, epsilon = 1e-15);
+
+    // equilibrium xenon ballpark
+    let poisoned_core = Xenon135Poisoning {
+        iodine_135_num_density: VolumetricNumberDensity::ZERO,
+        xenon_135_num_density: VolumetricNumberDensity::new::<per_cubic_meter>(3.0e21),
+    };
+    let poisoned_conc = poisoned_core.get_current_xe135_conc();
+    let thermal_utilisation_ratio = 
+        Xenon135Poisoning::simplified_poison_concentration_feedback(poisoned_conc)
+        .get::<ratio>();
+    let poisoned_reactivity = 
+        Xenon135Poisoning::simplified_poison_reactivity_feedback(poisoned_conc);
+
+    assert!(poisoned_reactivity.get_delta_k_over_k() < 0.0);
+    assert_abs_diff_eq!(
+        poisoned_reactivity.get_delta_k_over_k(),
+        1.0 - 1.0/thermal_utilisation_ratio,
+        epsilon = 1e-15);
+}
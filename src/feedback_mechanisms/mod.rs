@@ -42,13 +42,12 @@ impl SixFactorFormulaFeedback {
     /// stored data of the six factor formula
     #[inline]
     pub fn calc_keff(&self) -> Ratio {
-        return 
-            self.epsilon 
+        self.epsilon 
             * self.p_fnl 
             * self.p 
             * self.p_tnl
             * self.f
-            * self.eta;
+            * self.eta
     }
 
     /// calculates reactivity given the six factor 
@@ -68,7 +67,7 @@ impl SixFactorFormulaFeedback {
         }
 
 
-        return (keff-Ratio::new::<ratio>(1.0))/keff;
+        (keff-Ratio::new::<ratio>(1.0))/keff
     }
 
 
@@ -7,6 +7,7 @@ use uom::si::mass_density::gram_per_cubic_centimeter;
 use uom::si::ratio::ratio;
 use uom::si::specific_heat_capacity::joule_per_kilogram_kelvin;
 use uom::si::thermodynamic_temperature::kelvin;
+use crate::reactivity::Reactivity;
 use crate::teh_o_prke_error::TehOPrkeError;
 
 /// a struct for calculating fuel temperature feedback
@@ -133,7 +134,7 @@ impl SimpleFuelTemperatureFeedback {
     /// obtain reactivity change compared to reference temperature 
     /// usually 300K
     pub fn obtain_fuel_temperature_delta_rho(&self,
-        reference_temperature: ThermodynamicTemperature,) -> Result<Ratio, TehOPrkeError>{
+        reference_temperature: ThermodynamicTemperature,) -> Result<Reactivity, TehOPrkeError>{
 
         let alpha_coefficient = self.alpha_coefficient;
         let temperature = self.fuel_temperature;
@@ -243,13 +244,13 @@ pub fn obtain_fuel_temperature_feedback_coeff_thermal_spectrum(
 pub fn obtain_fuel_temperature_reactivity_feedback_thermal_spectrum(
     alpha_coefficient: Ratio,
     temperature: ThermodynamicTemperature,
-    reference_temperature: ThermodynamicTemperature) -> Result<Ratio, TehOPrkeError> {
+    reference_temperature: ThermodynamicTemperature) -> Result<Reactivity, TehOPrkeError> {
 
     let delta_rho = -2.0 * alpha_coefficient *
         (temperature.get::<kelvin>().sqrt()
          - reference_temperature.get::<kelvin>().sqrt());
 
-    Ok(delta_rho)
+    Ok(Reactivity::new(delta_rho))
 
 }
 
@@ -281,13 +282,13 @@ pub fn fuel_temperature_feedback_test(){
 
     // at 300K reference, we should get no reactivity feedback 
 
-    let reactivity_at_reference: Ratio = 
+    let reactivity_at_reference: Reactivity = 
         fuel_temperature_feedback_struct.obtain_fuel_temperature_delta_rho(
             ThermodynamicTemperature::new::<kelvin>(300.0)
             ).unwrap();
 
     assert_abs_diff_eq!(
-        reactivity_at_reference.get::<ratio>(),
+        reactivity_at_reference.get_delta_k_over_k(),
         0.0,
         epsilon = 1e-11);
 
@@ -296,14 +297,14 @@ pub fn fuel_temperature_feedback_test(){
     fuel_temperature_feedback_struct
         .set_fuel_temperature(fuel_temperature_test_1).unwrap();
 
-    let reactivity_test_1: Ratio = 
+    let reactivity_test_1: Reactivity = 
         fuel_temperature_feedback_struct.obtain_fuel_temperature_delta_rho(
             ThermodynamicTemperature::new::<kelvin>(300.0)
             ).unwrap();
 
     // should be -0.007174389
     assert_abs_diff_eq!(
-        reactivity_test_1.get::<ratio>(),
+        reactivity_test_1.get_delta_k_over_k(),
         -0.007174389,
         epsilon = 1e-5);
 
@@ -312,14 +313,14 @@ pub fn fuel_temperature_feedback_test(){
     fuel_temperature_feedback_struct
         .set_fuel_temperature(fuel_temperature_test_2).unwrap();
 
-    let reactivity_test_2: Ratio = 
+    let reactivity_test_2: Reactivity = 
         fuel_temperature_feedback_struct.obtain_fuel_temperature_delta_rho(
             ThermodynamicTemperature::new::<kelvin>(300.0)
             ).unwrap();
 
     // should be -0.01267949
     assert_abs_diff_eq!(
-        reactivity_test_2.get::<ratio>(),
+        reactivity_test_2.get_delta_k_over_k(),
         -0.01267949,
         epsilon = 1e-5);
 }
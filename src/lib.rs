@@ -5,6 +5,9 @@
 /// the SixGroupPRKE struct contains the code which performs solution of 
 /// the PRKE matrix with six precursor groups
 ///
+/// it is a specialisation of MultiGroupPRKE, which can take any 
+/// number of precursor groups (eg. 1, 6 or 8 groups)
+///
 /// but you must supply reactivity (or keff equivalently) as an input.
 ///
 /// for real-time calculations, only thermal reactors are okay 
@@ -27,6 +30,23 @@ pub mod fuel_temperature_feedback;
 /// contains functions and structs for control rod feedback 
 pub mod control_rod_feedback;
 
+/// contains subcritical multiplication and inverse count rate (1/M)
+/// tools for source driven subcritical cores and approach to
+/// criticality
+pub mod subcritical_multiplication;
+
+/// contains the Reactivity type, which converts between delta k/k,
+/// dollars, cents, pcm and keff
+pub mod reactivity;
+
+/// contains time dependent reactivity insertion profiles,
+/// ie. steps, ramps, sinusoids, tables and sums of these
+pub mod reactivity_profile;
+
+/// contains linearised stability analysis of the kinetics coupled
+/// with fuel temperature and xenon feedback
+pub mod stability_analysis;
+
 /// error type for the crate 
 pub mod teh_o_prke_error;
 
@@ -46,3 +66,7 @@ pub mod decay_heat;
 /// some algorithms copied from OpenFOAM
 ///
 pub mod time_stepping;
+
+/// contains LU decomposition and linear solvers used by the 
+/// implicit time stepping schemes
+pub mod linear_algebra;
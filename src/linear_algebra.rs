@@ -0,0 +1,700 @@
+use ndarray::Array2;
+use num_complex::Complex;
+
+use crate::teh_o_prke_error::TehOPrkeError;
+
+/// LU decomposition with partial pivoting, done in place
+///
+/// this follows LUDecompose in OpenFOAM, which the Rosenbrock and
+/// Rodas solvers use so that one decomposition can be reused for
+/// every stage of a timestep
+///
+/// after decomposition, the matrix holds L (below the diagonal,
+/// unit diagonal not stored) and U (on and above the diagonal).
+/// The returned pivot indices record which row was swapped
+/// with row k at step k
+pub fn lu_decompose(matrix: &mut Array2<f64>) -> Result<Vec<usize>, TehOPrkeError> {
+
+    let mut pivot_indices: Vec<usize> = vec![0; matrix.nrows()];
+
+    lu_decompose_into(matrix, &mut pivot_indices)?;
+
+    Ok(pivot_indices)
+}
+
+/// LU decomposition as in lu_decompose, but the pivot indices are
+/// written into the buffer given, so nothing is allocated
+///
+/// the buffer must be as long as the matrix has rows
+pub fn lu_decompose_into(matrix: &mut Array2<f64>,
+    pivot_indices: &mut [usize]) -> Result<(), TehOPrkeError> {
+
+    let n = matrix.nrows();
+
+    if matrix.ncols() != n {
+        return Err(TehOPrkeError::GenericStringError(
+            "LU decomposition needs a square matrix".to_string()
+        ));
+    }
+
+    if pivot_indices.len() != n {
+        return Err(TehOPrkeError::GenericStringError(
+            "LU decomposition needs one pivot index per row".to_string()
+        ));
+    }
+
+    for k in 0..n {
+
+        // find the largest entry in column k at or below the diagonal
+        let mut pivot_row = k;
+        let mut largest_entry = matrix[[k, k]].abs();
+
+        for i in (k+1)..n {
+            if matrix[[i, k]].abs() > largest_entry {
+                largest_entry = matrix[[i, k]].abs();
+                pivot_row = i;
+            }
+        }
+
+        if largest_entry == 0.0 || largest_entry.is_nan() {
+            return Err(TehOPrkeError::GenericStringError(
+                "matrix is singular, LU decomposition failed".to_string()
+            ));
+        }
+
+        pivot_indices[k] = pivot_row;
+
+        if pivot_row != k {
+            for j in 0..n {
+                matrix.swap([k, j], [pivot_row, j]);
+            }
+        }
+
+        let diagonal_entry = matrix[[k, k]];
+
+        for i in (k+1)..n {
+            matrix[[i, k]] /= diagonal_entry;
+            let multiplier = matrix[[i, k]];
+
+            if multiplier != 0.0 {
+                for j in (k+1)..n {
+                    matrix[[i, j]] -= multiplier * matrix[[k, j]];
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// solves LU x = b given the output of lu_decompose
+///
+/// the right hand side is overwritten with the solution,
+/// as in LUBacksubstitute in OpenFOAM
+pub fn lu_back_substitute(lu_matrix: &Array2<f64>,
+    pivot_indices: &[usize],
+    rhs: &mut [f64]) {
+
+    let n = lu_matrix.nrows();
+
+    // apply the row swaps in the order they were made
+    for (k, &pivot_row) in pivot_indices.iter().enumerate() {
+        rhs.swap(k, pivot_row);
+    }
+
+    // forward substitution, L has a unit diagonal
+    for i in 0..n {
+        let mut sum = rhs[i];
+        for j in 0..i {
+            sum -= lu_matrix[[i, j]] * rhs[j];
+        }
+        rhs[i] = sum;
+    }
+
+    // back substitution
+    for i in (0..n).rev() {
+        let mut sum = rhs[i];
+        for j in (i+1)..n {
+            sum -= lu_matrix[[i, j]] * rhs[j];
+        }
+        rhs[i] = sum/lu_matrix[[i, i]];
+    }
+}
+
+/// solves A x = b using LU decomposition with partial pivoting
+pub fn solve_linear_system(matrix: &Array2<f64>,
+    rhs: &[f64]) -> Result<Vec<f64>, TehOPrkeError> {
+
+    let mut lu_matrix = matrix.clone();
+    let pivot_indices = lu_decompose(&mut lu_matrix)?;
+
+    let mut solution = rhs.to_vec();
+    lu_back_substitute(&lu_matrix, &pivot_indices, &mut solution);
+
+    Ok(solution)
+}
+
+
+/// matrix exponential exp(A) by Pade approximation with scaling
+/// and squaring
+///
+/// A is scaled by 2^-s so that its infinity norm is at most 1/2,
+/// the (6,6) Pade approximant is used for exp(A/2^s), and the
+/// result is squared s times. This is algorithm 11.3.1 in
+/// Golub and Van Loan, Matrix Computations
+///
+/// this works for stiff matrices, eg. the PRKE with large negative
+/// prompt eigenvalues, as it does not need the eigenvectors
+pub fn matrix_exponential(matrix: &Array2<f64>) -> Result<Array2<f64>, TehOPrkeError> {
+
+    let n = matrix.nrows();
+
+    if matrix.ncols() != n {
+        return Err(TehOPrkeError::GenericStringError(
+            "matrix exponential needs a square matrix".to_string()
+        ));
+    }
+
+    let infinity_norm: f64 = matrix.rows().into_iter()
+        .map(|row| row.iter().map(|entry| entry.abs()).sum::<f64>())
+        .fold(0.0, f64::max);
+
+    if !infinity_norm.is_finite() {
+        return Err(TehOPrkeError::GenericStringError(
+            "matrix exponential needs a finite matrix".to_string()
+        ));
+    }
+
+    // number of squarings
+    let number_of_squarings: i32 = if infinity_norm > 0.5 {
+        (infinity_norm.log2().floor() as i32 + 2).max(0)
+    } else {
+        0
+    };
+
+    let scaled_matrix: Array2<f64> = matrix / 2.0_f64.powi(number_of_squarings);
+
+    // Pade approximant, N/D
+    const PADE_DEGREE: usize = 6;
+    let identity: Array2<f64> = Array2::eye(n);
+
+    let mut c: f64 = 0.5;
+    let mut matrix_power: Array2<f64> = scaled_matrix.clone();
+    let mut numerator: Array2<f64> = &identity + &(c * &scaled_matrix);
+    let mut denominator: Array2<f64> = &identity - &(c * &scaled_matrix);
+    let mut positive_term = true;
+
+    for k in 2..=PADE_DEGREE {
+        c *= (PADE_DEGREE - k + 1) as f64
+            / (k * (2 * PADE_DEGREE - k + 1)) as f64;
+        matrix_power = scaled_matrix.dot(&matrix_power);
+
+        let c_matrix_power = c * &matrix_power;
+        numerator += &c_matrix_power;
+
+        if positive_term {
+            denominator += &c_matrix_power;
+        } else {
+            denominator -= &c_matrix_power;
+        }
+        positive_term = !positive_term;
+    }
+
+    // solve D E = N one column at a time
+    let pivot_indices = lu_decompose(&mut denominator)?;
+    let mut exponential: Array2<f64> = Array2::zeros((n, n));
+    let mut column: Vec<f64> = vec![0.0; n];
+
+    for j in 0..n {
+        for i in 0..n {
+            column[i] = numerator[[i, j]];
+        }
+        lu_back_substitute(&denominator, &pivot_indices, &mut column);
+        for i in 0..n {
+            exponential[[i, j]] = column[i];
+        }
+    }
+
+    // undo the scaling
+    for _ in 0..number_of_squarings {
+        exponential = exponential.dot(&exponential);
+    }
+
+    Ok(exponential)
+}
+
+/// eigenvalues of a real square matrix, which may be complex
+///
+/// the matrix is balanced, reduced to upper Hessenberg form by
+/// elimination, then the Francis double shift QR algorithm finds
+/// the eigenvalues. This follows balanc, elmhes and hqr in
+/// Numerical Recipes (Press et al.), chapter 11
+///
+/// complex eigenvalues come in conjugate pairs. Eigenvectors
+/// are not computed
+pub fn eigenvalues(matrix: &Array2<f64>) -> Result<Vec<Complex<f64>>, TehOPrkeError> {
+
+    let n = matrix.nrows();
+
+    if matrix.ncols() != n {
+        return Err(TehOPrkeError::GenericStringError(
+            "eigenvalues need a square matrix".to_string()
+        ));
+    }
+
+    if matrix.iter().any(|entry| !entry.is_finite()) {
+        return Err(TehOPrkeError::GenericStringError(
+            "matrix has non finite entries".to_string()
+        ));
+    }
+
+    let mut a = matrix.clone();
+
+    balance_matrix(&mut a);
+    reduce_to_upper_hessenberg(&mut a);
+
+    hessenberg_qr_eigenvalues(&mut a)
+}
+
+/// scales rows and columns by powers of 2 so that their norms
+/// are similar, this does not change the eigenvalues but makes
+/// them less sensitive to round off
+fn balance_matrix(a: &mut Array2<f64>) {
+
+    let n = a.nrows();
+    let radix: f64 = 2.0;
+    let radix_squared = radix * radix;
+    let mut is_balanced = false;
+
+    while !is_balanced {
+        is_balanced = true;
+
+        for i in 0..n {
+            let mut column_norm = 0.0;
+            let mut row_norm = 0.0;
+
+            for j in 0..n {
+                if j != i {
+                    column_norm += a[[j, i]].abs();
+                    row_norm += a[[i, j]].abs();
+                }
+            }
+
+            if column_norm != 0.0 && row_norm != 0.0 {
+                let mut g = row_norm/radix;
+                let mut f = 1.0;
+                let s = column_norm + row_norm;
+
+                while column_norm < g {
+                    f *= radix;
+                    column_norm *= radix_squared;
+                }
+
+                g = row_norm * radix;
+
+                while column_norm > g {
+                    f /= radix;
+                    column_norm /= radix_squared;
+                }
+
+                if (column_norm + row_norm)/f < 0.95 * s {
+                    is_balanced = false;
+                    g = 1.0/f;
+
+                    for j in 0..n {
+                        a[[i, j]] *= g;
+                    }
+                    for j in 0..n {
+                        a[[j, i]] *= f;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// reduces the matrix to upper Hessenberg form by Gaussian
+/// elimination with pivoting, using similarity transforms
+fn reduce_to_upper_hessenberg(a: &mut Array2<f64>) {
+
+    let n = a.nrows();
+
+    for m in 1..n.saturating_sub(1) {
+        let mut x: f64 = 0.0;
+        let mut pivot_row = m;
+
+        for j in m..n {
+            if a[[j, m - 1]].abs() > x.abs() {
+                x = a[[j, m - 1]];
+                pivot_row = j;
+            }
+        }
+
+        if pivot_row != m {
+            for j in (m - 1)..n {
+                a.swap([pivot_row, j], [m, j]);
+            }
+            for j in 0..n {
+                a.swap([j, pivot_row], [j, m]);
+            }
+        }
+
+        if x != 0.0 {
+            for i in (m + 1)..n {
+                let mut y = a[[i, m - 1]];
+
+                if y != 0.0 {
+                    y /= x;
+                    a[[i, m - 1]] = y;
+
+                    for j in m..n {
+                        a[[i, j]] -= y * a[[m, j]];
+                    }
+                    for j in 0..n {
+                        a[[j, m]] += y * a[[j, i]];
+                    }
+                }
+            }
+        }
+    }
+
+    // the multipliers left below the subdiagonal are not part
+    // of the Hessenberg matrix
+    for i in 2..n {
+        for j in 0..(i - 1) {
+            a[[i, j]] = 0.0;
+        }
+    }
+}
+
+/// eigenvalues of an upper Hessenberg matrix by the shifted
+/// QR algorithm, the matrix is destroyed
+fn hessenberg_qr_eigenvalues(a: &mut Array2<f64>) -> Result<Vec<Complex<f64>>, TehOPrkeError> {
+
+    let n = a.nrows();
+    let mut eigenvalue_list = vec![Complex::new(0.0, 0.0); n];
+
+    let sign = |magnitude: f64, sign_of: f64| -> f64 {
+        if sign_of >= 0.0 { magnitude.abs() } else { -magnitude.abs() }
+    };
+
+    let mut matrix_norm = 0.0;
+    for i in 0..n {
+        for j in i.saturating_sub(1)..n {
+            matrix_norm += a[[i, j]].abs();
+        }
+    }
+
+    // eigenvalues are found from the bottom up, nn is one past
+    // the last row still being worked on
+    let mut nn = n;
+    // accumulated exceptional shifts
+    let mut t = 0.0;
+
+    while nn > 0 {
+        let last = nn - 1;
+        let mut iterations = 0;
+
+        loop {
+            // look for a small subdiagonal element to split the
+            // matrix at
+            let mut l = last;
+            while l >= 1 {
+                let mut s = a[[l - 1, l - 1]].abs() + a[[l, l]].abs();
+                if s == 0.0 {
+                    s = matrix_norm;
+                }
+                if a[[l, l - 1]].abs() + s == s {
+                    a[[l, l - 1]] = 0.0;
+                    break;
+                }
+                l -= 1;
+            }
+
+            let mut x = a[[last, last]];
+
+            // one root
+            if l == last {
+                eigenvalue_list[last] = Complex::new(x + t, 0.0);
+                nn -= 1;
+                break;
+            }
+
+            let mut y = a[[last - 1, last - 1]];
+            let mut w = a[[last, last - 1]] * a[[last - 1, last]];
+
+            // two roots, from the trailing 2 by 2 block
+            if l == last - 1 {
+                let p = 0.5 * (y - x);
+                let q = p * p + w;
+                let z = q.abs().sqrt();
+                x += t;
+
+                if q >= 0.0 {
+                    let z = p + sign(z, p);
+                    let mut smaller_root = x + z;
+                    if z != 0.0 {
+                        smaller_root = x - w/z;
+                    }
+                    eigenvalue_list[last - 1] = Complex::new(x + z, 0.0);
+                    eigenvalue_list[last] = Complex::new(smaller_root, 0.0);
+                } else {
+                    eigenvalue_list[last - 1] = Complex::new(x + p, -z);
+                    eigenvalue_list[last] = Complex::new(x + p, z);
+                }
+
+                nn -= 2;
+                break;
+            }
+
+            if iterations == 60 {
+                return Err(TehOPrkeError::GenericStringError(
+                    "QR algorithm did not converge for the eigenvalues".to_string()
+                ));
+            }
+
+            // exceptional shift, in case the iterations are stuck
+            if iterations == 10 || iterations == 20 {
+                t += x;
+                for i in 0..=last {
+                    a[[i, i]] -= x;
+                }
+                let s = a[[last, last - 1]].abs() + a[[last - 1, last - 2]].abs();
+                x = 0.75 * s;
+                y = x;
+                w = -0.4375 * s * s;
+            }
+            iterations += 1;
+
+            // form the double shift and look for two consecutive
+            // small subdiagonal elements
+            let mut m = last - 2;
+            let (mut p, mut q, mut r);
+            loop {
+                let z = a[[m, m]];
+                let r_shift = x - z;
+                let s_shift = y - z;
+                p = (r_shift * s_shift - w)/a[[m + 1, m]] + a[[m, m + 1]];
+                q = a[[m + 1, m + 1]] - z - r_shift - s_shift;
+                r = a[[m + 2, m + 1]];
+                let s = p.abs() + q.abs() + r.abs();
+                p /= s;
+                q /= s;
+                r /= s;
+
+                if m == l {
+                    break;
+                }
+
+                let u = a[[m, m - 1]].abs() * (q.abs() + r.abs());
+                let v = p.abs() * (a[[m - 1, m - 1]].abs() + z.abs() + a[[m + 1, m + 1]].abs());
+                if u + v == v {
+                    break;
+                }
+                m -= 1;
+            }
+
+            for i in (m + 2)..=last {
+                a[[i, i - 2]] = 0.0;
+                if i != m + 2 {
+                    a[[i, i - 3]] = 0.0;
+                }
+            }
+
+            // double QR step on rows l to last and columns m to last
+            let mut k = m;
+            while k < last {
+                if k != m {
+                    p = a[[k, k - 1]];
+                    q = a[[k + 1, k - 1]];
+                    r = 0.0;
+                    if k != last - 1 {
+                        r = a[[k + 2, k - 1]];
+                    }
+                    x = p.abs() + q.abs() + r.abs();
+                    if x != 0.0 {
+                        p /= x;
+                        q /= x;
+                        r /= x;
+                    }
+                }
+
+                let s = sign((p * p + q * q + r * r).sqrt(), p);
+
+                if s != 0.0 {
+                    if k == m {
+                        if l != m {
+                            a[[k, k - 1]] = -a[[k, k - 1]];
+                        }
+                    } else {
+                        a[[k, k - 1]] = -s * x;
+                    }
+
+                    p += s;
+                    x = p/s;
+                    y = q/s;
+                    let z = r/s;
+                    q /= p;
+                    r /= p;
+
+                    // row modification
+                    for j in k..=last {
+                        let mut p_row = a[[k, j]] + q * a[[k + 1, j]];
+                        if k != last - 1 {
+                            p_row += r * a[[k + 2, j]];
+                            a[[k + 2, j]] -= p_row * z;
+                        }
+                        a[[k + 1, j]] -= p_row * y;
+                        a[[k, j]] -= p_row * x;
+                    }
+
+                    // column modification
+                    let row_limit = if last < k + 3 { last } else { k + 3 };
+                    for i in l..=row_limit {
+                        let mut p_column = x * a[[i, k]] + y * a[[i, k + 1]];
+                        if k != last - 1 {
+                            p_column += z * a[[i, k + 2]];
+                            a[[i, k + 2]] -= p_column * r;
+                        }
+                        a[[i, k + 1]] -= p_column * q;
+                        a[[i, k]] -= p_column;
+                    }
+                }
+
+                k += 1;
+            }
+        }
+    }
+
+    Ok(eigenvalue_list)
+}
+
+#[test]
+pub fn lu_decomposition_solve_test(){
+
+    use approx::assert_relative_eq;
+    use ndarray::array;
+
+    // the first pivot is zero, so pivoting is needed
+    let matrix: Array2<f64> = array![
+        [0.0, 2.0, 1.0],
+        [1.0, -2.0, -3.0],
+        [-1.0, 1.0, 2.0],
+    ];
+
+    // x = [1, 2, 3]
+    let rhs = [7.0, -12.0, 7.0];
+
+    let solution = solve_linear_system(&matrix, &rhs).unwrap();
+
+    assert_relative_eq!(solution[0], 1.0, max_relative = 1e-12);
+    assert_relative_eq!(solution[1], 2.0, max_relative = 1e-12);
+    assert_relative_eq!(solution[2], 3.0, max_relative = 1e-12);
+
+    // reusing the decomposition for a second right hand side
+    let mut lu_matrix = matrix.clone();
+    let pivot_indices = lu_decompose(&mut lu_matrix).unwrap();
+
+    // x = [-1, 0, 1]
+    let mut rhs_2 = [1.0, -4.0, 3.0];
+    lu_back_substitute(&lu_matrix, &pivot_indices, &mut rhs_2);
+
+    assert_relative_eq!(rhs_2[0], -1.0, max_relative = 1e-12);
+    assert!(rhs_2[1].abs() < 1e-12);
+    assert_relative_eq!(rhs_2[2], 1.0, max_relative = 1e-12);
+
+    // singular matrices are refused
+    let singular_matrix: Array2<f64> = array![
+        [1.0, 2.0],
+        [2.0, 4.0],
+    ];
+    assert!(solve_linear_system(&singular_matrix, &[1.0, 2.0]).is_err());
+}
+
+#[test]
+pub fn matrix_exponential_test(){
+
+    use approx::assert_relative_eq;
+    use ndarray::array;
+
+    // exp of a rotation generator is a rotation,
+    // exp([[0, -t], [t, 0]]) = [[cos t, -sin t], [sin t, cos t]]
+    let angle: f64 = 2.5;
+    let rotation_generator: Array2<f64> = array![
+        [0.0, -angle],
+        [angle, 0.0],
+    ];
+
+    let rotation = matrix_exponential(&rotation_generator).unwrap();
+
+    assert_relative_eq!(rotation[[0, 0]], angle.cos(), epsilon = 1e-13);
+    assert_relative_eq!(rotation[[0, 1]], -angle.sin(), epsilon = 1e-13);
+    assert_relative_eq!(rotation[[1, 0]], angle.sin(), epsilon = 1e-13);
+    assert_relative_eq!(rotation[[1, 1]], angle.cos(), epsilon = 1e-13);
+
+    // stiff upper triangular matrix, eigenvalues -1000 and -1
+    // exp([[a, b], [0, d]]) has off diagonal b (e^a - e^d)/(a - d)
+    let (a, b, d) = (-1000.0_f64, 50.0_f64, -1.0_f64);
+    let stiff_matrix: Array2<f64> = array![
+        [a, b],
+        [0.0, d],
+    ];
+
+    let stiff_exponential = matrix_exponential(&stiff_matrix).unwrap();
+
+    assert!(stiff_exponential[[0, 0]].abs() < 1e-12);
+    assert_relative_eq!(stiff_exponential[[1, 1]], d.exp(), max_relative = 1e-12);
+    assert_relative_eq!(stiff_exponential[[0, 1]], 
+        b * (a.exp() - d.exp())/(a - d), max_relative = 1e-12);
+    assert_eq!(stiff_exponential[[1, 0]], 0.0);
+
+    // exp(0) is the identity
+    let identity = matrix_exponential(&Array2::zeros((3, 3))).unwrap();
+    assert_eq!(identity, Array2::<f64>::eye(3));
+}
+
+#[test]
+pub fn eigenvalues_test(){
+
+    use approx::assert_relative_eq;
+    use ndarray::array;
+
+    // companion matrix of (x - 1)(x - 2)(x^2 + 2x + 5)
+    // = x^4 - x^3 + x^2 - 11x + 10, roots 1, 2 and -1 +/- 2i
+    let companion_matrix: Array2<f64> = array![
+        [1.0, -1.0, 11.0, -10.0],
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+    ];
+
+    let mut eigenvalue_list = eigenvalues(&companion_matrix).unwrap();
+    eigenvalue_list.sort_by(|a, b| {
+        a.re.partial_cmp(&b.re).unwrap().then(a.im.partial_cmp(&b.im).unwrap())
+    });
+
+    assert_relative_eq!(eigenvalue_list[0].re, -1.0, max_relative = 1e-10);
+    assert_relative_eq!(eigenvalue_list[0].im, -2.0, max_relative = 1e-10);
+    assert_relative_eq!(eigenvalue_list[1].re, -1.0, max_relative = 1e-10);
+    assert_relative_eq!(eigenvalue_list[1].im, 2.0, max_relative = 1e-10);
+    assert_relative_eq!(eigenvalue_list[2].re, 1.0, max_relative = 1e-10);
+    assert_relative_eq!(eigenvalue_list[3].re, 2.0, max_relative = 1e-10);
+    assert_eq!(eigenvalue_list[2].im, 0.0);
+
+    // stiff triangular matrix, the eigenvalues are the diagonal
+    let stiff_matrix: Array2<f64> = array![
+        [-1.0e4, 3.0, 5.0],
+        [0.0, -1.0, 2.0],
+        [0.0, 0.0, -1.0e-5],
+    ];
+
+    let mut stiff_eigenvalue_list: Vec<f64> = eigenvalues(&stiff_matrix).unwrap()
+        .iter().map(|eigenvalue| eigenvalue.re).collect();
+    stiff_eigenvalue_list.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    assert_relative_eq!(stiff_eigenvalue_list[0], -1.0e4, max_relative = 1e-12);
+    assert_relative_eq!(stiff_eigenvalue_list[1], -1.0, max_relative = 1e-12);
+    assert_relative_eq!(stiff_eigenvalue_list[2], -1.0e-5, max_relative = 1e-10);
+
+    assert!(eigenvalues(&Array2::zeros((2, 3))).is_err());
+}
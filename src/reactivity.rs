@@ -0,0 +1,198 @@
+use std::iter::Sum;
+use std::ops::{Add, Mul, Neg, Sub};
+
+use uom::si::f64::*;
+use uom::si::ratio::ratio;
+
+use crate::teh_o_prke_error::TehOPrkeError;
+
+/// reactivity, rho = (keff - 1)/keff
+///
+/// stored as delta k/k, and can be constructed and read in dollars,
+/// cents, pcm or delta k/k, or converted to and from keff, so that
+/// the units are explicit wherever reactivity is passed around
+///
+/// dollars and cents need the effective delayed fraction, beta_eff,
+/// eg. from MultiGroupPRKE::get_total_delayed_fraction
+///
+/// Reactivity converts into a uom Ratio (and back), the solvers
+/// take either
+#[derive(Debug,Clone,Copy,PartialEq,PartialOrd,Default)]
+pub struct Reactivity {
+    delta_k_over_k: Ratio,
+}
+
+impl Reactivity {
+
+    /// constructs reactivity from a uom Ratio in delta k/k
+    pub fn new(delta_k_over_k: Ratio) -> Self {
+        Self { delta_k_over_k }
+    }
+
+    /// constructs reactivity from delta k/k, eg. 0.003
+    pub fn new_from_delta_k_over_k(delta_k_over_k: f64) -> Self {
+        Self::new(Ratio::new::<ratio>(delta_k_over_k))
+    }
+
+    /// constructs reactivity from pcm (per cent mille),
+    /// 1 pcm = 1e-5 delta k/k
+    pub fn new_from_pcm(pcm: f64) -> Self {
+        Self::new_from_delta_k_over_k(pcm * 1.0e-5)
+    }
+
+    /// constructs reactivity from dollars, rho = dollars * beta_eff
+    pub fn new_from_dollars(dollars: f64, beta_eff: Ratio) -> Self {
+        Self::new(dollars * beta_eff)
+    }
+
+    /// constructs reactivity from cents, 100 cents is one dollar
+    pub fn new_from_cents(cents: f64, beta_eff: Ratio) -> Self {
+        Self::new_from_dollars(cents/100.0, beta_eff)
+    }
+
+    /// constructs reactivity from keff, rho = (keff - 1)/keff
+    ///
+    /// keff must be positive
+    pub fn new_from_keff(keff: Ratio) -> Result<Self, TehOPrkeError> {
+
+        if keff.get::<ratio>() <= 0.0 {
+            return Err(TehOPrkeError::GenericStringError(
+                format!("keff must be positive, got {}", keff.get::<ratio>())
+            ));
+        }
+
+        let ratio_one = Ratio::new::<ratio>(1.0);
+
+        Ok(Self::new((keff - ratio_one)/keff))
+    }
+
+    /// returns reactivity as a uom Ratio in delta k/k
+    pub fn get_ratio(&self) -> Ratio {
+        self.delta_k_over_k
+    }
+
+    /// returns reactivity in delta k/k
+    pub fn get_delta_k_over_k(&self) -> f64 {
+        self.delta_k_over_k.get::<ratio>()
+    }
+
+    /// returns reactivity in pcm
+    pub fn get_pcm(&self) -> f64 {
+        self.get_delta_k_over_k() * 1.0e5
+    }
+
+    /// returns reactivity in dollars, rho/beta_eff
+    pub fn get_dollars(&self, beta_eff: Ratio) -> f64 {
+        (self.delta_k_over_k/beta_eff).get::<ratio>()
+    }
+
+    /// returns reactivity in cents
+    pub fn get_cents(&self, beta_eff: Ratio) -> f64 {
+        self.get_dollars(beta_eff) * 100.0
+    }
+
+    /// returns keff = 1/(1 - rho)
+    pub fn get_keff(&self) -> Ratio {
+        let ratio_one = Ratio::new::<ratio>(1.0);
+
+        ratio_one/(ratio_one - self.delta_k_over_k)
+    }
+}
+
+impl From<Ratio> for Reactivity {
+    fn from(delta_k_over_k: Ratio) -> Self {
+        Self::new(delta_k_over_k)
+    }
+}
+
+impl From<Reactivity> for Ratio {
+    fn from(reactivity: Reactivity) -> Self {
+        reactivity.delta_k_over_k
+    }
+}
+
+// reactivities (eg. from several feedback mechanisms) add up
+
+impl Add for Reactivity {
+    type Output = Reactivity;
+
+    fn add(self, other: Reactivity) -> Reactivity {
+        Self::new(self.delta_k_over_k + other.delta_k_over_k)
+    }
+}
+
+impl Sub for Reactivity {
+    type Output = Reactivity;
+
+    fn sub(self, other: Reactivity) -> Reactivity {
+        Self::new(self.delta_k_over_k - other.delta_k_over_k)
+    }
+}
+
+impl Neg for Reactivity {
+    type Output = Reactivity;
+
+    fn neg(self) -> Reactivity {
+        Self::new(-self.delta_k_over_k)
+    }
+}
+
+impl Mul<f64> for Reactivity {
+    type Output = Reactivity;
+
+    fn mul(self, scale: f64) -> Reactivity {
+        Self::new(self.delta_k_over_k * scale)
+    }
+}
+
+impl Sum for Reactivity {
+    fn sum<I: Iterator<Item = Reactivity>>(iter: I) -> Reactivity {
+        iter.fold(Reactivity::default(), |total, reactivity| total + reactivity)
+    }
+}
+
+#[test]
+pub fn reactivity_unit_conversion_test(){
+
+    use approx::assert_relative_eq;
+
+    let beta_eff = Ratio::new::<ratio>(0.0065);
+
+    // half a dollar
+    let reactivity = Reactivity::new_from_dollars(0.5, beta_eff);
+
+    assert_relative_eq!(reactivity.get_delta_k_over_k(), 0.00325);
+    assert_relative_eq!(reactivity.get_pcm(), 325.0);
+    assert_relative_eq!(reactivity.get_cents(beta_eff), 50.0);
+    assert_relative_eq!(reactivity.get_dollars(beta_eff), 0.5);
+
+    assert_eq!(reactivity, Reactivity::new_from_cents(50.0, beta_eff));
+    assert_relative_eq!(
+        Reactivity::new_from_pcm(325.0).get_delta_k_over_k(),
+        0.00325);
+
+    // keff round trip, rho = (k - 1)/k
+    let keff = Ratio::new::<ratio>(1.01);
+    let reactivity_from_keff = Reactivity::new_from_keff(keff).unwrap();
+
+    assert_relative_eq!(reactivity_from_keff.get_delta_k_over_k(), 0.01/1.01);
+    assert_relative_eq!(reactivity_from_keff.get_keff().get::<ratio>(), 1.01);
+    assert!(Reactivity::new_from_keff(Ratio::new::<ratio>(0.0)).is_err());
+
+    // conversion to and from Ratio
+    let reactivity_ratio: Ratio = reactivity.into();
+    assert_relative_eq!(reactivity_ratio.get::<ratio>(), 0.00325);
+    assert_eq!(Reactivity::from(reactivity_ratio), reactivity);
+
+    // feedback contributions add up
+    let total_reactivity: Reactivity = [
+        Reactivity::new_from_pcm(100.0),
+        Reactivity::new_from_pcm(-30.0),
+        -Reactivity::new_from_pcm(20.0),
+    ].into_iter().sum();
+
+    assert_relative_eq!(total_reactivity.get_pcm(), 50.0, max_relative = 1e-12);
+    assert_relative_eq!(
+        (total_reactivity * 2.0 - Reactivity::new_from_pcm(100.0)).get_pcm(),
+        0.0, epsilon = 1e-9);
+}
@@ -0,0 +1,571 @@
+use std::f64::consts::PI;
+use std::ops::Add;
+
+use uom::si::f64::*;
+use uom::si::frequency::hertz;
+use uom::si::ratio::ratio;
+use uom::si::time::second;
+
+use crate::teh_o_prke_error::TehOPrkeError;
+
+/// reactivity as a function of time, for scripting rod withdrawals,
+/// startup accidents and oscillator experiments
+///
+/// the profile can be evaluated at any time, so solvers which take
+/// a constant reactivity per step can use the mid step value
+/// (get_mid_step_reactivity), and the ODE solvers can evaluate it
+/// at each of their stage times
+///
+/// profiles can be added together, eg. a ramp on top of a step
+///
+/// Sinusoid, ZigZag and Exponential need a positive period or time
+/// constant, which their constructors (ReactivityProfile::sinusoid
+/// etc.) check. Profiles written out by hand can be checked with
+/// validate, which the solvers taking a profile also call
+#[derive(Debug,Clone,PartialEq)]
+pub enum ReactivityProfile {
+    /// the same reactivity at all times
+    Constant(Ratio),
+
+    /// jumps from reactivity_before to reactivity_after
+    /// at step_time
+    Step {
+        step_time: Time,
+        reactivity_before: Ratio,
+        reactivity_after: Ratio,
+    },
+
+    /// rho = initial_reactivity + ramp_rate (t - start_time)
+    /// between start_time and end_time, held constant outside
+    ///
+    /// for a ramp which never stops, end_time can be infinite
+    LinearRamp {
+        start_time: Time,
+        end_time: Time,
+        initial_reactivity: Ratio,
+        ramp_rate: Frequency,
+    },
+
+    /// rho = mean_reactivity + amplitude sin(2 pi (t - start_time)/period)
+    ///
+    /// before start_time, rho = mean_reactivity
+    Sinusoid {
+        start_time: Time,
+        mean_reactivity: Ratio,
+        amplitude: Ratio,
+        period: Time,
+    },
+
+    /// ramps from zero up to peak_reactivity in one half period,
+    /// then back down to zero in the next, and so on
+    ///
+    /// after number_of_half_periods, the last value is held
+    /// (peak_reactivity if the number is odd, zero if even)
+    ZigZag {
+        start_time: Time,
+        peak_reactivity: Ratio,
+        half_period: Time,
+        number_of_half_periods: usize,
+    },
+
+    /// rho = initial + (final - initial) (1 - exp(-(t - start_time)/time_constant))
+    ///
+    /// before start_time, rho = initial_reactivity
+    Exponential {
+        start_time: Time,
+        initial_reactivity: Ratio,
+        final_reactivity: Ratio,
+        time_constant: Time,
+    },
+
+    /// linear interpolation in a table of (time, reactivity),
+    /// held constant before the first and after the last point
+    ///
+    /// the table is checked when it is built, see
+    /// ReactivityProfile::piecewise_linear
+    PiecewiseLinear(ReactivityTable),
+
+    /// sum of several profiles
+    Sum(Vec<ReactivityProfile>),
+}
+
+impl ReactivityProfile {
+
+    /// constructs a piecewise linear profile, the table must not
+    /// be empty and its times must be strictly increasing
+    pub fn piecewise_linear(
+        time_and_reactivity_table: Vec<(Time, Ratio)>) -> Result<Self, TehOPrkeError> {
+
+        Ok(Self::PiecewiseLinear(ReactivityTable::new(time_and_reactivity_table)?))
+    }
+
+    /// constructs a sinusoidal profile, the period must be positive
+    pub fn sinusoid(start_time: Time,
+        mean_reactivity: Ratio,
+        amplitude: Ratio,
+        period: Time) -> Result<Self, TehOPrkeError> {
+
+        let sinusoid = Self::Sinusoid { start_time, mean_reactivity, amplitude, period };
+        sinusoid.validate()?;
+
+        Ok(sinusoid)
+    }
+
+    /// constructs a zig-zag profile, the half period must be positive
+    pub fn zig_zag(start_time: Time,
+        peak_reactivity: Ratio,
+        half_period: Time,
+        number_of_half_periods: usize) -> Result<Self, TehOPrkeError> {
+
+        let zig_zag = Self::ZigZag { start_time, peak_reactivity, half_period,
+            number_of_half_periods };
+        zig_zag.validate()?;
+
+        Ok(zig_zag)
+    }
+
+    /// constructs an exponential profile, the time constant must
+    /// be positive
+    pub fn exponential(start_time: Time,
+        initial_reactivity: Ratio,
+        final_reactivity: Ratio,
+        time_constant: Time) -> Result<Self, TehOPrkeError> {
+
+        let exponential = Self::Exponential { start_time, initial_reactivity,
+            final_reactivity, time_constant };
+        exponential.validate()?;
+
+        Ok(exponential)
+    }
+
+    /// checks that periods and time constants are positive and
+    /// finite, as get_reactivity divides by them, including the
+    /// profiles inside a sum
+    pub fn validate(&self) -> Result<(), TehOPrkeError> {
+
+        let check_positive = |time: &Time, name: &str| -> Result<(), TehOPrkeError> {
+            let time: f64 = time.get::<second>();
+
+            if time > 0.0 && time.is_finite() {
+                Ok(())
+            } else {
+                Err(TehOPrkeError::GenericStringError(
+                    format!("reactivity profile {} must be positive and finite, \
+                        got {} s", name, time)
+                ))
+            }
+        };
+
+        match self {
+            Self::Sinusoid { period, .. } => check_positive(period, "period"),
+
+            Self::ZigZag { half_period, .. } => check_positive(half_period, "half period"),
+
+            Self::Exponential { time_constant, .. } => {
+                check_positive(time_constant, "time constant")
+            },
+
+            Self::Sum(profile_list) => {
+                profile_list.iter().try_for_each(|profile| profile.validate())
+            },
+
+            Self::Constant(_) | Self::Step { .. } | Self::LinearRamp { .. }
+                | Self::PiecewiseLinear(_) => Ok(()),
+        }
+    }
+
+    /// returns the reactivity at the given time
+    pub fn get_reactivity(&self, time: Time) -> Ratio {
+
+        let t: f64 = time.get::<second>();
+
+        let reactivity: f64 = match self {
+            Self::Constant(reactivity) => reactivity.get::<ratio>(),
+
+            Self::Step { step_time, reactivity_before, reactivity_after } => {
+                if time < *step_time {
+                    reactivity_before.get::<ratio>()
+                } else {
+                    reactivity_after.get::<ratio>()
+                }
+            },
+
+            Self::LinearRamp { start_time, end_time, initial_reactivity, ramp_rate } => {
+                let ramp_time: f64 = t.min(end_time.get::<second>())
+                    - start_time.get::<second>();
+
+                initial_reactivity.get::<ratio>()
+                    + ramp_rate.get::<hertz>() * ramp_time.max(0.0)
+            },
+
+            Self::Sinusoid { start_time, mean_reactivity, amplitude, period } => {
+                let time_since_start: f64 = (t - start_time.get::<second>()).max(0.0);
+
+                mean_reactivity.get::<ratio>()
+                    + amplitude.get::<ratio>()
+                    * (2.0 * PI * time_since_start/period.get::<second>()).sin()
+            },
+
+            Self::ZigZag { start_time, peak_reactivity, half_period, number_of_half_periods } => {
+                let half_periods_elapsed: f64 = ((t - start_time.get::<second>())
+                    /half_period.get::<second>())
+                    .clamp(0.0, *number_of_half_periods as f64);
+
+                // fraction of the way up to the peak
+                let mut fraction_of_peak: f64 = half_periods_elapsed % 2.0;
+                if fraction_of_peak > 1.0 {
+                    fraction_of_peak = 2.0 - fraction_of_peak;
+                }
+
+                peak_reactivity.get::<ratio>() * fraction_of_peak
+            },
+
+            Self::Exponential { start_time, initial_reactivity, final_reactivity, time_constant } => {
+                let time_since_start: f64 = (t - start_time.get::<second>()).max(0.0);
+
+                initial_reactivity.get::<ratio>()
+                    + (final_reactivity.get::<ratio>() - initial_reactivity.get::<ratio>())
+                    * (1.0 - (-time_since_start/time_constant.get::<second>()).exp())
+            },
+
+            Self::PiecewiseLinear(reactivity_table) => {
+                reactivity_table.interpolate(time).0
+            },
+
+            Self::Sum(profile_list) => {
+                profile_list.iter()
+                    .map(|profile| profile.get_reactivity(time).get::<ratio>())
+                    .sum()
+            },
+        };
+
+        Ratio::new::<ratio>(reactivity)
+    }
+
+    /// returns the rate of change of reactivity at the given time,
+    /// d rho/dt
+    ///
+    /// steps are taken to have zero rate, and at the corners of
+    /// ramps and tables the rate after the corner is given
+    ///
+    /// the Rosenbrock and Rodas solvers use this for the time
+    /// derivative in the jacobian (dfdx)
+    pub fn get_reactivity_rate(&self, time: Time) -> Frequency {
+
+        let t: f64 = time.get::<second>();
+
+        let reactivity_rate: f64 = match self {
+            Self::Constant(_) => 0.0,
+
+            Self::Step { .. } => 0.0,
+
+            Self::LinearRamp { start_time, end_time, ramp_rate, .. } => {
+                if time >= *start_time && time < *end_time {
+                    ramp_rate.get::<hertz>()
+                } else {
+                    0.0
+                }
+            },
+
+            Self::Sinusoid { start_time, amplitude, period, .. } => {
+                if time < *start_time {
+                    0.0
+                } else {
+                    let angular_frequency: f64 = 2.0 * PI/period.get::<second>();
+
+                    amplitude.get::<ratio>() * angular_frequency
+                        * (angular_frequency * (t - start_time.get::<second>())).cos()
+                }
+            },
+
+            Self::ZigZag { start_time, peak_reactivity, half_period, number_of_half_periods } => {
+                let half_periods_elapsed: f64 = (t - start_time.get::<second>())
+                    /half_period.get::<second>();
+
+                if half_periods_elapsed < 0.0
+                    || half_periods_elapsed >= *number_of_half_periods as f64 {
+                        0.0
+                } else {
+                    let ramp_rate: f64 = peak_reactivity.get::<ratio>()
+                        /half_period.get::<second>();
+
+                    // rising on even half periods, falling on odd ones
+                    if half_periods_elapsed % 2.0 < 1.0 {
+                        ramp_rate
+                    } else {
+                        -ramp_rate
+                    }
+                }
+            },
+
+            Self::Exponential { start_time, initial_reactivity, final_reactivity, time_constant } => {
+                if time < *start_time {
+                    0.0
+                } else {
+                    let tau: f64 = time_constant.get::<second>();
+
+                    (final_reactivity.get::<ratio>() - initial_reactivity.get::<ratio>())
+                        /tau * (-(t - start_time.get::<second>())/tau).exp()
+                }
+            },
+
+            Self::PiecewiseLinear(reactivity_table) => {
+                reactivity_table.interpolate(time).1
+            },
+
+            Self::Sum(profile_list) => {
+                profile_list.iter()
+                    .map(|profile| profile.get_reactivity_rate(time).get::<hertz>())
+                    .sum()
+            },
+        };
+
+        Frequency::new::<hertz>(reactivity_rate)
+    }
+
+    /// returns the reactivity halfway through a timestep starting
+    /// at the given time
+    ///
+    /// for solvers which hold reactivity constant over a step, this
+    /// is second order accurate for smoothly varying reactivity
+    pub fn get_mid_step_reactivity(&self, time: Time, timestep: Time) -> Ratio {
+        self.get_reactivity(time + 0.5 * timestep)
+    }
+}
+
+/// adding two profiles gives their sum
+impl Add for ReactivityProfile {
+    type Output = ReactivityProfile;
+
+    fn add(self, other: ReactivityProfile) -> ReactivityProfile {
+
+        let mut profile_list: Vec<ReactivityProfile> = vec![];
+
+        for profile in [self, other] {
+            match profile {
+                Self::Sum(inner_profile_list) => profile_list.extend(inner_profile_list),
+                profile => profile_list.push(profile),
+            }
+        }
+
+        Self::Sum(profile_list)
+    }
+}
+
+/// table of (time, reactivity) for a piecewise linear profile,
+/// never empty, with strictly increasing times
+#[derive(Debug,Clone,PartialEq)]
+pub struct ReactivityTable {
+    time_and_reactivity_table: Vec<(Time, Ratio)>,
+}
+
+impl ReactivityTable {
+
+    /// checks and constructs the table, it must not be empty and
+    /// its times must be strictly increasing
+    pub fn new(time_and_reactivity_table: Vec<(Time, Ratio)>) -> Result<Self, TehOPrkeError> {
+
+        if time_and_reactivity_table.is_empty() {
+            return Err(TehOPrkeError::GenericStringError(
+                "piecewise linear reactivity profile needs at least one point".to_string()
+            ));
+        }
+
+        for window in time_and_reactivity_table.windows(2) {
+            if window[1].0 <= window[0].0 {
+                return Err(TehOPrkeError::GenericStringError(
+                    format!("piecewise linear reactivity profile times must be \
+                        strictly increasing, got {} s then {} s",
+                        window[0].0.get::<second>(),
+                        window[1].0.get::<second>())
+                ));
+            }
+        }
+
+        Ok(Self { time_and_reactivity_table })
+    }
+
+    /// returns the (time, reactivity) points
+    pub fn get_time_and_reactivity_table(&self) -> &[(Time, Ratio)] {
+        &self.time_and_reactivity_table
+    }
+
+    /// returns (reactivity, d rho/dt) in SI units from linear
+    /// interpolation in the table
+    fn interpolate(&self, time: Time) -> (f64, f64) {
+
+        let time_and_reactivity_table = &self.time_and_reactivity_table;
+
+        let (first_time, first_reactivity) = time_and_reactivity_table[0];
+        let (last_time, last_reactivity) =
+            time_and_reactivity_table[time_and_reactivity_table.len() - 1];
+
+        if time < first_time {
+            return (first_reactivity.get::<ratio>(), 0.0);
+        }
+
+        if time >= last_time {
+            return (last_reactivity.get::<ratio>(), 0.0);
+        }
+
+        // the first point after the given time
+        let upper_index = time_and_reactivity_table
+            .partition_point(|&(table_time, _)| table_time <= time);
+
+        let (lower_time, lower_reactivity) = time_and_reactivity_table[upper_index - 1];
+        let (upper_time, upper_reactivity) = time_and_reactivity_table[upper_index];
+
+        let slope: f64 = (upper_reactivity - lower_reactivity).get::<ratio>()
+            /(upper_time - lower_time).get::<second>();
+
+        (lower_reactivity.get::<ratio>() + slope * (time - lower_time).get::<second>(),
+        slope)
+    }
+}
+
+#[test]
+pub fn reactivity_profile_shapes_test(){
+
+    use approx::assert_relative_eq;
+
+    let seconds = Time::new::<second>;
+    let reactivity = Ratio::new::<ratio>;
+
+    // step
+    let step = ReactivityProfile::Step {
+        step_time: seconds(1.0),
+        reactivity_before: reactivity(0.0),
+        reactivity_after: reactivity(0.003),
+    };
+
+    assert_relative_eq!(step.get_reactivity(seconds(0.5)).get::<ratio>(), 0.0);
+    assert_relative_eq!(step.get_reactivity(seconds(1.0)).get::<ratio>(), 0.003);
+
+    // ramp at 0.001 per second from 1 s to 3 s
+    let ramp = ReactivityProfile::LinearRamp {
+        start_time: seconds(1.0),
+        end_time: seconds(3.0),
+        initial_reactivity: reactivity(0.0),
+        ramp_rate: Frequency::new::<hertz>(0.001),
+    };
+
+    assert_relative_eq!(ramp.get_reactivity(seconds(0.0)).get::<ratio>(), 0.0);
+    assert_relative_eq!(ramp.get_reactivity(seconds(2.0)).get::<ratio>(), 0.001);
+    assert_relative_eq!(ramp.get_reactivity(seconds(10.0)).get::<ratio>(), 0.002);
+    assert_relative_eq!(ramp.get_reactivity_rate(seconds(2.0)).get::<hertz>(), 0.001);
+    assert_relative_eq!(ramp.get_reactivity_rate(seconds(10.0)).get::<hertz>(), 0.0);
+
+    // mid step of [1 s, 2 s] is 1.5 s
+    assert_relative_eq!(
+        ramp.get_mid_step_reactivity(seconds(1.0), seconds(1.0)).get::<ratio>(),
+        0.0005);
+
+    // sinusoid, quarter period is the peak
+    let sinusoid = ReactivityProfile::sinusoid(
+        seconds(0.0),
+        reactivity(0.001),
+        reactivity(0.002),
+        seconds(4.0)).unwrap();
+
+    assert_relative_eq!(sinusoid.get_reactivity(seconds(1.0)).get::<ratio>(), 0.003);
+    assert_relative_eq!(sinusoid.get_reactivity(seconds(3.0)).get::<ratio>(), -0.001);
+    assert_relative_eq!(sinusoid.get_reactivity_rate(seconds(0.0)).get::<hertz>(),
+        0.002 * 2.0 * PI/4.0);
+
+    // zig-zag, up, down, up and then held at the peak
+    let zig_zag = ReactivityProfile::zig_zag(
+        seconds(0.0),
+        reactivity(0.004),
+        seconds(0.5),
+        3).unwrap();
+
+    assert_relative_eq!(zig_zag.get_reactivity(seconds(0.25)).get::<ratio>(), 0.002);
+    assert_relative_eq!(zig_zag.get_reactivity(seconds(0.5)).get::<ratio>(), 0.004);
+    assert_relative_eq!(zig_zag.get_reactivity(seconds(0.75)).get::<ratio>(), 0.002);
+    assert_relative_eq!(zig_zag.get_reactivity(seconds(1.0)).get::<ratio>(), 0.0);
+    assert_relative_eq!(zig_zag.get_reactivity(seconds(1.25)).get::<ratio>(), 0.002);
+    assert_relative_eq!(zig_zag.get_reactivity(seconds(5.0)).get::<ratio>(), 0.004);
+    assert_relative_eq!(zig_zag.get_reactivity_rate(seconds(0.75)).get::<hertz>(), -0.008);
+    assert_relative_eq!(zig_zag.get_reactivity_rate(seconds(5.0)).get::<hertz>(), 0.0);
+
+    // exponential, one time constant in
+    let exponential = ReactivityProfile::exponential(
+        seconds(0.0),
+        reactivity(0.0),
+        reactivity(0.001),
+        seconds(2.0)).unwrap();
+
+    assert_relative_eq!(exponential.get_reactivity(seconds(2.0)).get::<ratio>(),
+        0.001 * (1.0 - (-1.0_f64).exp()));
+
+    // table
+    let table = ReactivityProfile::piecewise_linear(vec![
+        (seconds(0.0), reactivity(0.0)),
+        (seconds(1.0), reactivity(0.002)),
+        (seconds(3.0), reactivity(-0.002)),
+    ]).unwrap();
+
+    assert_relative_eq!(table.get_reactivity(seconds(-1.0)).get::<ratio>(), 0.0);
+    assert_relative_eq!(table.get_reactivity(seconds(0.5)).get::<ratio>(), 0.001);
+    assert_relative_eq!(table.get_reactivity(seconds(2.5)).get::<ratio>(), -0.001);
+    assert_relative_eq!(table.get_reactivity(seconds(4.0)).get::<ratio>(), -0.002);
+    assert_relative_eq!(table.get_reactivity_rate(seconds(2.0)).get::<hertz>(), -0.002);
+
+    assert!(ReactivityProfile::piecewise_linear(vec![]).is_err());
+    assert!(ReactivityTable::new(vec![]).is_err());
+
+    match &table {
+        ReactivityProfile::PiecewiseLinear(reactivity_table) => {
+            assert_eq!(reactivity_table.get_time_and_reactivity_table().len(), 3);
+        },
+        _ => panic!("expected a piecewise linear profile"),
+    }
+    assert!(ReactivityProfile::piecewise_linear(vec![
+        (seconds(1.0), reactivity(0.0)),
+        (seconds(1.0), reactivity(0.001)),
+    ]).is_err());
+
+    // a ramp on top of a step, the sum is flattened
+    let step_and_ramp = step + ramp + ReactivityProfile::Constant(reactivity(-0.001));
+
+    match &step_and_ramp {
+        ReactivityProfile::Sum(profile_list) => assert_eq!(profile_list.len(), 3),
+        _ => panic!("adding profiles should give a sum"),
+    }
+
+    assert_relative_eq!(step_and_ramp.get_reactivity(seconds(2.0)).get::<ratio>(), 0.003);
+    assert_relative_eq!(step_and_ramp.get_reactivity_rate(seconds(2.0)).get::<hertz>(), 0.001);
+}
+
+#[test]
+pub fn reactivity_profile_rejects_non_positive_periods(){
+
+    let seconds = Time::new::<second>;
+    let reactivity = Ratio::new::<ratio>;
+
+    for bad_time in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+        assert!(ReactivityProfile::sinusoid(
+            seconds(0.0), reactivity(0.0), reactivity(0.001), seconds(bad_time)).is_err());
+        assert!(ReactivityProfile::zig_zag(
+            seconds(0.0), reactivity(0.001), seconds(bad_time), 2).is_err());
+        assert!(ReactivityProfile::exponential(
+            seconds(0.0), reactivity(0.0), reactivity(0.001), seconds(bad_time)).is_err());
+    }
+
+    // written out by hand, and hidden in a sum
+    let hand_written_sinusoid = ReactivityProfile::Sinusoid {
+        start_time: seconds(0.0),
+        mean_reactivity: reactivity(0.0),
+        amplitude: reactivity(0.001),
+        period: seconds(0.0),
+    };
+    assert!(hand_written_sinusoid.validate().is_err());
+
+    let sum = ReactivityProfile::Constant(reactivity(0.001)) + hand_written_sinusoid;
+    assert!(sum.validate().is_err());
+
+    let good_sum = ReactivityProfile::Constant(reactivity(0.001))
+        + ReactivityProfile::exponential(
+            seconds(0.0), reactivity(0.0), reactivity(0.001), seconds(1.0)).unwrap();
+    assert!(good_sum.validate().is_ok());
+}
@@ -0,0 +1,590 @@
+use std::f64::consts::PI;
+
+use ndarray::Array2;
+use num_complex::Complex;
+use uom::si::area::square_meter;
+use uom::si::f64::*;
+use uom::si::frequency::hertz;
+use uom::si::linear_number_density::per_meter;
+use uom::si::ratio::ratio;
+use uom::si::temperature_interval;
+use uom::si::thermodynamic_temperature::kelvin;
+use uom::si::time::second;
+use uom::si::velocity::meter_per_second;
+use uom::si::volumetric_number_density::per_cubic_meter;
+
+use crate::feedback_mechanisms::fission_product_poisons::Xenon135Poisoning;
+use crate::fuel_temperature_feedback::SimpleFuelTemperatureFeedback;
+use crate::linear_algebra::eigenvalues;
+use crate::teh_o_prke_error::TehOPrkeError;
+use crate::zero_power_prke::multi_group_precursor_prke::MultiGroupPRKE;
+use crate::zero_power_prke::reactor_power::ReactorPowerModel;
+use crate::zero_power_prke::transfer_function::LinearFeedbackModel;
+
+/// growth rates below this are round off rather than real
+/// instabilities (the e-folding time is decades)
+const NEUTRAL_GROWTH_RATE_HERTZ: f64 = 1.0e-9;
+
+/// linearised stability of the PRKE coupled with feedback, about
+/// a critical steady state at a given power
+///
+/// the state is the relative deviation from steady state of the
+/// neutron population and each precursor group, then one
+/// reactivity state for each first order lag feedback (eg. fuel
+/// temperature), then the relative deviation of iodine 135 and
+/// xenon 135 if xenon is included
+///
+/// the Jacobian of the coupled system is written down directly
+/// (the linearisation is analytic, except for the xenon worth,
+/// which is differenced through Xenon135Poisoning), and its
+/// eigenvalues give the modes
+#[derive(Debug,Clone)]
+pub struct CoupledStabilityAnalysis<const G: usize> {
+    /// delayed neutron data
+    pub prke: MultiGroupPRKE<G>,
+    /// neutron generation time, Lambda
+    pub neutron_generation_time: Time,
+    /// converts between neutron population and power
+    pub reactor_power_model: ReactorPowerModel,
+    /// steady state neutron population at the operating point
+    pub neutron_population_number_density: VolumetricNumberDensity,
+    /// linear feedback acting on the kinetics
+    pub feedback_model_list: Vec<LinearFeedbackModel>,
+    /// whether iodine 135 and xenon 135 are coupled in
+    pub include_xenon_135: bool,
+}
+
+/// one mode of the linearised system, for complex conjugate
+/// pairs only the one with positive imaginary part is kept
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub struct StabilityMode {
+    /// eigenvalue, in 1/s
+    pub eigenvalue: Complex<f64>,
+    /// damping ratio, -Re/|eigenvalue|, negative if the mode grows
+    pub damping_ratio: f64,
+    /// 2 pi/Im, None if the mode does not oscillate
+    pub oscillation_period: Option<Time>,
+}
+
+impl StabilityMode {
+
+    /// constructs the mode from its eigenvalue
+    pub fn new(eigenvalue: Complex<f64>) -> Self {
+
+        let damping_ratio = if eigenvalue.norm() == 0.0 {
+            0.0
+        } else {
+            -eigenvalue.re/eigenvalue.norm()
+        };
+
+        let oscillation_period = if eigenvalue.im == 0.0 {
+            None
+        } else {
+            Some(Time::new::<second>(2.0 * PI/eigenvalue.im.abs()))
+        };
+
+        Self { eigenvalue, damping_ratio, oscillation_period }
+    }
+
+    /// returns true if the mode grows with time
+    pub fn is_unstable(&self) -> bool {
+        self.eigenvalue.re > NEUTRAL_GROWTH_RATE_HERTZ
+    }
+}
+
+/// problems found by the stability analysis
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub enum StabilityWarning {
+    /// steady state reactivity per unit relative power is positive,
+    /// so a power rise adds reactivity
+    PositivePowerCoefficient(Ratio),
+    /// a growing oscillation, eg. xenon driven power oscillations,
+    /// with periods of about a day
+    UnstableOscillatoryMode(StabilityMode),
+    /// a mode which grows without oscillating, eg. from a positive
+    /// power coefficient
+    UnstableExponentialMode(StabilityMode),
+}
+
+/// result of the stability analysis
+#[derive(Debug,Clone,PartialEq)]
+pub struct StabilityReport {
+    /// modes of the linearised system, from the fastest growing
+    /// (or slowest decaying) to the fastest decaying
+    pub mode_list: Vec<StabilityMode>,
+    /// steady state reactivity per unit relative power change,
+    /// with lagged feedback and xenon at their new equilibria
+    pub power_coefficient: Ratio,
+    /// unstable modes and a positive power coefficient, if any
+    pub warning_list: Vec<StabilityWarning>,
+}
+
+impl StabilityReport {
+
+    /// returns true if nothing was flagged
+    pub fn is_stable(&self) -> bool {
+        self.warning_list.is_empty()
+    }
+
+    /// returns the mode with the largest real part
+    pub fn get_least_stable_mode(&self) -> StabilityMode {
+        self.mode_list[0]
+    }
+}
+
+impl<const G: usize> CoupledStabilityAnalysis<G> {
+
+    /// sets up the analysis at a steady state power, with no
+    /// feedback yet
+    pub fn new(prke: MultiGroupPRKE<G>,
+        neutron_generation_time: Time,
+        reactor_power_model: ReactorPowerModel,
+        reactor_power: Power) -> Result<Self, TehOPrkeError> {
+
+        if neutron_generation_time.get::<second>() <= 0.0 {
+            return Err(TehOPrkeError::GenericStringError(
+                "neutron generation time must be positive".to_string()
+            ));
+        }
+
+        let neutron_population_number_density =
+            reactor_power_model.get_neutron_population_number_density(reactor_power);
+
+        if neutron_population_number_density.get::<per_cubic_meter>() <= 0.0 {
+            return Err(TehOPrkeError::GenericStringError(
+                "the operating power must be positive".to_string()
+            ));
+        }
+
+        Ok(Self {
+            prke,
+            neutron_generation_time,
+            reactor_power_model,
+            neutron_population_number_density,
+            feedback_model_list: vec![],
+            include_xenon_135: false,
+        })
+    }
+
+    /// returns the power at the operating point
+    pub fn get_reactor_power(&self) -> Power {
+        self.reactor_power_model.get_power(self.neutron_population_number_density)
+    }
+
+    /// adds fuel temperature feedback, linearised about the steady
+    /// state fuel temperature at the operating power
+    ///
+    /// T_0 = T_coolant + P_0/(hA)
+    ///
+    /// the feedback is a first order lag, see
+    /// LinearFeedbackModel::new_from_fuel_temperature_feedback
+    pub fn add_fuel_temperature_feedback(&mut self,
+        fuel_temperature_feedback: &SimpleFuelTemperatureFeedback,
+        coolant_temperature: ThermodynamicTemperature) -> Result<(), TehOPrkeError> {
+
+        let heat_transfer_conductance = fuel_temperature_feedback.convection_heat_trf_coeff
+            * fuel_temperature_feedback.convection_heat_trf_area;
+
+        let steady_state_power = self.get_reactor_power();
+
+        let steady_state_temperature_rise: TemperatureInterval =
+            steady_state_power/heat_transfer_conductance;
+
+        let steady_state_fuel_temperature = ThermodynamicTemperature::new::<kelvin>(
+            coolant_temperature.get::<kelvin>()
+            + steady_state_temperature_rise.get::<temperature_interval::kelvin>());
+
+        self.feedback_model_list.push(LinearFeedbackModel::new_from_fuel_temperature_feedback(
+            fuel_temperature_feedback,
+            steady_state_power,
+            steady_state_fuel_temperature)?);
+
+        Ok(())
+    }
+
+    /// couples iodine 135 and xenon 135 to the kinetics, at their
+    /// equilibrium for the operating power
+    ///
+    /// this fails if there are no iodine and xenon yields for the
+    /// fissioning nuclide of the prke
+    pub fn add_xenon_135_feedback(&mut self) -> Result<(), TehOPrkeError> {
+        Xenon135Poisoning::get_iodine_135_yield(self.prke.delayed_group_mode)?;
+        Xenon135Poisoning::get_xe_135_yield(self.prke.delayed_group_mode)?;
+
+        self.include_xenon_135 = true;
+
+        Ok(())
+    }
+
+    /// returns the steady state reactivity per unit relative power
+    /// change, dn/n0
+    ///
+    /// for the lagged feedback this is K, and for xenon
+    /// drho/dX dX_eq/d(n/n0)
+    pub fn get_power_coefficient(&self) -> Result<Ratio, TehOPrkeError> {
+
+        let mut power_coefficient: f64 = self.feedback_model_list.iter()
+            .map(get_steady_state_gain)
+            .sum();
+
+        if self.include_xenon_135 {
+            let xenon_data = self.get_xenon_steady_state_data()?;
+
+            // at equilibrium, dX/X0 = lambda_X/(lambda_X + sigma phi0) dn/n0
+            power_coefficient += xenon_data.reactivity_per_relative_xenon
+                * xenon_data.xenon_decay_constant
+                /(xenon_data.xenon_decay_constant + xenon_data.xenon_burnup_rate);
+        }
+
+        Ok(Ratio::new::<ratio>(power_coefficient))
+    }
+
+    /// returns the Jacobian of the linearised system, in 1/s
+    ///
+    /// for the kinetics, with x = dn/n0 and c_i = dC_i/C_i0,
+    ///
+    /// dx/dt = -beta/Lambda x + sum_i beta_i/Lambda c_i + drho/Lambda
+    ///
+    /// dc_i/dt = lambda_i (x - c_i)
+    pub fn get_jacobian_matrix(&self) -> Result<Array2<f64>, TehOPrkeError> {
+
+        let big_lambda = self.neutron_generation_time.get::<second>();
+        let number_of_lag_states: usize = self.feedback_model_list.iter()
+            .map(get_number_of_lag_states)
+            .sum();
+        let number_of_xenon_states = if self.include_xenon_135 { 2 } else { 0 };
+        let size = G + 1 + number_of_lag_states + number_of_xenon_states;
+
+        let mut jacobian_matrix: Array2<f64> = Array2::zeros((size, size));
+
+        let beta: f64 = self.prke.delayed_fraction_array.iter()
+            .map(|beta_i| beta_i.get::<ratio>())
+            .sum();
+
+        jacobian_matrix[[0, 0]] = -beta/big_lambda;
+
+        for i in 0..G {
+            let beta_i = self.prke.delayed_fraction_array[i].get::<ratio>();
+            let lambda_i = self.prke.decay_constant_array[i].get::<hertz>();
+
+            jacobian_matrix[[0, i + 1]] = beta_i/big_lambda;
+            jacobian_matrix[[i + 1, 0]] = lambda_i;
+            jacobian_matrix[[i + 1, i + 1]] = -lambda_i;
+        }
+
+        // each lag state is the feedback reactivity r, with
+        // tau dr/dt = K x - r
+        let mut next_state = G + 1;
+        for feedback_model in self.feedback_model_list.iter() {
+            add_feedback_to_jacobian(feedback_model,
+                &mut jacobian_matrix,
+                &mut next_state,
+                big_lambda);
+        }
+
+        if self.include_xenon_135 {
+            let xenon_data = self.get_xenon_steady_state_data()?;
+            let iodine_state = next_state;
+            let xenon_state = next_state + 1;
+            let lambda_iodine = xenon_data.iodine_decay_constant;
+            let lambda_xenon = xenon_data.xenon_decay_constant;
+            let xenon_burnup_rate = xenon_data.xenon_burnup_rate;
+
+            // d iota/dt = lambda_I (x - iota)
+            jacobian_matrix[[iodine_state, 0]] = lambda_iodine;
+            jacobian_matrix[[iodine_state, iodine_state]] = -lambda_iodine;
+
+            // d xi/dt = gamma_X F0/X0 x + lambda_I I0/X0 iota
+            // - lambda_X xi - sigma phi0 (x + xi)
+            jacobian_matrix[[xenon_state, 0]] =
+                xenon_data.direct_xenon_production_rate - xenon_burnup_rate;
+            jacobian_matrix[[xenon_state, iodine_state]] =
+                xenon_data.xenon_production_rate_from_iodine;
+            jacobian_matrix[[xenon_state, xenon_state]] =
+                -(lambda_xenon + xenon_burnup_rate);
+
+            jacobian_matrix[[0, xenon_state]] =
+                xenon_data.reactivity_per_relative_xenon/big_lambda;
+        }
+
+        Ok(jacobian_matrix)
+    }
+
+    /// returns the modes of the linearised system, and flags
+    /// unstable modes and a positive power coefficient
+    pub fn analyse(&self) -> Result<StabilityReport, TehOPrkeError> {
+
+        let eigenvalue_list = eigenvalues(&self.get_jacobian_matrix()?)?;
+
+        let mut mode_list: Vec<StabilityMode> = eigenvalue_list.into_iter()
+            .filter(|eigenvalue| eigenvalue.im >= 0.0)
+            .map(StabilityMode::new)
+            .collect();
+
+        mode_list.sort_by(|mode_1, mode_2| {
+            mode_2.eigenvalue.re.total_cmp(&mode_1.eigenvalue.re)
+        });
+
+        let power_coefficient = self.get_power_coefficient()?;
+
+        let mut warning_list: Vec<StabilityWarning> = vec![];
+
+        if power_coefficient.get::<ratio>() > 0.0 {
+            warning_list.push(StabilityWarning::PositivePowerCoefficient(power_coefficient));
+        }
+
+        for mode in mode_list.iter().filter(|mode| mode.is_unstable()) {
+            match mode.oscillation_period {
+                Some(_) => warning_list.push(StabilityWarning::UnstableOscillatoryMode(*mode)),
+                None => warning_list.push(StabilityWarning::UnstableExponentialMode(*mode)),
+            }
+        }
+
+        Ok(StabilityReport {
+            mode_list,
+            power_coefficient,
+            warning_list,
+        })
+    }
+
+    /// equilibrium iodine and xenon and the rates needed for
+    /// their linearisation, all in 1/s except the reactivity
+    fn get_xenon_steady_state_data(&self) -> Result<XenonSteadyStateData, TehOPrkeError> {
+
+        let fissioning_nuclide = self.prke.delayed_group_mode;
+        let gamma_iodine = Xenon135Poisoning::get_iodine_135_yield(fissioning_nuclide)?
+            .get::<ratio>();
+        let gamma_xenon = Xenon135Poisoning::get_xe_135_yield(fissioning_nuclide)?
+            .get::<ratio>();
+        let lambda_iodine = Xenon135Poisoning::iodine_135_decay_const().get::<hertz>();
+        let lambda_xenon = Xenon135Poisoning::xe_135_decay_const().get::<hertz>();
+        let sigma_xenon = Xenon135Poisoning::xe135_thermal_abs_xs().get::<square_meter>();
+
+        // phi = n v and F = Sigma_f phi, as in the power model
+        let neutron_flux = self.neutron_population_number_density.get::<per_cubic_meter>()
+            * self.reactor_power_model.neutron_speed.get::<meter_per_second>();
+        let fission_rate = neutron_flux
+            * self.reactor_power_model.macroscopic_fission_xs.get::<per_meter>();
+
+        let iodine_number_density = gamma_iodine * fission_rate/lambda_iodine;
+        let xenon_burnup_rate = sigma_xenon * neutron_flux;
+        let xenon_number_density = (gamma_iodine + gamma_xenon) * fission_rate
+            /(lambda_xenon + xenon_burnup_rate);
+
+        Ok(XenonSteadyStateData {
+            iodine_decay_constant: lambda_iodine,
+            xenon_decay_constant: lambda_xenon,
+            xenon_burnup_rate,
+            direct_xenon_production_rate: gamma_xenon * fission_rate/xenon_number_density,
+            xenon_production_rate_from_iodine:
+                lambda_iodine * iodine_number_density/xenon_number_density,
+            reactivity_per_relative_xenon: get_xenon_reactivity_derivative(xenon_number_density)
+                * xenon_number_density,
+        })
+    }
+}
+
+/// iodine and xenon rates about equilibrium, in 1/s, and the
+/// reactivity for a unit relative change in xenon
+struct XenonSteadyStateData {
+    iodine_decay_constant: f64,
+    xenon_decay_constant: f64,
+    xenon_burnup_rate: f64,
+    direct_xenon_production_rate: f64,
+    xenon_production_rate_from_iodine: f64,
+    reactivity_per_relative_xenon: f64,
+}
+
+/// drho/dX per (1/m3), differenced through the xenon worth of
+/// Xenon135Poisoning, where k goes as the thermal utilisation
+/// f(X), so drho = df/f
+fn get_xenon_reactivity_derivative(xenon_number_density: f64) -> f64 {
+
+    let get_log_thermal_utilisation = |xenon_number_density: f64| -> f64 {
+        let xenon_poisoning = Xenon135Poisoning {
+            iodine_135_num_density: VolumetricNumberDensity::new::<per_cubic_meter>(0.0),
+            xenon_135_num_density:
+                VolumetricNumberDensity::new::<per_cubic_meter>(xenon_number_density),
+        };
+
+        Xenon135Poisoning::simplified_poison_concentration_feedback(
+            xenon_poisoning.get_current_xe135_conc()).get::<ratio>().ln()
+    };
+
+    let step = 1.0e-4 * xenon_number_density.max(1.0);
+
+    (get_log_thermal_utilisation(xenon_number_density + step)
+     - get_log_thermal_utilisation((xenon_number_density - step).max(0.0)))
+        /(xenon_number_density + step - (xenon_number_density - step).max(0.0))
+}
+
+fn get_steady_state_gain(feedback_model: &LinearFeedbackModel) -> f64 {
+    match feedback_model {
+        LinearFeedbackModel::Proportional { reactivity_per_relative_power }
+            | LinearFeedbackModel::FirstOrderLag { reactivity_per_relative_power, .. } => {
+                reactivity_per_relative_power.get::<ratio>()
+            },
+        LinearFeedbackModel::Sum(feedback_model_list) => {
+            feedback_model_list.iter().map(get_steady_state_gain).sum()
+        },
+    }
+}
+
+fn get_number_of_lag_states(feedback_model: &LinearFeedbackModel) -> usize {
+    match feedback_model {
+        LinearFeedbackModel::Proportional { .. } => 0,
+        LinearFeedbackModel::FirstOrderLag { .. } => 1,
+        LinearFeedbackModel::Sum(feedback_model_list) => {
+            feedback_model_list.iter().map(get_number_of_lag_states).sum()
+        },
+    }
+}
+
+fn add_feedback_to_jacobian(feedback_model: &LinearFeedbackModel,
+    jacobian_matrix: &mut Array2<f64>,
+    next_state: &mut usize,
+    big_lambda: f64) {
+
+    match feedback_model {
+        LinearFeedbackModel::Proportional { reactivity_per_relative_power } => {
+            jacobian_matrix[[0, 0]] += reactivity_per_relative_power.get::<ratio>()/big_lambda;
+        },
+        LinearFeedbackModel::FirstOrderLag { reactivity_per_relative_power, time_constant } => {
+            let lag_state = *next_state;
+            let tau = time_constant.get::<second>();
+
+            jacobian_matrix[[lag_state, 0]] = reactivity_per_relative_power.get::<ratio>()/tau;
+            jacobian_matrix[[lag_state, lag_state]] = -1.0/tau;
+            jacobian_matrix[[0, lag_state]] = 1.0/big_lambda;
+
+            *next_state += 1;
+        },
+        LinearFeedbackModel::Sum(feedback_model_list) => {
+            for feedback_model in feedback_model_list.iter() {
+                add_feedback_to_jacobian(feedback_model,
+                    jacobian_matrix,
+                    next_state,
+                    big_lambda);
+            }
+        },
+    }
+}
+
+#[test]
+pub fn stability_analysis_of_kinetics_fuel_temperature_and_xenon(){
+
+    use approx::assert_relative_eq;
+    use uom::si::power::{kilowatt, megawatt};
+    use uom::si::time::{hour, microsecond};
+    use uom::si::volume::cubic_meter;
+    use crate::zero_power_prke::six_group_precursor_prke::SixGroupPRKE;
+    use crate::zero_power_prke::six_group_precursor_prke::six_group_constants::FissioningNuclideType;
+
+    let neutron_generation_time = Time::new::<microsecond>(100.0);
+    let reactor_power_model = ReactorPowerModel::new_thermal_reactor(
+        LinearNumberDensity::new::<per_meter>(10.0),
+        Volume::new::<cubic_meter>(30.0)).unwrap();
+    let prke = SixGroupPRKE::new_from_nuclide(FissioningNuclideType::U235);
+
+    // without feedback, the modes are the inhour roots at zero
+    // reactivity, one of which is zero (neutral, not unstable)
+    let kinetics_analysis = CoupledStabilityAnalysis::new(prke,
+        neutron_generation_time,
+        reactor_power_model,
+        Power::new::<kilowatt>(1.0)).unwrap();
+
+    let kinetics_report = kinetics_analysis.analyse().unwrap();
+    let inhour_solution = prke.solve_inhour_equation(
+        Ratio::new::<ratio>(0.0), neutron_generation_time).unwrap();
+
+    assert!(kinetics_report.is_stable());
+    assert_eq!(kinetics_report.mode_list.len(), 7);
+    assert_relative_eq!(kinetics_report.mode_list[0].eigenvalue.re, 0.0, epsilon = 1e-9);
+
+    for (mode, root) in kinetics_report.mode_list.iter()
+        .zip(inhour_solution.root_list.iter()).skip(1) {
+            assert_eq!(mode.oscillation_period, None);
+            assert_relative_eq!(mode.eigenvalue.re, root.get::<hertz>(),
+                max_relative = 1e-6);
+    }
+
+    // negative fuel temperature feedback damps everything, and
+    // the power coefficient is K = alpha P0/(hA)
+    let mut fuel_temperature_feedback = SimpleFuelTemperatureFeedback::default();
+    let coolant_temperature = ThermodynamicTemperature::new::<kelvin>(300.0);
+
+    let mut fuel_analysis = kinetics_analysis.clone();
+    fuel_analysis.add_fuel_temperature_feedback(&fuel_temperature_feedback,
+        coolant_temperature).unwrap();
+
+    let fuel_report = fuel_analysis.analyse().unwrap();
+    let heat_transfer_conductance = fuel_temperature_feedback.convection_heat_trf_coeff
+        * fuel_temperature_feedback.convection_heat_trf_area;
+    let steady_state_fuel_temperature = 300.0 + 1000.0/heat_transfer_conductance.value;
+    let alpha = -fuel_temperature_feedback.alpha_coefficient.get::<ratio>()
+        /steady_state_fuel_temperature.sqrt();
+
+    assert!(fuel_report.is_stable());
+    assert!(fuel_report.get_least_stable_mode().eigenvalue.re < 0.0);
+    assert_relative_eq!(fuel_report.power_coefficient.get::<ratio>(),
+        alpha * 1000.0/heat_transfer_conductance.value, max_relative = 1e-12);
+
+    // flipping the sign of alpha makes power run away
+    fuel_temperature_feedback.alpha_coefficient = -fuel_temperature_feedback.alpha_coefficient;
+
+    let mut positive_feedback_analysis = kinetics_analysis.clone();
+    positive_feedback_analysis.add_fuel_temperature_feedback(&fuel_temperature_feedback,
+        coolant_temperature).unwrap();
+
+    let positive_feedback_report = positive_feedback_analysis.analyse().unwrap();
+
+    assert!(!positive_feedback_report.is_stable());
+    assert!(matches!(positive_feedback_report.warning_list[0],
+        StabilityWarning::PositivePowerCoefficient(_)));
+    assert!(positive_feedback_report.warning_list.iter().any(|warning| {
+        matches!(warning, StabilityWarning::UnstableExponentialMode(_))
+    }));
+
+    // xenon alone at 30 MW, a low flux of about 3e15 /m2 s here
+    // (3e11 /cm2 s), with no prompt feedback to damp it gives a
+    // growing power oscillation with a period of about a day
+    let mut xenon_analysis = CoupledStabilityAnalysis::new(prke,
+        neutron_generation_time,
+        reactor_power_model,
+        Power::new::<megawatt>(30.0)).unwrap();
+    xenon_analysis.add_xenon_135_feedback().unwrap();
+
+    // there are no iodine and xenon yields for Th232
+    let mut thorium_analysis = xenon_analysis.clone();
+    thorium_analysis.include_xenon_135 = false;
+    thorium_analysis.prke.delayed_group_mode = FissioningNuclideType::Th232;
+    assert!(thorium_analysis.add_xenon_135_feedback().is_err());
+
+    let xenon_report = xenon_analysis.analyse().unwrap();
+
+    assert!(xenon_report.power_coefficient.get::<ratio>() < 0.0);
+
+    let unstable_xenon_mode = match xenon_report.warning_list[..] {
+        [StabilityWarning::UnstableOscillatoryMode(mode)] => mode,
+        _ => panic!("expected one unstable xenon oscillation, got {:?}",
+            xenon_report.warning_list),
+    };
+
+    let oscillation_period = unstable_xenon_mode.oscillation_period.unwrap();
+    assert!(oscillation_period > Time::new::<hour>(10.0));
+    assert!(oscillation_period < Time::new::<hour>(50.0));
+    assert!(unstable_xenon_mode.damping_ratio < 0.0);
+
+    // a modest prompt negative power coefficient stabilises it
+    let mut damped_xenon_analysis = xenon_analysis.clone();
+    damped_xenon_analysis.feedback_model_list.push(LinearFeedbackModel::FirstOrderLag {
+        reactivity_per_relative_power: Ratio::new::<ratio>(-1.0e-4),
+        time_constant: Time::new::<second>(5.0),
+    });
+
+    let damped_xenon_report = damped_xenon_analysis.analyse().unwrap();
+
+    assert!(damped_xenon_report.is_stable());
+    assert_relative_eq!(damped_xenon_report.power_coefficient.get::<ratio>(),
+        xenon_report.power_coefficient.get::<ratio>() - 1.0e-4, max_relative = 1e-12);
+}
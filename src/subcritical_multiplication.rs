@@ -0,0 +1,186 @@
+use uom::si::f64::*;
+use uom::si::frequency::hertz;
+use uom::si::length::meter;
+use uom::si::ratio::ratio;
+
+use crate::teh_o_prke_error::TehOPrkeError;
+
+/// subcritical multiplication, M = 1/(1 - keff)
+///
+/// a source emitting S neutrons per second in a subcritical core
+/// gives S + kS + k^2 S + ... = S/(1 - k) neutrons per second,
+/// so the detector count rate goes up by a factor of M
+///
+/// keff must be between 0 and 1
+pub fn obtain_subcritical_multiplication(keff: Ratio) -> Result<Ratio, TehOPrkeError> {
+
+    let keff_value = keff.get::<ratio>();
+
+    if !(0.0..1.0).contains(&keff_value) {
+        return Err(TehOPrkeError::GenericStringError(
+            format!("subcritical multiplication needs 0 <= keff < 1, got {}", keff_value)
+        ));
+    }
+
+    Ok(Ratio::new::<ratio>(1.0/(1.0 - keff_value)))
+}
+
+/// equilibrium detector count rate for a source driven subcritical
+/// core
+///
+/// C = C_0 M = C_0/(1 - keff)
+///
+/// where C_0 is the count rate from the source alone (keff = 0)
+///
+/// note that the PRKE source equilibrium, n = - S Lambda/rho,
+/// is S Lambda keff M, since Lambda = l/keff, and l is
+/// the prompt neutron lifetime
+pub fn obtain_equilibrium_count_rate(keff: Ratio,
+    source_count_rate: Frequency) -> Result<Frequency, TehOPrkeError> {
+
+    let subcritical_multiplication = obtain_subcritical_multiplication(keff)?;
+
+    Ok(source_count_rate * subcritical_multiplication)
+}
+
+/// returns the inverse count rate ratio, C_ref/C, at each rod
+/// position, normalised to the first count rate in the list
+///
+/// this is proportional to 1/M = 1 - keff and goes to zero as
+/// the core approaches critical
+///
+/// the list is (rod position, count rate), in the order the
+/// rods were withdrawn
+pub fn obtain_inverse_count_rate_ratio_list(
+    count_rate_list: &[(Length, Frequency)])
+    -> Result<Vec<(Length, Ratio)>, TehOPrkeError> {
+
+    if count_rate_list.is_empty() {
+        return Err(TehOPrkeError::GenericStringError(
+            "count rate list is empty".to_string()
+        ));
+    }
+
+    if count_rate_list.iter().any(|(_, count_rate)| count_rate.get::<hertz>() <= 0.0) {
+        return Err(TehOPrkeError::GenericStringError(
+            "count rates must be positive".to_string()
+        ));
+    }
+
+    let reference_count_rate = count_rate_list[0].1;
+
+    let inverse_count_rate_ratio_list = count_rate_list.iter()
+        .map(|(rod_position, count_rate)| {
+            (*rod_position, reference_count_rate/(*count_rate))
+        })
+        .collect();
+
+    Ok(inverse_count_rate_ratio_list)
+}
+
+/// predicts the critical rod position from counts at several rod
+/// positions (the 1/M plot used in approach to criticality)
+///
+/// the line through the last two points of the inverse count rate
+/// ratio is extrapolated to zero, the prediction gets better as
+/// the rods get closer to critical, so it should be redone after
+/// every withdrawal
+///
+/// the list is (rod position, count rate), simulated or recorded,
+/// in the order the rods were withdrawn. Recorded counts should be
+/// divided by the counting time first
+pub fn predict_critical_rod_position(
+    count_rate_list: &[(Length, Frequency)]) -> Result<Length, TehOPrkeError> {
+
+    if count_rate_list.len() < 2 {
+        return Err(TehOPrkeError::GenericStringError(
+            "need counts at two or more rod positions to extrapolate".to_string()
+        ));
+    }
+
+    let inverse_count_rate_ratio_list =
+        obtain_inverse_count_rate_ratio_list(count_rate_list)?;
+
+    let number_of_points = inverse_count_rate_ratio_list.len();
+    let (previous_position, previous_inverse_ratio) =
+        inverse_count_rate_ratio_list[number_of_points - 2];
+    let (last_position, last_inverse_ratio) =
+        inverse_count_rate_ratio_list[number_of_points - 1];
+
+    let change_in_position = last_position - previous_position;
+    let change_in_inverse_ratio = last_inverse_ratio - previous_inverse_ratio;
+
+    // 1/M has to fall as the rods come out, otherwise the line
+    // never reaches zero
+    if change_in_position.get::<meter>() == 0.0
+        || change_in_inverse_ratio.get::<ratio>() * change_in_position.get::<meter>() >= 0.0 {
+            return Err(TehOPrkeError::GenericStringError(
+                "inverse count rate does not decrease with rod position, \
+                cannot extrapolate to critical".to_string()
+            ));
+    }
+
+    // x_c = x_2 - (1/M)_2 (x_2 - x_1)/((1/M)_2 - (1/M)_1)
+    let critical_rod_position: Length = last_position
+        - (last_inverse_ratio/change_in_inverse_ratio) * change_in_position;
+
+    Ok(critical_rod_position)
+}
+
+#[test]
+pub fn subcritical_multiplication_test(){
+
+    use approx::assert_relative_eq;
+    use uom::si::frequency::hertz;
+    use uom::si::length::centimeter;
+
+    let subcritical_multiplication =
+        obtain_subcritical_multiplication(Ratio::new::<ratio>(0.95)).unwrap();
+
+    assert_relative_eq!(subcritical_multiplication.get::<ratio>(), 20.0,
+        max_relative = 1e-12);
+
+    let count_rate = obtain_equilibrium_count_rate(
+        Ratio::new::<ratio>(0.9),
+        Frequency::new::<hertz>(50.0)).unwrap();
+
+    assert_relative_eq!(count_rate.get::<hertz>(), 500.0, max_relative = 1e-12);
+
+    assert!(obtain_subcritical_multiplication(Ratio::new::<ratio>(1.0)).is_err());
+    assert!(obtain_subcritical_multiplication(Ratio::new::<ratio>(-0.1)).is_err());
+
+    // 1 - keff linear in rod position, critical at 80 cm,
+    // so the extrapolation is exact
+    let source_count_rate = Frequency::new::<hertz>(10.0);
+    let count_rate_list: Vec<(Length, Frequency)> = [0.0, 20.0, 40.0, 60.0]
+        .iter()
+        .map(|position_cm| {
+            let keff = Ratio::new::<ratio>(1.0 - 0.1 * (80.0 - position_cm)/80.0);
+            (Length::new::<centimeter>(*position_cm),
+            obtain_equilibrium_count_rate(keff, source_count_rate).unwrap())
+        })
+        .collect();
+
+    let inverse_count_rate_ratio_list =
+        obtain_inverse_count_rate_ratio_list(&count_rate_list).unwrap();
+
+    assert_relative_eq!(inverse_count_rate_ratio_list[0].1.get::<ratio>(), 1.0);
+    assert_relative_eq!(inverse_count_rate_ratio_list[2].1.get::<ratio>(), 0.5,
+        max_relative = 1e-12);
+
+    let critical_rod_position =
+        predict_critical_rod_position(&count_rate_list).unwrap();
+
+    assert_relative_eq!(critical_rod_position.get::<centimeter>(), 80.0,
+        max_relative = 1e-10);
+
+    // one point cannot be extrapolated, and neither can count
+    // rates that go down as rods come out
+    assert!(predict_critical_rod_position(&count_rate_list[..1]).is_err());
+
+    let mut falling_count_rate_list = count_rate_list.clone();
+    falling_count_rate_list.reverse();
+    falling_count_rate_list.iter_mut().enumerate()
+        .for_each(|(i, point)| point.0 = Length::new::<centimeter>(i as f64));
+    assert!(predict_critical_rod_position(&falling_count_rate_list).is_err());
+}
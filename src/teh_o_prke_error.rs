@@ -3,10 +3,6 @@ use thiserror::Error;
 /// Master Error type of this crate
 #[derive(Debug, Error)]
 pub enum TehOPrkeError {
-    /// linear algebra error
-    #[error("linear algebra error")]
-    LinalgError(#[from] ndarray_linalg::error::LinalgError),
-
     /// empty mass flowrate vector error 
     ///
     /// this case is where the mass flowrate vector in a control 
@@ -28,12 +24,9 @@ impl From<String> for TehOPrkeError {
     }
 }
 
-impl Into<String> for TehOPrkeError {
-    fn into(self) -> String {
-        match self {
-            TehOPrkeError::LinalgError(_) => {
-                self.to_string()
-            },
+impl From<TehOPrkeError> for String {
+    fn from(value: TehOPrkeError) -> Self {
+        match value {
             TehOPrkeError::GenericStringError(string) => {
                 string
             },
@@ -17,4 +17,30 @@ pub mod openfoam_rfk45;
 
 pub mod openfoam_ode_system;
 
+/// ODESolver which owns an ODESystem, and the enum of 
+/// available methods 
 pub mod openfoam_ode_solver;
+
+/// step size control for embedded methods such as RKF45,
+/// translated from OpenFOAM's adaptiveSolver
+pub mod openfoam_adaptive_solver;
+
+/// explicit Euler method
+pub mod openfoam_euler;
+
+/// Rosenbrock solvers, these need the jacobian of the ode system
+#[allow(non_upper_case_globals)]
+pub mod openfoam_rosenbrock12;
+
+#[allow(non_upper_case_globals)]
+pub mod openfoam_rosenbrock23;
+
+#[allow(non_upper_case_globals)]
+pub mod openfoam_rosenbrock34;
+
+/// Rodas solvers, these need the jacobian of the ode system
+#[allow(non_upper_case_globals)]
+pub mod openfoam_rodas23;
+
+#[allow(non_upper_case_globals)]
+pub mod openfoam_rodas34;
@@ -0,0 +1,386 @@
+/*---------------------------------------------------------------------------*\
+  =========                 |
+  \\      /  F ield         | OpenFOAM: The Open Source CFD Toolbox
+   \\    /   O peration     |
+    \\  /    A nd           | www.openfoam.com
+     \\/     M anipulation  |
+-------------------------------------------------------------------------------
+    Copyright (C) 2013-2016 OpenFOAM Foundation
+-------------------------------------------------------------------------------
+License
+    This file is part of OpenFOAM.
+
+    OpenFOAM is free software: you can redistribute it and/or modify it
+    under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    OpenFOAM is distributed in the hope that it will be useful, but WITHOUT
+    ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+    FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+    for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with OpenFOAM.  If not, see <http://www.gnu.org/licenses/>.
+
+\*---------------------------------------------------------------------------*/
+// rust translation of adaptiveSolver.C and the xStart to xEnd
+// loop of ODESolver.C
+use crate::teh_o_prke_error::TehOPrkeError;
+
+/// step size control in the style of OpenFOAM's adaptiveSolver
+///
+/// a single step solver returns the new y and an error vector,
+/// the error is normalised with the absolute and relative
+/// tolerances (as in ODESolver::normalizeError), and the step is
+/// rejected and retried with a smaller step if the normalised
+/// error is more than 1
+#[derive(Debug,Clone,Copy)]
+pub struct AdaptiveSolver {
+    /// absolute tolerance, absTol_ in OpenFOAM
+    pub abs_tol: f64,
+    /// relative tolerance, relTol_ in OpenFOAM
+    pub rel_tol: f64,
+    /// maximum number of accepted steps from x_start to x_end,
+    /// maxSteps_ in OpenFOAM
+    pub max_steps: usize,
+    /// safety factor on the step size estimate, safeScale_
+    pub safe_scale: f64,
+    /// exponent for increasing the step, alphaInc_
+    pub alpha_inc: f64,
+    /// exponent for decreasing the step, alphaDec_
+    pub alpha_dec: f64,
+    /// smallest factor the step may shrink by, minScale_
+    pub min_scale: f64,
+    /// largest factor the step may grow by, maxScale_
+    pub max_scale: f64,
+}
+
+/// default is to use the OpenFOAM defaults
+impl Default for AdaptiveSolver {
+    fn default() -> Self {
+        Self {
+            // SMALL in OpenFOAM
+            abs_tol: 1e-15,
+            rel_tol: 1e-4,
+            max_steps: 10000,
+            safe_scale: 0.9,
+            alpha_inc: 0.2,
+            alpha_dec: 0.25,
+            min_scale: 0.2,
+            max_scale: 10.0,
+        }
+    }
+}
+
+/// result of integrating from x_start to x_end
+#[derive(Debug,Clone)]
+pub struct AdaptiveSolution {
+    /// y at x_end
+    pub y: Vec<f64>,
+    /// estimate for the next step size, this can be passed in as
+    /// dx_try for the next call
+    pub dx_try: f64,
+    /// step sizes which were accepted, in order
+    pub accepted_step_list: Vec<f64>,
+    /// number of steps which were rejected and retried
+    pub number_of_rejected_steps: usize,
+}
+
+impl AdaptiveSolver {
+
+    /// constructs an adaptive solver with the given tolerances and
+    /// OpenFOAM default step size limits
+    pub fn new(abs_tol: f64, rel_tol: f64) -> Self {
+        Self {
+            abs_tol,
+            rel_tol,
+            ..Default::default()
+        }
+    }
+
+    /// maximum of the error over the tolerance
+    ///
+    /// tol_i = absTol + relTol * max(|y0_i|, |y_i|)
+    ///
+    /// a value below 1 means the step is acceptable
+    pub fn normalize_error(&self,
+        y0: &[f64],
+        y: &[f64],
+        err: &[f64]) -> f64 {
+
+        let mut max_err: f64 = 0.0;
+
+        for i in 0..err.len() {
+            let tol = self.abs_tol + self.rel_tol * y0[i].abs().max(y[i].abs());
+            max_err = max_err.max(err[i].abs()/tol);
+        }
+
+        max_err
+    }
+
+    /// takes one accepted step, shrinking dx until the
+    /// error is acceptable, working on buffers so that nothing
+    /// is allocated
+    ///
+    /// x and y are updated, dx_try is updated with the estimate
+    /// for the next step, and the step actually taken is returned
+    /// together with the number of rejected tries
+    ///
+    /// the derivatives take in (x, y, dydx) and fill in dydx,
+    /// the single step solver takes in (x0, y0, dydx0, dx, y, err)
+    /// and fills in y and the unnormalised error vector err
+    pub fn solve_single_step_in_place(&self,
+        x: &mut f64,
+        y: &mut [f64],
+        dx_try: &mut f64,
+        adaptive_solver_buffers: &mut AdaptiveSolverBuffers,
+        derivatives: &impl Fn(f64, &[f64], &mut [f64]),
+        single_step_solver: &mut impl FnMut(f64, &[f64], &[f64], f64, &mut [f64], &mut [f64])
+            -> Result<(), TehOPrkeError>)
+        -> Result<(f64, usize), TehOPrkeError> {
+
+        let AdaptiveSolverBuffers { dydx0_, yTemp_, err_ } = adaptive_solver_buffers;
+
+        let mut dx = *dx_try;
+        let mut number_of_rejected_steps: usize = 0;
+
+        derivatives(*x, y, dydx0_);
+
+        // Loop over solver and adjust step-size as necessary
+        let err = loop {
+
+            // Solve step and provide error estimate
+            single_step_solver(*x, y, dydx0_, dx, yTemp_, err_)?;
+            let err = self.normalize_error(y, yTemp_, err_);
+
+            if err.is_nan() {
+                return Err(TehOPrkeError::GenericStringError(
+                    "error estimate is not a number, check the ode system".to_string()
+                ));
+            }
+
+            if err <= 1.0 {
+                break err;
+            }
+
+            // If error is large reduce dx
+            let scale = (self.safe_scale * err.powf(-self.alpha_dec)).max(self.min_scale);
+            dx *= scale;
+            number_of_rejected_steps += 1;
+
+            if dx.abs() < f64::MIN_POSITIVE {
+                return Err(TehOPrkeError::GenericStringError(
+                    "Stepsize underflow".to_string()
+                ));
+            }
+        };
+
+        // Update the state
+        *x += dx;
+        y.copy_from_slice(yTemp_);
+
+        // If the error is small increase the step-size
+        if err > (self.max_scale/self.safe_scale).powf(-1.0/self.alpha_inc) {
+            let scale = (self.safe_scale * err.powf(-self.alpha_inc)).min(self.max_scale);
+            *dx_try = scale * dx;
+        } else {
+            *dx_try = self.safe_scale * self.max_scale * dx;
+        }
+
+        Ok((dx, number_of_rejected_steps))
+    }
+
+    /// integrates y from x_start to x_end with adaptive steps,
+    /// y is updated in place and the steps work on the buffers
+    /// given (see solve_single_step_in_place)
+    ///
+    /// the last step is truncated so as to land on x_end exactly
+    ///
+    /// dx_try is updated with an estimate for the next step, and 
+    /// the accepted step sizes and number of rejected steps are 
+    /// returned
+    #[allow(clippy::too_many_arguments)]
+    pub fn solve_in_place(&self,
+        x_start: f64,
+        x_end: f64,
+        y: &mut [f64],
+        dx_try: &mut f64,
+        adaptive_solver_buffers: &mut AdaptiveSolverBuffers,
+        derivatives: impl Fn(f64, &[f64], &mut [f64]),
+        mut single_step_solver: impl FnMut(f64, &[f64], &[f64], f64, &mut [f64], &mut [f64])
+            -> Result<(), TehOPrkeError>)
+        -> Result<(Vec<f64>, usize), TehOPrkeError> {
+
+        let mut x = x_start;
+        let mut step_dx_try = *dx_try;
+        let mut accepted_step_list: Vec<f64> = vec![];
+        let mut number_of_rejected_steps: usize = 0;
+
+        for n_step in 0..self.max_steps {
+
+            // Store previous iteration dxTry
+            let dx_try0 = step_dx_try;
+            let mut last = false;
+
+            // Check if this is a truncated step and set dxTry to integrate to xEnd
+            if (x + step_dx_try - x_end) * (x + step_dx_try - x_start) > 0.0 {
+                last = true;
+                step_dx_try = x_end - x;
+            }
+
+            // Integrate as far as possible up to step.dxTry
+            let (dx_did, rejected_steps) = self.solve_single_step_in_place(
+                &mut x,
+                y,
+                &mut step_dx_try,
+                adaptive_solver_buffers,
+                &derivatives,
+                &mut single_step_solver)?;
+
+            // land exactly on xEnd rather than a rounding error away
+            if last && rejected_steps == 0 {
+                x = x_end;
+            }
+
+            accepted_step_list.push(dx_did);
+            number_of_rejected_steps += rejected_steps;
+
+            // Check if reached xEnd
+            if (x - x_end) * (x_end - x_start) >= 0.0 {
+
+                if n_step > 0 && last {
+                    step_dx_try = dx_try0;
+                }
+
+                *dx_try = step_dx_try;
+
+                return Ok((accepted_step_list, number_of_rejected_steps));
+            }
+        }
+
+        Err(TehOPrkeError::GenericStringError(
+            format!("Integration steps greater than maximum {}, \
+                xStart = {}, xEnd = {}, x = {}",
+                self.max_steps, x_start, x_end, x)
+        ))
+    }
+
+}
+
+/// buffers for the adaptive step loop, as held by OpenFOAM's
+/// adaptiveSolver (dydx0_ and yTemp_) together with the error
+/// vector of the single step solver
+#[allow(non_snake_case)]
+#[derive(Debug,Clone,Default)]
+pub struct AdaptiveSolverBuffers {
+    dydx0_: Vec<f64>,
+    yTemp_: Vec<f64>,
+    err_: Vec<f64>,
+}
+
+impl AdaptiveSolverBuffers {
+
+    /// constructs the buffers for n equations
+    pub fn new(n: usize) -> Self {
+        Self {
+            dydx0_: vec![0.0; n],
+            yTemp_: vec![0.0; n],
+            err_: vec![0.0; n],
+        }
+    }
+
+    /// resizes the buffers for n equations, this only allocates
+    /// if n has changed
+    pub fn resize(&mut self, n: usize) {
+        self.dydx0_.resize(n, 0.0);
+        self.yTemp_.resize(n, 0.0);
+        self.err_.resize(n, 0.0);
+    }
+}
+
+
+#[test]
+pub fn rkf45_adaptive_exponential_decay_test(){
+
+    use approx::assert_relative_eq;
+    use crate::time_stepping::openfoam_rfk45::RKF45;
+    use crate::time_stepping::openfoam_ode_system::ODESystem;
+
+    // dy/dx = -y, y(0) = 1, so y(2) = exp(-2)
+    let exponential_decay = ODESystem::new(|_x: f64, y: &[f64], dydx: &mut [f64]| {
+        dydx[0] = -y[0];
+    });
+
+    let adaptive_solver = AdaptiveSolver::new(1e-15, 1e-9);
+
+    let adaptive_solution = RKF45::solve_ode_system_adaptive_stepsize(
+        &exponential_decay, 
+        0.0, 
+        2.0, 
+        vec![1.0], 
+        0.5, 
+        &adaptive_solver).unwrap();
+
+    assert_relative_eq!(adaptive_solution.y[0], (-2.0_f64).exp(), max_relative = 1e-8);
+
+    // accepted steps should add up to the interval
+    let total_step: f64 = adaptive_solution.accepted_step_list.iter().sum();
+    assert_relative_eq!(total_step, 2.0, max_relative = 1e-12);
+
+    // tight tolerance means the first try of 0.5 is rejected
+    assert!(adaptive_solution.number_of_rejected_steps > 0);
+    assert!(adaptive_solution.accepted_step_list.len() > 4);
+
+    // looser tolerances need fewer steps
+    let loose_adaptive_solution = RKF45::solve_ode_system_adaptive_stepsize(
+        &exponential_decay, 
+        0.0, 
+        2.0, 
+        vec![1.0], 
+        0.5, 
+        &AdaptiveSolver::new(1e-15, 1e-4)).unwrap();
+
+    assert!(loose_adaptive_solution.accepted_step_list.len() 
+        < adaptive_solution.accepted_step_list.len());
+
+    // too few steps allowed gives an error
+    let mut restricted_adaptive_solver = adaptive_solver;
+    restricted_adaptive_solver.max_steps = 2;
+
+    assert!(RKF45::solve_ode_system_adaptive_stepsize(
+        &exponential_decay, 
+        0.0, 
+        2.0, 
+        vec![1.0], 
+        0.5, 
+        &restricted_adaptive_solver).is_err());
+}
+
+#[test]
+pub fn rkf45_adaptive_ode_system_test(){
+
+    use approx::assert_relative_eq;
+    use crate::time_stepping::openfoam_rfk45::RKF45;
+    use crate::time_stepping::openfoam_ode_system::ODESystem;
+
+    // simple harmonic oscillator
+    // y0' = y1, y1' = -y0, y(0) = [0,1] so y0 = sin(x)
+    let harmonic_oscillator = |_x: f64, y: &[f64], dydx: &mut [f64]| {
+        dydx[0] = y[1];
+        dydx[1] = -y[0];
+    };
+
+    let odes = ODESystem::new(harmonic_oscillator);
+
+    let adaptive_solution = RKF45::solve_ode_system_adaptive_stepsize(
+        &odes, 
+        0.0, 
+        std::f64::consts::PI/2.0, 
+        vec![0.0, 1.0], 
+        0.1, 
+        &AdaptiveSolver::new(1e-12, 1e-10)).unwrap();
+
+    assert_relative_eq!(adaptive_solution.y[0], 1.0, max_relative = 1e-8);
+    assert!(adaptive_solution.y[1].abs() < 1e-8);
+}
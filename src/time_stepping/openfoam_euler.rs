@@ -0,0 +1,58 @@
+/*---------------------------------------------------------------------------*\
+  =========                 |
+  \\      /  F ield         | OpenFOAM: The Open Source CFD Toolbox
+   \\    /   O peration     |
+    \\  /    A nd           | www.openfoam.com
+     \\/     M anipulation  |
+-------------------------------------------------------------------------------
+    Copyright (C) 2013-2016 OpenFOAM Foundation
+-------------------------------------------------------------------------------
+License
+    This file is part of OpenFOAM.
+
+    OpenFOAM is free software: you can redistribute it and/or modify it
+    under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    OpenFOAM is distributed in the hope that it will be useful, but WITHOUT
+    ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+    FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+    for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with OpenFOAM.  If not, see <http://www.gnu.org/licenses/>.
+
+\*---------------------------------------------------------------------------*/
+// rust translation of Euler.C
+
+/// explicit Euler, first order, with the change in state
+/// as the error estimate
+///
+/// this is the cheapest method, but only first order accurate,
+/// so the adaptive solver will take many small steps
+#[derive(Debug,Clone,Copy)]
+pub struct Euler;
+
+impl Euler {
+
+    /// single Euler step, the new state and the error vector are
+    /// written into y and err
+    #[inline]
+    #[allow(clippy::needless_range_loop)]
+    pub(crate) fn solve_single_step_in_place(
+        y0: &[f64],
+        dydx0: &[f64],
+        dx: f64,
+        y: &mut [f64],
+        err: &mut [f64]) {
+
+        for i in 0..y0.len() {
+            // Calculate error estimate from the change in state:
+            err[i] = dx*dydx0[i];
+
+            // Update the state
+            y[i] = y0[i] + err[i];
+        }
+    }
+}
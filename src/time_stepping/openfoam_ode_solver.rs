@@ -0,0 +1,491 @@
+// rust translation of the ODESolver run time selection in OpenFOAM
+//
+// in OpenFOAM, the ODESolver is chosen from a dictionary at run time,
+// here the method is an enum, so that a whole simulation can switch
+// integrators in one place
+use ndarray::Array2;
+
+use crate::teh_o_prke_error::TehOPrkeError;
+use crate::linear_algebra::lu_decompose_into;
+use super::openfoam_ode_system::ODESystem;
+use super::openfoam_adaptive_solver::{AdaptiveSolver, AdaptiveSolverBuffers};
+use super::openfoam_euler::Euler;
+use super::openfoam_rfk45::RKF45;
+use super::openfoam_rosenbrock12::Rosenbrock12;
+use super::openfoam_rosenbrock23::Rosenbrock23;
+use super::openfoam_rosenbrock34::Rosenbrock34;
+use super::openfoam_rodas23::Rodas23;
+use super::openfoam_rodas34::Rodas34;
+
+/// available ODE solution methods
+///
+/// all of them go through the same adaptive step size control,
+/// the Rosenbrock and Rodas methods are linearly implicit and
+/// need the jacobian of the ode system, so they suit stiff
+/// systems such as the PRKE with short generation times
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum ODESolverMethod {
+    /// explicit Euler, first order
+    Euler,
+    /// Runge-Kutta-Fehlberg, explicit, order 4(5)
+    RKF45,
+    /// L-stable Rosenbrock, order 1(2)
+    Rosenbrock12,
+    /// L-stable Rosenbrock, order 2(3)
+    Rosenbrock23,
+    /// Rosenbrock with Shampine's constants, order 3(4)
+    Rosenbrock34,
+    /// L-stable stiffly accurate Rosenbrock, order 2(3)
+    Rodas23,
+    /// L-stable stiffly accurate Rosenbrock, order 3(4)
+    Rodas34,
+}
+
+impl ODESolverMethod {
+
+    /// returns true for the methods which need a jacobian
+    pub fn needs_jacobian(&self) -> bool {
+        !matches!(self, Self::Euler | Self::RKF45)
+    }
+
+    /// takes one step of size dx of the ODESystem, the new state
+    /// and the unnormalised error vector are written into y and err,
+    /// and the stages work on the workspace, so nothing is allocated
+    ///
+    /// the jacobian is only evaluated for the Rosenbrock and Rodas
+    /// methods
+    #[allow(clippy::too_many_arguments)]
+    pub fn solve_single_step_in_place(&self,
+        odes: &ODESystem,
+        x0: f64,
+        y0: &[f64],
+        dydx0: &[f64],
+        dx: f64,
+        y: &mut [f64],
+        err: &mut [f64],
+        workspace: &mut ODESolverWorkspace) -> Result<(), TehOPrkeError> {
+
+        // the jacobian is evaluated here for all the Rosenbrock 
+        // and Rodas methods, the stages then only read dfdx_ and dfdy_
+        if self.needs_jacobian() {
+            odes.jacobian_with_buffers(x0, y0, 
+                &mut workspace.dfdx_, 
+                &mut workspace.dfdy_, 
+                &mut workspace.dydx_, 
+                &mut workspace.yTemp_);
+        }
+
+        let derivatives = |x: f64, y: &[f64], dydx: &mut [f64]| {
+            odes.derivatives(x, y, dydx);
+        };
+
+        match self {
+            Self::Euler => {
+                Euler::solve_single_step_in_place(y0, dydx0, dx, y, err);
+                Ok(())
+            },
+            Self::RKF45 => {
+                RKF45::solve_single_step_in_place(
+                    x0, y0, dydx0, dx, y, err, &derivatives, workspace);
+                Ok(())
+            },
+            Self::Rosenbrock12 => {
+                Rosenbrock12::solve_single_step_in_place(
+                    x0, y0, dydx0, dx, y, err, &derivatives, workspace)
+            },
+            Self::Rosenbrock23 => {
+                Rosenbrock23::solve_single_step_in_place(
+                    x0, y0, dydx0, dx, y, err, &derivatives, workspace)
+            },
+            Self::Rosenbrock34 => {
+                Rosenbrock34::solve_single_step_in_place(
+                    x0, y0, dydx0, dx, y, err, &derivatives, workspace)
+            },
+            Self::Rodas23 => {
+                Rodas23::solve_single_step_in_place(
+                    x0, y0, dydx0, dx, y, err, &derivatives, workspace)
+            },
+            Self::Rodas34 => {
+                Rodas34::solve_single_step_in_place(
+                    x0, y0, dydx0, dx, y, err, &derivatives, workspace)
+            },
+        }
+    }
+}
+
+/// scratch buffers for the stages of the ODE solution methods,
+/// sized once for n equations so that stepping allocates nothing
+///
+/// in OpenFOAM, each solver holds these as members (k1_, dfdy_,
+/// pivotIndices_ etc.), here one set is shared by all the methods
+/// so that the method can be switched without reallocating
+#[allow(non_snake_case)]
+#[derive(Debug,Clone)]
+pub struct ODESolverWorkspace {
+    pub(crate) yTemp_: Vec<f64>,
+    pub(crate) dydx_: Vec<f64>,
+    pub(crate) dy_: Vec<f64>,
+    pub(crate) k1_: Vec<f64>,
+    pub(crate) k2_: Vec<f64>,
+    pub(crate) k3_: Vec<f64>,
+    pub(crate) k4_: Vec<f64>,
+    pub(crate) k5_: Vec<f64>,
+    pub(crate) k6_: Vec<f64>,
+    pub(crate) dfdx_: Vec<f64>,
+    pub(crate) dfdy_: Array2<f64>,
+    pub(crate) a_: Array2<f64>,
+    pub(crate) pivotIndices_: Vec<usize>,
+}
+
+impl ODESolverWorkspace {
+
+    /// constructs the workspace for n equations
+    pub fn new(n: usize) -> Self {
+        Self {
+            yTemp_: vec![0.0; n],
+            dydx_: vec![0.0; n],
+            dy_: vec![0.0; n],
+            k1_: vec![0.0; n],
+            k2_: vec![0.0; n],
+            k3_: vec![0.0; n],
+            k4_: vec![0.0; n],
+            k5_: vec![0.0; n],
+            k6_: vec![0.0; n],
+            dfdx_: vec![0.0; n],
+            dfdy_: Array2::zeros((n, n)),
+            a_: Array2::zeros((n, n)),
+            pivotIndices_: vec![0; n],
+        }
+    }
+
+    /// returns the number of equations the workspace is sized for
+    pub fn len(&self) -> usize {
+        self.yTemp_.len()
+    }
+
+    /// returns true if the workspace is sized for no equations
+    pub fn is_empty(&self) -> bool {
+        self.yTemp_.is_empty()
+    }
+
+    /// resizes the workspace for n equations, this only allocates
+    /// if n has changed
+    pub fn resize(&mut self, n: usize) {
+        if self.len() != n {
+            *self = Self::new(n);
+        }
+    }
+}
+
+/// ODE solver which owns the ODESystem, as in OpenFOAM
+///
+/// the method and adaptive step size settings are public so they
+/// can be switched in one place for a whole simulation
+///
+/// the solver also owns the scratch buffers for stepping, which 
+/// are sized on the first call to solve
+#[allow(non_snake_case)]
+#[derive(Debug,Clone)]
+pub struct ODESolver {
+    odes_: ODESystem,
+    /// the ODE solution method
+    pub method: ODESolverMethod,
+    /// tolerances and step size limits
+    pub adaptive_solver: AdaptiveSolver,
+    adaptive_solver_buffers_: AdaptiveSolverBuffers,
+    workspace_: ODESolverWorkspace,
+}
+
+impl ODESolver {
+
+    /// constructs an ODESolver with the OpenFOAM default
+    /// tolerances (absTol = SMALL, relTol = 1e-4)
+    pub fn new(odes: ODESystem, method: ODESolverMethod) -> Self {
+        Self::new_with_adaptive_solver(odes, method, AdaptiveSolver::default())
+    }
+
+    /// constructs an ODESolver with the given tolerances
+    pub fn new_with_tolerances(odes: ODESystem,
+        method: ODESolverMethod,
+        abs_tol: f64,
+        rel_tol: f64) -> Self {
+        Self::new_with_adaptive_solver(odes, method, AdaptiveSolver::new(abs_tol, rel_tol))
+    }
+
+    /// constructs an ODESolver with the given step size control
+    pub fn new_with_adaptive_solver(odes: ODESystem,
+        method: ODESolverMethod,
+        adaptive_solver: AdaptiveSolver) -> Self {
+        Self {
+            odes_: odes,
+            method,
+            adaptive_solver,
+            adaptive_solver_buffers_: AdaptiveSolverBuffers::default(),
+            workspace_: ODESolverWorkspace::new(0),
+        }
+    }
+
+    /// returns a reference to the ode system being solved
+    pub fn get_ode_system(&self) -> &ODESystem {
+        &self.odes_
+    }
+
+    /// solves the ODE system from x_start to x_end, y is updated,
+    /// and dx_try is updated with an estimate for the next step
+    ///
+    /// the accepted step sizes are returned
+    ///
+    /// every step works on y and the buffers owned by the solver,
+    /// so after the first call (which sizes the buffers), the ode
+    /// and jacobian evaluations allocate nothing
+    pub fn solve(&mut self,
+        x_start: f64,
+        x_end: f64,
+        y: &mut [f64],
+        dx_try: &mut f64) -> Result<Vec<f64>, TehOPrkeError> {
+
+        let n = y.len();
+        self.adaptive_solver_buffers_.resize(n);
+        self.workspace_.resize(n);
+
+        let odes = &self.odes_;
+        let method = self.method;
+        let workspace = &mut self.workspace_;
+
+        let derivatives = |x: f64, y: &[f64], dydx: &mut [f64]| {
+            odes.derivatives(x, y, dydx);
+        };
+
+        let single_step_solver = 
+            |x0: f64, y0: &[f64], dydx0: &[f64], dx: f64, y: &mut [f64], err: &mut [f64]|
+            -> Result<(), TehOPrkeError> {
+                method.solve_single_step_in_place(
+                    odes, x0, y0, dydx0, dx, y, err, workspace)
+            };
+
+        let (accepted_step_list, _number_of_rejected_steps) = 
+            self.adaptive_solver.solve_in_place(
+                x_start,
+                x_end,
+                y,
+                dx_try,
+                &mut self.adaptive_solver_buffers_,
+                derivatives,
+                single_step_solver)?;
+
+        Ok(accepted_step_list)
+    }
+}
+
+/// constructs and LU decomposes the matrix used in every stage of
+/// the Rosenbrock and Rodas methods, writing into a_ and the pivot
+/// indices given
+///
+/// a_ = I/(gamma dx) - dfdy
+pub(crate) fn construct_rosenbrock_lu_matrix_into(dfdy: &Array2<f64>,
+    gamma: f64,
+    dx: f64,
+    a_: &mut Array2<f64>,
+    pivot_indices: &mut [usize]) -> Result<(), TehOPrkeError> {
+
+    let n = dfdy.nrows();
+
+    a_.zip_mut_with(dfdy, |a_ij, dfdy_ij| *a_ij = -dfdy_ij);
+
+    for i in 0..n {
+        a_[[i, i]] += 1.0/(gamma*dx);
+    }
+
+    lu_decompose_into(a_, pivot_indices)
+}
+
+
+#[test]
+pub fn ode_solver_order_of_accuracy_test(){
+    // single steps on dy/dx = -y (with an x dependent term so that
+    // dfdx is exercised), halving dx should reduce the local
+    // error by about 2^(p+1) for a method of order p
+
+    // dy/dx = -y + x, y(0) = 1
+    // y = x - 1 + 2 exp(-x)
+    //
+    // no analytic jacobian, so the Rosenbrock and Rodas methods
+    // use finite differences
+    let odes = ODESystem::new(|x: f64, y: &[f64], dydx: &mut [f64]| {
+        dydx[0] = -y[0] + x;
+    });
+    let exact_solution = |x: f64| -> f64 {
+        x - 1.0 + 2.0 * (-x).exp()
+    };
+
+    let method_and_order_list = [
+        (ODESolverMethod::Euler, 1.0),
+        (ODESolverMethod::RKF45, 5.0),
+        (ODESolverMethod::Rosenbrock12, 2.0),
+        (ODESolverMethod::Rosenbrock23, 3.0),
+        (ODESolverMethod::Rosenbrock34, 4.0),
+        (ODESolverMethod::Rodas23, 3.0),
+        (ODESolverMethod::Rodas34, 4.0),
+    ];
+
+    for (method, order) in method_and_order_list {
+
+        let local_error = |dx: f64| -> f64 {
+            let y0 = [1.0];
+            let mut dydx0 = [0.0];
+            let mut y = [0.0];
+            let mut err = [0.0];
+            let mut workspace = ODESolverWorkspace::new(1);
+
+            odes.derivatives(0.0, &y0, &mut dydx0);
+            method.solve_single_step_in_place(
+                &odes, 0.0, &y0, &dydx0, dx, &mut y, &mut err, &mut workspace).unwrap();
+            (y[0] - exact_solution(dx)).abs()
+        };
+
+        let dx = match method {
+            // keep clear of round off for the high order method
+            ODESolverMethod::RKF45 => 0.2,
+            _ => 0.05,
+        };
+
+        let observed_order =
+            (local_error(dx)/local_error(0.5*dx)).log2() - 1.0;
+
+        assert!((observed_order - order).abs() < 0.3,
+            "{:?} observed order {} expected {}", method, observed_order, order);
+    }
+}
+
+#[test]
+pub fn ode_solver_stiff_problem_test(){
+    // stiff problem: y' = -k (y - cos x), y(0) = 0, k = 10^4
+    // after the fast transient, y follows cos(x) closely
+    //
+    // the higher order implicit methods should take fewer 
+    // steps than RKF45, which is limited by stability
+
+    use approx::assert_relative_eq;
+
+    const STIFFNESS: f64 = 1.0e4;
+
+    let stiff_ode = |x: f64, y: &[f64], dydx: &mut [f64]| {
+        dydx[0] = -STIFFNESS * (y[0] - x.cos());
+    };
+
+    // dfdx = -k sin x, dfdy = -k
+    let stiff_ode_jacobian = |x: f64, _y: &[f64], dfdx: &mut [f64], dfdy: &mut Array2<f64>| {
+        dfdx[0] = -STIFFNESS * x.sin();
+        dfdy[[0, 0]] = -STIFFNESS;
+    };
+
+    // y = (k^2 cos x + k sin x)/(k^2 + 1) + C exp(-k x)
+    let exact_solution_at_one =
+        (STIFFNESS.powi(2) * 1.0_f64.cos() + STIFFNESS * 1.0_f64.sin())
+        /(STIFFNESS.powi(2) + 1.0);
+
+    let mut number_of_steps_list: Vec<(ODESolverMethod, usize)> = vec![];
+
+    for method in [
+        ODESolverMethod::RKF45,
+        ODESolverMethod::Rosenbrock12,
+        ODESolverMethod::Rosenbrock23,
+        ODESolverMethod::Rosenbrock34,
+        ODESolverMethod::Rodas23,
+        ODESolverMethod::Rodas34] {
+
+        let mut ode_solver = ODESolver::new_with_tolerances(
+            ODESystem::new_with_jacobian(stiff_ode, stiff_ode_jacobian), method, 1e-10, 1e-6);
+
+        let mut y = vec![0.0];
+        let mut dx_try = 1e-4;
+
+        let accepted_step_list = ode_solver.solve(0.0, 1.0, &mut y, &mut dx_try).unwrap();
+
+        assert_relative_eq!(y[0], exact_solution_at_one, max_relative = 1e-4);
+
+        number_of_steps_list.push((method, accepted_step_list.len()));
+    }
+
+    let rkf45_steps = number_of_steps_list[0].1;
+
+    // Rosenbrock12 is only second order, so at this tolerance 
+    // it takes more steps than RKF45 even though it is stable
+    for (method, number_of_steps) in number_of_steps_list[2..].iter() {
+        assert!(*number_of_steps < rkf45_steps,
+            "{:?} took {} steps, RKF45 took {}", method, number_of_steps, rkf45_steps);
+    }
+}
+
+#[test]
+pub fn ode_solver_analytic_and_finite_difference_jacobian_test(){
+    // the ODESolver should give the same steps and answer whether
+    // the jacobian is analytic or from finite differences, and the
+    // buffers should be reusable for the next call
+
+    use approx::assert_relative_eq;
+
+    // dy0/dx = -y0 + x, dy1/dx = y0 - 2 y1, y(0) = [1, 0]
+    // y0 = x - 1 + 2 exp(-x)
+    // y1 = x/2 - 3/4 + 2 exp(-x) - 5/4 exp(-2x)
+    let derivatives = |x: f64, y: &[f64], dydx: &mut [f64]| {
+        dydx[0] = -y[0] + x;
+        dydx[1] = y[0] - 2.0 * y[1];
+    };
+    let jacobian = |_x: f64, _y: &[f64], dfdx: &mut [f64], dfdy: &mut Array2<f64>| {
+        dfdx[0] = 1.0;
+        dfdx[1] = 0.0;
+        dfdy.fill(0.0);
+        dfdy[[0, 0]] = -1.0;
+        dfdy[[1, 0]] = 1.0;
+        dfdy[[1, 1]] = -2.0;
+    };
+    let exact_solution = |x: f64| -> [f64;2] {
+        [x - 1.0 + 2.0 * (-x).exp(),
+        0.5 * x - 0.75 + 2.0 * (-x).exp() - 1.25 * (-2.0 * x).exp()]
+    };
+
+    // loose enough for Euler to finish in a reasonable number of steps
+    let adaptive_solver = AdaptiveSolver::new(1e-4, 1e-4);
+
+    for method in [
+        ODESolverMethod::Euler,
+        ODESolverMethod::RKF45,
+        ODESolverMethod::Rosenbrock12,
+        ODESolverMethod::Rosenbrock23,
+        ODESolverMethod::Rosenbrock34,
+        ODESolverMethod::Rodas23,
+        ODESolverMethod::Rodas34] {
+
+        let mut solution_list: Vec<(usize, [f64;2])> = vec![];
+
+        for odes in [ODESystem::new(derivatives), 
+            ODESystem::new_with_jacobian(derivatives, jacobian)] {
+
+            let mut ode_solver = ODESolver::new_with_adaptive_solver(
+                odes, method, adaptive_solver);
+
+            let mut y = vec![1.0, 0.0];
+            let mut dx_try = 0.1;
+            let mut number_of_steps = ode_solver.solve(0.0, 1.0, &mut y, &mut dx_try)
+                .unwrap().len();
+
+            // carry on with the same buffers
+            number_of_steps += ode_solver.solve(1.0, 2.0, &mut y, &mut dx_try)
+                .unwrap().len();
+
+            let y_exact = exact_solution(2.0);
+            assert_relative_eq!(y[0], y_exact[0], max_relative = 1e-2);
+            assert_relative_eq!(y[1], y_exact[1], max_relative = 1e-2);
+
+            solution_list.push((number_of_steps, [y[0], y[1]]));
+        }
+
+        let (finite_difference_steps, finite_difference_y) = solution_list[0];
+        let (analytic_steps, analytic_y) = solution_list[1];
+
+        assert_eq!(finite_difference_steps, analytic_steps, "{:?}", method);
+        assert_relative_eq!(finite_difference_y[0], analytic_y[0], max_relative = 1e-6);
+        assert_relative_eq!(finite_difference_y[1], analytic_y[1], max_relative = 1e-6);
+    }
+}
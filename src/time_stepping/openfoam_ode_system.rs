@@ -1,54 +1,237 @@
+use std::fmt;
+use std::sync::Arc;
+
+use ndarray::Array2;
+
+/// derivative function of an ode system, takes in x and y
+/// and writes dydx into the buffer given
+///
+/// this is a closure, so it can carry its own parameters
+/// (eg. reactivity and generation time for the PRKE)
+pub type DerivativeFunction = Arc<dyn Fn(f64, &[f64], &mut [f64]) + Send + Sync>;
+
+/// analytic jacobian of an ode system, takes in x and y
+/// and writes dfdx and dfdy into the buffers given
+pub type JacobianFunction = Arc<dyn Fn(f64, &[f64], &mut [f64], &mut Array2<f64>) + Send + Sync>;
+
 /// rust translation of the OpenFOAM ODE system
 ///
 /// note that this is nested inside ODESolver struct
+///
+/// the closures are reference counted so that the ODESystem
+/// (and the solvers owning it) can still be cloned and sent
+/// across threads
 #[allow(non_snake_case)]
-#[derive(Debug,Clone)]
+#[derive(Clone)]
 pub struct ODESystem {
-    /// takes in two vectors, x and y 
-    /// and then outputs dydx
-    user_specified_ode_system: fn(f64, &Vec<f64>) -> Vec<f64>
+    /// takes in x and y
+    /// and then writes dydx
+    user_specified_ode_system: DerivativeFunction,
+    /// optional analytic jacobian returning (dfdx, dfdy),
+    /// finite differences are used if this is not given
+    user_specified_jacobian: Option<JacobianFunction>,
 }
 
+impl fmt::Debug for ODESystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ODESystem")
+            .field("has_analytic_jacobian", &self.has_analytic_jacobian())
+            .finish()
+    }
+}
 
 
 impl ODESystem {
 
-    /// constructor for ODE system 
-    /// probably want some easier ways to construct the ode system
-    pub fn new(ode_system: fn(f64, &Vec<f64>) -> Vec<f64>) -> Self
+    /// constructor for ODE system
+    ///
+    /// the closure takes in (x, y, dydx) and fills in dydx,
+    /// any parameters it needs can be moved into it
+    pub fn new(ode_system: impl Fn(f64, &[f64], &mut [f64]) + Send + Sync + 'static) -> Self
     {
         Self {
-            user_specified_ode_system: ode_system
+            user_specified_ode_system: Arc::new(ode_system),
+            user_specified_jacobian: None,
         }
     }
 
+    /// constructor for ODE system with an analytic jacobian,
+    /// which the Rosenbrock and Rodas solvers will use instead
+    /// of finite differences
+    ///
+    /// the jacobian closure takes in (x, y, dfdx, dfdy) and
+    /// fills in dfdx and dfdy
+    pub fn new_with_jacobian(
+        ode_system: impl Fn(f64, &[f64], &mut [f64]) + Send + Sync + 'static,
+        jacobian: impl Fn(f64, &[f64], &mut [f64], &mut Array2<f64>) + Send + Sync + 'static)
+        -> Self
+    {
+        Self {
+            user_specified_ode_system: Arc::new(ode_system),
+            user_specified_jacobian: Some(Arc::new(jacobian)),
+        }
+    }
 
-    /// this evaluates a vector dydx based on a vector y and 
+    /// returns true if an analytic jacobian was given
+    pub fn has_analytic_jacobian(&self) -> bool {
+        self.user_specified_jacobian.is_some()
+    }
+
+
+    /// this evaluates a vector dydx based on a vector y and
     /// scalar coordinate x
+    ///
+    /// dydx is written into the buffer given, so nothing is
+    /// allocated
     pub fn derivatives(&self,
         x: f64,
-        y: &Vec<f64>,
-        dydx: &mut Vec<f64>) {
+        y: &[f64],
+        dydx: &mut [f64]) {
+
+        (self.user_specified_ode_system)(x, y, dydx);
+
+    }
+
+    /// this evaluates the jacobian of the ode system, ie.
+    /// dfdx (the derivative of dydx with respect to x) and
+    /// dfdy (the matrix of derivatives of dydx_i with respect to y_j)
+    ///
+    /// this is needed for the Rosenbrock and Rodas solvers, if no
+    /// analytic jacobian was given, it is estimated using
+    /// finite differences, which needs two scratch vectors, use
+    /// jacobian_with_buffers to supply them
+    pub fn jacobian(&self,
+        x: f64,
+        y: &[f64],
+        dfdx: &mut [f64],
+        dfdy: &mut Array2<f64>) {
+
+        match &self.user_specified_jacobian {
+            Some(jacobian) => jacobian(x, y, dfdx, dfdy),
+            None => {
+                let mut dydx: Vec<f64> = vec![0.0; y.len()];
+                let mut y_perturbed: Vec<f64> = vec![0.0; y.len()];
+
+                self.jacobian_with_buffers(x, y, dfdx, dfdy, &mut dydx, &mut y_perturbed);
+            },
+        }
+    }
+
+    /// same as jacobian, but the finite differences (if there
+    /// is no analytic jacobian) use the scratch vectors given, 
+    /// dydx and y_perturbed, which must be as long as y
+    ///
+    /// nothing is allocated, so the ODE solvers use this
+    pub fn jacobian_with_buffers(&self,
+        x: f64,
+        y: &[f64],
+        dfdx: &mut [f64],
+        dfdy: &mut Array2<f64>,
+        dydx: &mut [f64],
+        y_perturbed: &mut [f64]) {
+
+        if let Some(jacobian) = &self.user_specified_jacobian {
+            jacobian(x, y, dfdx, dfdy);
+            return;
+        }
+
+        // forward differences, each perturbation is scaled with the
+        // size of the variable being perturbed, so that variables of
+        // very different magnitudes (eg neutron population and 
+        // precursor concentrations) are handled alike
+        let n = y.len();
+        let sqrt_epsilon = f64::EPSILON.sqrt();
+        self.derivatives(x, y, dydx);
+
+        // dfdy, one column at a time, with dfdx holding the 
+        // perturbed derivatives until it is needed
+        y_perturbed.copy_from_slice(y);
+
+        for j in 0..n {
+            let y_perturbation = sqrt_epsilon * y[j].abs().max(sqrt_epsilon);
+            y_perturbed[j] = y[j] + y_perturbation;
+
+            self.derivatives(x, y_perturbed, dfdx);
 
-        let dydx_local = (self.user_specified_ode_system)(x,y);
+            for i in 0..n {
+                dfdy[[i, j]] = (dfdx[i] - dydx[i])/y_perturbation;
+            }
 
-        *dydx = dydx_local;
+            y_perturbed[j] = y[j];
+        }
 
+        // dfdx
+        let x_perturbation = sqrt_epsilon * x.abs().max(1.0);
+        self.derivatives(x + x_perturbation, y, dfdx);
 
+        for i in 0..n {
+            dfdx[i] = (dfdx[i] - dydx[i])/x_perturbation;
+        }
     }
 
-    /// this evaluates a vector dydx based on a vector y and 
+    /// this evaluates a vector dydx based on a vector y and
     /// scalar coordinate x
     pub fn derivatives_with_fn(
         ode_system: impl Fn(f64, &Vec<f64>) -> Vec<f64>,
         x: f64,
         y: &Vec<f64>,) -> Vec<f64> {
 
-        let dydx = (ode_system)(x,y);
+        (ode_system)(x,y)
+    }
+
+}
 
-        return dydx;
 
+#[test]
+pub fn ode_system_closure_with_parameters_and_jacobian_test(){
+    // the decay constant is carried by the closure rather
+    // than a global, and both the analytic and finite
+    // difference jacobians should agree
 
-    }
+    use approx::assert_relative_eq;
 
+    let decay_constant = 0.5;
+    let source = 2.0;
+
+    // dy0/dx = -lambda y0 + s x, dy1/dx = lambda y0
+    let derivatives = move |x: f64, y: &[f64], dydx: &mut [f64]| {
+        dydx[0] = -decay_constant * y[0] + source * x;
+        dydx[1] = decay_constant * y[0];
+    };
+
+    let jacobian = move |_x: f64, _y: &[f64], dfdx: &mut [f64], dfdy: &mut Array2<f64>| {
+        dfdx[0] = source;
+        dfdx[1] = 0.0;
+        dfdy.fill(0.0);
+        dfdy[[0, 0]] = -decay_constant;
+        dfdy[[1, 0]] = decay_constant;
+    };
+
+    let odes = ODESystem::new(derivatives);
+    let odes_with_jacobian = ODESystem::new_with_jacobian(derivatives, jacobian);
+
+    assert!(!odes.has_analytic_jacobian());
+    assert!(odes_with_jacobian.has_analytic_jacobian());
+
+    let y = [4.0, 1.0];
+    let mut dydx = [0.0; 2];
+    odes.derivatives(1.0, &y, &mut dydx);
+
+    assert_relative_eq!(dydx[0], 0.0);
+    assert_relative_eq!(dydx[1], 2.0);
+
+    let mut dfdx = [0.0; 2];
+    let mut dfdy: Array2<f64> = Array2::zeros((2, 2));
+    let mut dfdx_analytic = [0.0; 2];
+    let mut dfdy_analytic: Array2<f64> = Array2::zeros((2, 2));
+
+    odes.jacobian(1.0, &y, &mut dfdx, &mut dfdy);
+    odes_with_jacobian.jacobian(1.0, &y, &mut dfdx_analytic, &mut dfdy_analytic);
+
+    for i in 0..2 {
+        assert_relative_eq!(dfdx[i], dfdx_analytic[i], epsilon = 1e-6);
+        for j in 0..2 {
+            assert_relative_eq!(dfdy[[i, j]], dfdy_analytic[[i, j]], epsilon = 1e-6);
+        }
+    }
 }
@@ -1,4 +1,7 @@
 use super::openfoam_ode_system::ODESystem;
+use super::openfoam_adaptive_solver::{AdaptiveSolver, AdaptiveSolution, AdaptiveSolverBuffers};
+use super::openfoam_ode_solver::ODESolverWorkspace;
+use crate::teh_o_prke_error::TehOPrkeError;
 
 /*---------------------------------------------------------------------------*\
   =========                 |
@@ -64,20 +67,16 @@ const e5  : f64 = -1.0/5.0 - b5;
 const e6  : f64 = -b6;
 
 
-/// note: need a verification test too
-
+/// verified against the point kinetics benchmarks
+/// (see zero_power_prke/benchmarks.rs), both as a fixed
+/// step and an adaptive solver
 #[allow(non_snake_case)]
 #[derive(Debug,Clone)]
 pub struct RKF45 {
-    yTemp_: Vec<f64>,
-    k2_: Vec<f64>,
-    k3_: Vec<f64>,
-    k4_: Vec<f64>,
-    k5_: Vec<f64>,
-    k6_: Vec<f64>,
+    // yTemp_ and k2_ to k6_ live in the workspace, which the
+    // ODESolverMethod::RKF45 stepping shares
+    workspace_: ODESolverWorkspace,
     err_: Vec<f64>,
-    // i'll need to make way for a system of ODEs
-    //
 
     odes_: ODESystem,
 }
@@ -88,24 +87,71 @@ pub struct RKF45 {
 #[allow(non_snake_case)]
 impl RKF45 {
 
+    /// constructs the RKF45 solver for an ODESystem with 
+    /// n equations
+    pub fn new(odes: ODESystem, n: usize) -> Self {
+        Self {
+            workspace_: ODESolverWorkspace::new(n),
+            err_: vec![0.0; n],
+            odes_: odes,
+        }
+    }
+
+    /// returns the error estimate from the last call to solve
+    pub fn get_error_estimate(&self) -> &Vec<f64> {
+        &self.err_
+    }
+
+    /// single RKF45 step of the ODESystem, y0 and dydx0 are not
+    /// changed and the 5th order solution is written into y
+    ///
+    /// the error estimate is kept, see get_error_estimate
     #[inline]
     pub fn solve(&mut self, 
         x0: f64, 
-        y0: Vec<f64>,
-        dydx0: Vec<f64>,
+        y0: &[f64],
+        dydx0: &[f64],
+        dx: f64,
+        y: &mut [f64],){
+
+        let odes = &self.odes_;
+        let derivatives = |x: f64, y: &[f64], dydx: &mut [f64]| {
+            odes.derivatives(x, y, dydx);
+        };
+
+        Self::solve_single_step_in_place(
+            x0, y0, dydx0, dx, y, &mut self.err_, &derivatives, &mut self.workspace_);
+    }
+
+    /// single RKF45 step, the 5th order solution and the error 
+    /// vector are written into y and err, and the stages use 
+    /// yTemp_ and k2_ to k6_ from the workspace
+    #[inline]
+    #[allow(clippy::needless_range_loop)]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn solve_single_step_in_place(
+        x0: f64, 
+        y0: &[f64],
+        dydx0: &[f64],
         dx: f64,
-        y: &mut Vec<f64>,){
+        y: &mut [f64],
+        err: &mut [f64],
+        derivatives: &impl Fn(f64, &[f64], &mut [f64]),
+        workspace: &mut ODESolverWorkspace){
 
 
-        let yTemp_ = self.yTemp_.clone();
+        // the derivatives must be evaluated at the updated yTemp_,
+        // so there is no local copy of yTemp_ here
+        let n = y0.len();
+        let ODESolverWorkspace { yTemp_, k2_, k3_, k4_, k5_, k6_, .. } = workspace;
 
         // note, in the RKF45, there is k1_
         //
         // but k1_ in this case is just dydx0
         // ie f(x,y)
 
-        for (i,_yTemp) in yTemp_.iter().enumerate() {
-            self.yTemp_[i] = y0[i] + a21*dx*dydx0[i];
+        for i in 0..n {
+            yTemp_[i] = y0[i] + a21*dx*dydx0[i];
         }
 
         //note: it appears odes_ is a reference to the ODE system 
@@ -115,7 +161,7 @@ impl RKF45 {
         //
         // question is what does the derivatives method do?
 
-        self.odes_.derivatives(x0 + c2*dx, &yTemp_, &mut self.k2_);
+        derivatives(x0 + c2*dx, yTemp_, k2_);
         //
         // //- Calculate the derivatives in dydx
         // virtual void derivatives
@@ -134,39 +180,39 @@ impl RKF45 {
         // x = x0 + c2*dx
         // y = yTemp_
         //
-        // and in this case, storing it in self.k2_
+        // and in this case, storing it in k2_
 
         // forAll(yTemp_, i)
         // {
         //     yTemp_[i] = y0[i] + dx*(a31*dydx0[i] + a32*k2_[i]);
         // }
-        for (i,_yTemp) in yTemp_.iter().enumerate() {
-            self.yTemp_[i] = y0[i] + dx*(a31*dydx0[i] + a32*self.k2_[i]);
+        for i in 0..n {
+            yTemp_[i] = y0[i] + dx*(a31*dydx0[i] + a32*k2_[i]);
         }
 
-        self.odes_.derivatives(x0 + c3*dx, &yTemp_, &mut self.k3_);
+        derivatives(x0 + c3*dx, yTemp_, k3_);
 
         // forAll(yTemp_, i)
         // {
         //     yTemp_[i] = y0[i] + dx*(a41*dydx0[i] + a42*k2_[i] + a43*k3_[i]);
         // }
-        for (i,_yTemp) in yTemp_.iter().enumerate() {
-            self.yTemp_[i] = y0[i] + dx*(a41*dydx0[i] + a42*self.k2_[i] + a43*self.k3_[i]);
+        for i in 0..n {
+            yTemp_[i] = y0[i] + dx*(a41*dydx0[i] + a42*k2_[i] + a43*k3_[i]);
         }
 
-        self.odes_.derivatives(x0 + c4*dx, &yTemp_, &mut self.k4_);
+        derivatives(x0 + c4*dx, yTemp_, k4_);
 
         // forAll(yTemp_, i)
         // {
         //     yTemp_[i] = y0[i]
         //         + dx*(a51*dydx0[i] + a52*k2_[i] + a53*k3_[i] + a54*k4_[i]);
         // }
-        for (i,_yTemp) in yTemp_.iter().enumerate() {
-            self.yTemp_[i] = y0[i]
-                + dx*(a51*dydx0[i] + a52*self.k2_[i] + a53*self.k3_[i] + a54*self.k4_[i]);
+        for i in 0..n {
+            yTemp_[i] = y0[i]
+                + dx*(a51*dydx0[i] + a52*k2_[i] + a53*k3_[i] + a54*k4_[i]);
         }
 
-        self.odes_.derivatives(x0 + c5*dx, &yTemp_, &mut self.k5_);
+        derivatives(x0 + c5*dx, yTemp_, k5_);
 
         // forAll(yTemp_, i)
         // {
@@ -174,16 +220,16 @@ impl RKF45 {
         //         + dx
         //         *(a61*dydx0[i] + a62*k2_[i] + a63*k3_[i] + a64*k4_[i] + a65*k5_[i]);
         // }
-        for (i,_yTemp) in yTemp_.iter().enumerate() {
-            self.yTemp_[i] = y0[i]
+        for i in 0..n {
+            yTemp_[i] = y0[i]
                 + dx*(
-                    a61*dydx0[i] + a62*self.k2_[i] + a63*self.k3_[i] 
-                    + a64*self.k4_[i] + a65*self.k5_[i]
+                    a61*dydx0[i] + a62*k2_[i] + a63*k3_[i] 
+                    + a64*k4_[i] + a65*k5_[i]
                 );
         }
 
 
-        self.odes_.derivatives(x0 + c6*dx, &yTemp_, &mut self.k6_);
+        derivatives(x0 + c6*dx, yTemp_, k6_);
 
         // // Calculate the 5th-order solution
         // forAll(y, i)
@@ -193,12 +239,12 @@ impl RKF45 {
         //        *(b1*dydx0[i] + b3*k3_[i] + b4*k4_[i] + b5*k5_[i] + b6*k6_[i]);
         // }
 
-        for (i,_yTemp) in yTemp_.iter().enumerate() {
+        for i in 0..n {
 
             y[i] = y0[i]
                 + dx*(
-                    b1*dydx0[i] + b3*self.k3_[i] + b4*self.k4_[i] 
-                    + b5*self.k5_[i] + b6*self.k6_[i]
+                    b1*dydx0[i] + b3*k3_[i] + b4*k4_[i] 
+                    + b5*k5_[i] + b6*k6_[i]
                 );
         }
         // // Calculate the error estimate from the difference between the
@@ -209,12 +255,12 @@ impl RKF45 {
         //         dx
         //        *(e1*dydx0[i] + e3*k3_[i] + e4*k4_[i] + e5*k5_[i] + e6*k6_[i]);
         // }
-        for (i,_yTemp) in yTemp_.iter().enumerate() {
+        for i in 0..n {
 
-            self.err_[i] =
+            err[i] =
                 dx
-                *(e1*dydx0[i] + e3*self.k3_[i] + e4*self.k4_[i] 
-                    + e5*self.k5_[i] + e6*self.k6_[i]);
+                *(e1*dydx0[i] + e3*k3_[i] + e4*k4_[i] 
+                    + e5*k5_[i] + e6*k6_[i]);
         }
 
         // return normalizeError(y0, y, err_);
@@ -228,6 +274,10 @@ impl RKF45 {
     /// approach
     /// ie, you need to define the function which returns
     ///
+    /// this is a thin adapter over solve_single_step_in_place,
+    /// since the function returns a Vec, every stage allocates,
+    /// so for anything stepped many times, use an ODESystem
+    /// with RKF45::solve or ODESolver instead
     #[inline]
     pub fn solve_functional_prog_single_stepsize_no_stepsize_adjust( 
         x0: f64, 
@@ -235,139 +285,73 @@ impl RKF45 {
         dx: f64,
         user_defined_ode: impl Fn(f64, &Vec<f64>) -> Vec<f64>) -> Vec<f64>{
 
-        let k2_: Vec<f64>;
-        let k3_: Vec<f64>;
-        let k4_: Vec<f64>;
-        let k5_: Vec<f64>;
-        let k6_: Vec<f64>;
-        let mut err_: Vec<f64> = y0.clone();
-        let mut yTemp_ = y0.clone();
-        let mut y = y0.clone();
-
         // note, in the RKF45, there is k1_
         //
         // but k1_ in this case is just dydx0
         // ie f(x,y)
 
+        let n = y0.len();
         let dydx0: Vec<f64> = user_defined_ode(x0,&y0);
+        let mut workspace = ODESolverWorkspace::new(n);
+        let mut y: Vec<f64> = vec![0.0; n];
+        let mut err_: Vec<f64> = vec![0.0; n];
 
-        for (i,yTemp) in yTemp_.iter_mut().enumerate() {
-            *yTemp = y0[i] + a21*dx*dydx0[i];
-        }
-
-        //note: it appears odes_ is a reference to the ODE system 
-        //in ODESolver.H
-        // in ODESolver.H, we find that the ode_ is a pointer to 
-        // the ODESystem class
-        //
-        // question is what does the derivatives method do?
-        
-        k2_ = user_defined_ode(x0 + c2*dx, &yTemp_);
-        //
-        // //- Calculate the derivatives in dydx
-        // virtual void derivatives
-        // (
-        //     const scalar x,
-        //     const scalarField& y,
-        //     scalarField& dydx
-        // ) const = 0;
-        
-        // it seems the method isn't properly derived. 
-        //
-        // But, yes. RKF45 after all is a method, the derivatives themselves 
-        // are user defined.
-        //
-        // so this is evaluating the system of derivatives at 
-        // x = x0 + c2*dx
-        // y = yTemp_
-        //
-        // and in this case, storing it in k2_
-
-        // forAll(yTemp_, i)
-        // {
-        //     yTemp_[i] = y0[i] + dx*(a31*dydx0[i] + a32*k2_[i]);
-        // }
-        for (i,yTemp) in yTemp_.iter_mut().enumerate() {
-            *yTemp = y0[i] + dx*(a31*dydx0[i] + a32*k2_[i]);
-        }
-
-        k3_ = user_defined_ode(x0 + c3*dx, &yTemp_);
-
-        // forAll(yTemp_, i)
-        // {
-        //     yTemp_[i] = y0[i] + dx*(a41*dydx0[i] + a42*k2_[i] + a43*k3_[i]);
-        // }
-        for (i,yTemp) in yTemp_.iter_mut().enumerate() {
-            *yTemp = y0[i] + dx*(a41*dydx0[i] + a42*k2_[i] + a43*k3_[i]);
-        }
-
-        k4_ = user_defined_ode(x0 + c4*dx, &yTemp_);
-
-        // forAll(yTemp_, i)
-        // {
-        //     *_yTemp = y0[i]
-        //         + dx*(a51*dydx0[i] + a52*k2_[i] + a53*k3_[i] + a54*k4_[i]);
-        // }
-        for (i,yTemp) in yTemp_.iter_mut().enumerate() {
-            *yTemp = y0[i]
-                + dx*(a51*dydx0[i] + a52*k2_[i] + a53*k3_[i] + a54*k4_[i]);
-        }
-
-        k5_ = user_defined_ode(x0 + c5*dx, &yTemp_);
-
-        // forAll(yTemp_, i)
-        // {
-        //     *_yTemp = y0[i]
-        //         + dx
-        //         *(a61*dydx0[i] + a62*k2_[i] + a63*k3_[i] + a64*k4_[i] + a65*k5_[i]);
-        // }
-        for (i,yTemp) in yTemp_.iter_mut().enumerate() {
-            *yTemp = y0[i]
-                + dx*(
-                    a61*dydx0[i] + a62*k2_[i] + a63*k3_[i] 
-                    + a64*k4_[i] + a65*k5_[i]
-                );
-        }
-
-
-        k6_ = user_defined_ode(x0 + c6*dx, &yTemp_);
-
-        // // Calculate the 5th-order solution
-        // forAll(y, i)
-        // {
-        //     y[i] = y0[i]
-        //       + dx
-        //        *(b1*dydx0[i] + b3*k3_[i] + b4*k4_[i] + b5*k5_[i] + b6*k6_[i]);
-        // }
-
-        for (i,_yTemp) in yTemp_.iter_mut().enumerate() {
-
-            y[i] = y0[i]
-                + dx*(
-                    b1*dydx0[i] + b3*k3_[i] + b4*k4_[i] 
-                    + b5*k5_[i] + b6*k6_[i]
-                );
-        }
-        // // Calculate the error estimate from the difference between the
-        // // 4th-order and 5th-order solutions
-        // forAll(err_, i)
-        // {
-        //     err_[i] =
-        //         dx
-        //        *(e1*dydx0[i] + e3*k3_[i] + e4*k4_[i] + e5*k5_[i] + e6*k6_[i]);
-        // }
-        for (i,_yTemp) in yTemp_.iter_mut().enumerate() {
+        let derivatives = |x: f64, y: &[f64], dydx: &mut [f64]| {
+            dydx.copy_from_slice(&user_defined_ode(x, &y.to_vec()));
+        };
 
-            err_[i] =
-                dx
-                *(e1*dydx0[i] + e3*k3_[i] + e4*k4_[i] 
-                    + e5*k5_[i] + e6*k6_[i]);
-        }
-
-        // return normalizeError(y0, y, err_);
+        Self::solve_single_step_in_place(
+            x0, &y0, &dydx0, dx, &mut y, &mut err_, &derivatives, &mut workspace);
 
         // returns the vector at next timestep
-        return y;
+        y
+    }
 
+    /// integrates an ODESystem from x_start to x_end using RKF45 
+    /// with adaptive step size control
+    ///
+    /// the buffers are allocated once here, the steps themselves
+    /// allocate nothing
+    pub fn solve_ode_system_adaptive_stepsize(
+        odes: &ODESystem,
+        x_start: f64,
+        x_end: f64,
+        y0: Vec<f64>,
+        dx_try: f64,
+        adaptive_solver: &AdaptiveSolver) -> Result<AdaptiveSolution, TehOPrkeError> {
+
+        let n = y0.len();
+        let mut y = y0;
+        let mut step_dx_try = dx_try;
+        let mut adaptive_solver_buffers = AdaptiveSolverBuffers::new(n);
+        let mut workspace = ODESolverWorkspace::new(n);
+
+        let derivatives = |x: f64, y: &[f64], dydx: &mut [f64]| {
+            odes.derivatives(x, y, dydx);
+        };
+
+        let single_step_solver = 
+            |x0: f64, y0: &[f64], dydx0: &[f64], dx: f64, y: &mut [f64], err: &mut [f64]|
+            -> Result<(), TehOPrkeError> {
+                Self::solve_single_step_in_place(
+                    x0, y0, dydx0, dx, y, err, &derivatives, &mut workspace);
+                Ok(())
+            };
+
+        let (accepted_step_list, number_of_rejected_steps) = adaptive_solver.solve_in_place(
+            x_start, 
+            x_end, 
+            &mut y, 
+            &mut step_dx_try, 
+            &mut adaptive_solver_buffers, 
+            derivatives, 
+            single_step_solver)?;
+
+        Ok(AdaptiveSolution {
+            y,
+            dx_try: step_dx_try,
+            accepted_step_list,
+            number_of_rejected_steps,
+        })
     }
 }
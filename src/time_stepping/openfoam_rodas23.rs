@@ -0,0 +1,123 @@
+/*---------------------------------------------------------------------------*\
+  =========                 |
+  \\      /  F ield         | OpenFOAM: The Open Source CFD Toolbox
+   \\    /   O peration     |
+    \\  /    A nd           | www.openfoam.com
+     \\/     M anipulation  |
+-------------------------------------------------------------------------------
+    Copyright (C) 2013-2016 OpenFOAM Foundation
+-------------------------------------------------------------------------------
+License
+    This file is part of OpenFOAM.
+
+    OpenFOAM is free software: you can redistribute it and/or modify it
+    under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    OpenFOAM is distributed in the hope that it will be useful, but WITHOUT
+    ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+    FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+    for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with OpenFOAM.  If not, see <http://www.gnu.org/licenses/>.
+
+\*---------------------------------------------------------------------------*/
+// rust translation of rodas23.C
+
+use crate::teh_o_prke_error::TehOPrkeError;
+use crate::linear_algebra::lu_back_substitute;
+use super::openfoam_ode_solver::{construct_rosenbrock_lu_matrix_into, ODESolverWorkspace};
+
+const c3: f64 = 1.0;
+const d1: f64 = 1.0/2.0;
+const d2: f64 = 3.0/2.0;
+const a31: f64 = 2.0;
+const c21: f64 = 4.0;
+const c31: f64 = 1.0;
+const c32: f64 = -1.0;
+const c41: f64 = 1.0;
+const c42: f64 = -1.0;
+const c43: f64 = -8.0/3.0;
+const gamma: f64 = 1.0/2.0;
+
+/// L-stable, stiffly-accurate embedded Rosenbrock ODE solver
+/// of order (2)3
+///
+/// Sandu, A., Verwer, J. G., Blom, J. G., Spee, E. J.,
+/// Carmichael, G. R., & Potra, F. A. (1997).
+#[derive(Debug,Clone,Copy)]
+pub struct Rodas23;
+
+#[allow(non_snake_case)]
+impl Rodas23 {
+
+    /// single Rodas23 step, the new state and the error 
+    /// vector are written into y and err
+    ///
+    /// dfdx_ and dfdy_ in the workspace must hold the jacobian
+    /// at (x0, y0)
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn solve_single_step_in_place(
+        x0: f64,
+        y0: &[f64],
+        dydx0: &[f64],
+        dx: f64,
+        y: &mut [f64],
+        err: &mut [f64],
+        derivatives: &impl Fn(f64, &[f64], &mut [f64]),
+        workspace: &mut ODESolverWorkspace) -> Result<(), TehOPrkeError> {
+
+        let n = y0.len();
+        let ODESolverWorkspace { dydx_, dy_, k1_, k2_, k3_, dfdx_, dfdy_, a_, pivotIndices_, .. } 
+            = workspace;
+
+        construct_rosenbrock_lu_matrix_into(dfdy_, gamma, dx, a_, pivotIndices_)?;
+
+        // Calculate k1:
+        for i in 0..n {
+            k1_[i] = dydx0[i] + dx*d1*dfdx_[i];
+        }
+        lu_back_substitute(a_, pivotIndices_, k1_);
+
+        // Calculate k2:
+        for i in 0..n {
+            k2_[i] = dydx0[i] + dx*d2*dfdx_[i] + c21*k1_[i]/dx;
+        }
+        lu_back_substitute(a_, pivotIndices_, k2_);
+
+        // Calculate k3:
+        for i in 0..n {
+            dy_[i] = a31*k1_[i];
+            y[i] = y0[i] + dy_[i];
+        }
+
+        derivatives(x0 + c3*dx, y, dydx_);
+
+        for i in 0..n {
+            k3_[i] = dydx_[i] + (c31*k1_[i] + c32*k2_[i])/dx;
+        }
+        lu_back_substitute(a_, pivotIndices_, k3_);
+
+        // Calculate new state and error
+        for i in 0..n {
+            dy_[i] += k3_[i];
+            y[i] = y0[i] + dy_[i];
+        }
+
+        derivatives(x0 + dx, y, dydx_);
+
+        for i in 0..n {
+            err[i] = dydx_[i] + (c41*k1_[i] + c42*k2_[i] + c43*k3_[i])/dx;
+        }
+        lu_back_substitute(a_, pivotIndices_, err);
+
+        for i in 0..n {
+            y[i] = y0[i] + dy_[i] + err[i];
+        }
+
+        Ok(())
+    }
+}
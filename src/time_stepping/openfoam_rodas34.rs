@@ -0,0 +1,180 @@
+/*---------------------------------------------------------------------------*\
+  =========                 |
+  \\      /  F ield         | OpenFOAM: The Open Source CFD Toolbox
+   \\    /   O peration     |
+    \\  /    A nd           | www.openfoam.com
+     \\/     M anipulation  |
+-------------------------------------------------------------------------------
+    Copyright (C) 2013-2016 OpenFOAM Foundation
+-------------------------------------------------------------------------------
+License
+    This file is part of OpenFOAM.
+
+    OpenFOAM is free software: you can redistribute it and/or modify it
+    under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    OpenFOAM is distributed in the hope that it will be useful, but WITHOUT
+    ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+    FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+    for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with OpenFOAM.  If not, see <http://www.gnu.org/licenses/>.
+
+\*---------------------------------------------------------------------------*/
+// rust translation of rodas34.C
+// the constants are kept as written in OpenFOAM
+#![allow(clippy::excessive_precision)]
+
+use crate::teh_o_prke_error::TehOPrkeError;
+use crate::linear_algebra::lu_back_substitute;
+use super::openfoam_ode_solver::{construct_rosenbrock_lu_matrix_into, ODESolverWorkspace};
+
+const c2: f64 = 0.386;
+const c3: f64 = 0.21;
+const c4: f64 = 0.63;
+const d1: f64 =  0.25;
+const d2: f64 = -0.1043;
+const d3: f64 =  0.1035;
+const d4: f64 = -0.3620000000000023e-01;
+const a21: f64 =  0.1544e1;
+const a31: f64 =  0.9466785280815826;
+const a32: f64 =  0.2557011698983284;
+const a41: f64 =  0.3314825187068521e1;
+const a42: f64 =  0.2896124015972201e1;
+const a43: f64 =  0.9986419139977817;
+const a51: f64 =  0.1221224509226641e1;
+const a52: f64 =  0.6019134481288629e1;
+const a53: f64 =  0.1253708332932087e2;
+const a54: f64 = -0.6878860361058950;
+const c21: f64 = -0.56688e1;
+const c31: f64 = -0.2430093356833875e1;
+const c32: f64 = -0.2063599157091915;
+const c41: f64 = -0.1073529058151375;
+const c42: f64 = -0.9594562251023355e1;
+const c43: f64 = -0.2047028614809616e2;
+const c51: f64 =  0.7496443313967647e1;
+const c52: f64 = -0.1024680431464352e2;
+const c53: f64 = -0.3399990352819905e2;
+const c54: f64 =  0.1170890893206160e2;
+const c61: f64 =  0.8083246795921522e1;
+const c62: f64 = -0.7981132988064893e1;
+const c63: f64 = -0.3152159432874371e2;
+const c64: f64 =  0.1631930543123136e2;
+const c65: f64 = -0.6058818238834054e1;
+const gamma: f64 = 0.25;
+
+/// L-stable, stiffly-accurate embedded Rosenbrock ODE solver
+/// of order (3)4
+///
+/// Hairer, E., Nørsett, S. P., & Wanner, G. (1996).
+#[derive(Debug,Clone,Copy)]
+pub struct Rodas34;
+
+#[allow(non_snake_case)]
+impl Rodas34 {
+
+    /// single Rodas34 step, the new state and the error 
+    /// vector are written into y and err
+    ///
+    /// dfdx_ and dfdy_ in the workspace must hold the jacobian
+    /// at (x0, y0)
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn solve_single_step_in_place(
+        x0: f64,
+        y0: &[f64],
+        dydx0: &[f64],
+        dx: f64,
+        y: &mut [f64],
+        err: &mut [f64],
+        derivatives: &impl Fn(f64, &[f64], &mut [f64]),
+        workspace: &mut ODESolverWorkspace) -> Result<(), TehOPrkeError> {
+
+        let n = y0.len();
+        let ODESolverWorkspace { 
+            dydx_, dy_, k1_, k2_, k3_, k4_, k5_, dfdx_, dfdy_, a_, pivotIndices_, .. 
+        } = workspace;
+
+        construct_rosenbrock_lu_matrix_into(dfdy_, gamma, dx, a_, pivotIndices_)?;
+
+        // Calculate k1:
+        for i in 0..n {
+            k1_[i] = dydx0[i] + dx*d1*dfdx_[i];
+        }
+        lu_back_substitute(a_, pivotIndices_, k1_);
+
+        // Calculate k2:
+        for i in 0..n {
+            y[i] = y0[i] + a21*k1_[i];
+        }
+
+        derivatives(x0 + c2*dx, y, dydx_);
+
+        for i in 0..n {
+            k2_[i] = dydx_[i] + dx*d2*dfdx_[i] + c21*k1_[i]/dx;
+        }
+        lu_back_substitute(a_, pivotIndices_, k2_);
+
+        // Calculate k3:
+        for i in 0..n {
+            y[i] = y0[i] + a31*k1_[i] + a32*k2_[i];
+        }
+
+        derivatives(x0 + c3*dx, y, dydx_);
+
+        for i in 0..n {
+            k3_[i] = dydx_[i] + dx*d3*dfdx_[i] + (c31*k1_[i] + c32*k2_[i])/dx;
+        }
+        lu_back_substitute(a_, pivotIndices_, k3_);
+
+        // Calculate k4:
+        for i in 0..n {
+            y[i] = y0[i] + a41*k1_[i] + a42*k2_[i] + a43*k3_[i];
+        }
+
+        derivatives(x0 + c4*dx, y, dydx_);
+
+        for i in 0..n {
+            k4_[i] = dydx_[i] + dx*d4*dfdx_[i]
+                + (c41*k1_[i] + c42*k2_[i] + c43*k3_[i])/dx;
+        }
+        lu_back_substitute(a_, pivotIndices_, k4_);
+
+        // Calculate k5:
+        for i in 0..n {
+            dy_[i] = a51*k1_[i] + a52*k2_[i] + a53*k3_[i] + a54*k4_[i];
+            y[i] = y0[i] + dy_[i];
+        }
+
+        derivatives(x0 + dx, y, dydx_);
+
+        for i in 0..n {
+            k5_[i] = dydx_[i]
+                + (c51*k1_[i] + c52*k2_[i] + c53*k3_[i] + c54*k4_[i])/dx;
+        }
+        lu_back_substitute(a_, pivotIndices_, k5_);
+
+        // Calculate new state and error
+        for i in 0..n {
+            dy_[i] += k5_[i];
+            y[i] = y0[i] + dy_[i];
+        }
+
+        derivatives(x0 + dx, y, dydx_);
+
+        for i in 0..n {
+            err[i] = dydx_[i]
+                + (c61*k1_[i] + c62*k2_[i] + c63*k3_[i] + c64*k4_[i] + c65*k5_[i])/dx;
+        }
+        lu_back_substitute(a_, pivotIndices_, err);
+
+        for i in 0..n {
+            y[i] = y0[i] + dy_[i] + err[i];
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,129 @@
+/*---------------------------------------------------------------------------*\
+  =========                 |
+  \\      /  F ield         | OpenFOAM: The Open Source CFD Toolbox
+   \\    /   O peration     |
+    \\  /    A nd           | www.openfoam.com
+     \\/     M anipulation  |
+-------------------------------------------------------------------------------
+    Copyright (C) 2013-2016 OpenFOAM Foundation
+-------------------------------------------------------------------------------
+License
+    This file is part of OpenFOAM.
+
+    OpenFOAM is free software: you can redistribute it and/or modify it
+    under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    OpenFOAM is distributed in the hope that it will be useful, but WITHOUT
+    ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+    FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+    for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with OpenFOAM.  If not, see <http://www.gnu.org/licenses/>.
+
+\*---------------------------------------------------------------------------*/
+// rust translation of Rosenbrock23.C
+// the constants are kept as written in OpenFOAM
+#![allow(clippy::excessive_precision)]
+
+use crate::teh_o_prke_error::TehOPrkeError;
+use crate::linear_algebra::lu_back_substitute;
+use super::openfoam_ode_solver::{construct_rosenbrock_lu_matrix_into, ODESolverWorkspace};
+
+// ROS3 coefficients of Sandu et al.,
+// the third stage is evaluated at the same point as the second
+// (a31 = 1, a32 = 0), so only two derivative evaluations are needed
+const a21: f64 = 1.0;
+
+const c21: f64 = -1.0156171083877702091975600115545;
+const c31: f64 = 4.0759956452537699824805835358067;
+const c32: f64 = 9.2076794298330791242156818474003;
+
+const b1: f64 = 1.0;
+const b2: f64 = 6.1697947043828245592553615689730;
+const b3: f64 = -0.4277225654321857332623837380651;
+
+const e1: f64 = 0.5;
+const e2: f64 = -2.9079558716805469821718236208017;
+const e3: f64 = 0.2235406989781156962736090927619;
+
+const gamma: f64 = 0.43586652150845899941601945119356;
+const c2: f64 = 0.43586652150845899941601945119356;
+
+const d1: f64 = 0.43586652150845899941601945119356;
+const d2: f64 = 0.24291996454816804366592249683314;
+const d3: f64 = 2.1851380027664058511513169485832;
+
+/// L-stable embedded Rosenbrock ODE solver of order (2)3
+///
+/// Sandu, A., Verwer, J. G., Blom, J. G., Spee, E. J.,
+/// Carmichael, G. R., & Potra, F. A. (1997).
+/// Benchmarking stiff ODE solvers for atmospheric chemistry
+/// problems II: Rosenbrock solvers.
+/// Atmospheric Environment, 31(20), 3459-3472.
+#[derive(Debug,Clone,Copy)]
+pub struct Rosenbrock23;
+
+#[allow(non_snake_case)]
+impl Rosenbrock23 {
+
+    /// single Rosenbrock23 step, the new state and the error 
+    /// vector are written into y and err
+    ///
+    /// dfdx_ and dfdy_ in the workspace must hold the jacobian
+    /// at (x0, y0)
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn solve_single_step_in_place(
+        x0: f64,
+        y0: &[f64],
+        dydx0: &[f64],
+        dx: f64,
+        y: &mut [f64],
+        err: &mut [f64],
+        derivatives: &impl Fn(f64, &[f64], &mut [f64]),
+        workspace: &mut ODESolverWorkspace) -> Result<(), TehOPrkeError> {
+
+        let n = y0.len();
+        let ODESolverWorkspace { dydx_, k1_, k2_, k3_, dfdx_, dfdy_, a_, pivotIndices_, .. } 
+            = workspace;
+
+        construct_rosenbrock_lu_matrix_into(dfdy_, gamma, dx, a_, pivotIndices_)?;
+
+        // Calculate k1:
+        for i in 0..n {
+            k1_[i] = dydx0[i] + dx*d1*dfdx_[i];
+        }
+        lu_back_substitute(a_, pivotIndices_, k1_);
+
+        // Calculate k2:
+        for i in 0..n {
+            y[i] = y0[i] + a21*k1_[i];
+        }
+
+        derivatives(x0 + c2*dx, y, dydx_);
+
+        for i in 0..n {
+            k2_[i] = dydx_[i] + dx*d2*dfdx_[i] + c21*k1_[i]/dx;
+        }
+        lu_back_substitute(a_, pivotIndices_, k2_);
+
+        // Calculate k3:
+        // since a31 = a21 and a32 = 0, the derivatives from the
+        // second stage are reused
+        for i in 0..n {
+            k3_[i] = dydx_[i] + dx*d3*dfdx_[i] + (c31*k1_[i] + c32*k2_[i])/dx;
+        }
+        lu_back_substitute(a_, pivotIndices_, k3_);
+
+        // Calculate error and update state:
+        for i in 0..n {
+            y[i] = y0[i] + b1*k1_[i] + b2*k2_[i] + b3*k3_[i];
+            err[i] = e1*k1_[i] + e2*k2_[i] + e3*k3_[i];
+        }
+
+        Ok(())
+    }
+}
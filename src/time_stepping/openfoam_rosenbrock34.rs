@@ -0,0 +1,143 @@
+/*---------------------------------------------------------------------------*\
+  =========                 |
+  \\      /  F ield         | OpenFOAM: The Open Source CFD Toolbox
+   \\    /   O peration     |
+    \\  /    A nd           | www.openfoam.com
+     \\/     M anipulation  |
+-------------------------------------------------------------------------------
+    Copyright (C) 2013-2016 OpenFOAM Foundation
+-------------------------------------------------------------------------------
+License
+    This file is part of OpenFOAM.
+
+    OpenFOAM is free software: you can redistribute it and/or modify it
+    under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    OpenFOAM is distributed in the hope that it will be useful, but WITHOUT
+    ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+    FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+    for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with OpenFOAM.  If not, see <http://www.gnu.org/licenses/>.
+
+\*---------------------------------------------------------------------------*/
+// rust translation of Rosenbrock34.C
+
+use crate::teh_o_prke_error::TehOPrkeError;
+use crate::linear_algebra::lu_back_substitute;
+use super::openfoam_ode_solver::{construct_rosenbrock_lu_matrix_into, ODESolverWorkspace};
+
+// Constants by Shampine
+// More accurate than the L-Stable coefficients for small step-size
+// but less stable for large step-size
+const a21: f64 = 2.0;
+const a31: f64 = 48.0/25.0;
+const a32: f64 = 6.0/25.0;
+
+const c21: f64 = -8.0;
+const c31: f64 = 372.0/25.0;
+const c32: f64 = 12.0/5.0;
+
+const c41: f64 = -112.0/125.0;
+const c42: f64 = -54.0/125.0;
+const c43: f64 = -2.0/5.0;
+
+const b1: f64 = 19.0/9.0;
+const b2: f64 = 1.0/2.0;
+const b3: f64 = 25.0/108.0;
+const b4: f64 = 125.0/108.0;
+
+const e1: f64 = 34.0/108.0;
+const e2: f64 = 7.0/36.0;
+const e4: f64 = 125.0/108.0;
+
+const gamma: f64 = 1.0/2.0;
+const c2: f64 = 1.0;
+const c3: f64 = 3.0/5.0;
+
+const d1: f64 = 1.0/2.0;
+const d2: f64 = -3.0/2.0;
+const d3: f64 = 605.0/250.0;
+const d4: f64 = 29.0/250.0;
+
+/// embedded Rosenbrock ODE solver of order (3)4
+///
+/// Hairer, E., Nørsett, S. P., & Wanner, G. (1996).
+#[derive(Debug,Clone,Copy)]
+pub struct Rosenbrock34;
+
+#[allow(non_snake_case)]
+impl Rosenbrock34 {
+
+    /// single Rosenbrock34 step, the new state and the error 
+    /// vector are written into y and err
+    ///
+    /// dfdx_ and dfdy_ in the workspace must hold the jacobian
+    /// at (x0, y0)
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn solve_single_step_in_place(
+        x0: f64,
+        y0: &[f64],
+        dydx0: &[f64],
+        dx: f64,
+        y: &mut [f64],
+        err: &mut [f64],
+        derivatives: &impl Fn(f64, &[f64], &mut [f64]),
+        workspace: &mut ODESolverWorkspace) -> Result<(), TehOPrkeError> {
+
+        let n = y0.len();
+        let ODESolverWorkspace { dydx_, k1_, k2_, k3_, k4_, dfdx_, dfdy_, a_, pivotIndices_, .. } 
+            = workspace;
+
+        construct_rosenbrock_lu_matrix_into(dfdy_, gamma, dx, a_, pivotIndices_)?;
+
+        // Calculate k1:
+        for i in 0..n {
+            k1_[i] = dydx0[i] + dx*d1*dfdx_[i];
+        }
+        lu_back_substitute(a_, pivotIndices_, k1_);
+
+        // Calculate k2:
+        for i in 0..n {
+            y[i] = y0[i] + a21*k1_[i];
+        }
+
+        derivatives(x0 + c2*dx, y, dydx_);
+
+        for i in 0..n {
+            k2_[i] = dydx_[i] + dx*d2*dfdx_[i] + c21*k1_[i]/dx;
+        }
+        lu_back_substitute(a_, pivotIndices_, k2_);
+
+        // Calculate k3:
+        for i in 0..n {
+            y[i] = y0[i] + a31*k1_[i] + a32*k2_[i];
+        }
+
+        derivatives(x0 + c3*dx, y, dydx_);
+
+        for i in 0..n {
+            k3_[i] = dydx_[i] + dx*d3*dfdx_[i] + (c31*k1_[i] + c32*k2_[i])/dx;
+        }
+        lu_back_substitute(a_, pivotIndices_, k3_);
+
+        // Calculate k4:
+        for i in 0..n {
+            k4_[i] = dydx_[i] + dx*d4*dfdx_[i]
+                + (c41*k1_[i] + c42*k2_[i] + c43*k3_[i])/dx;
+        }
+        lu_back_substitute(a_, pivotIndices_, k4_);
+
+        // Calculate error and update state:
+        for i in 0..n {
+            y[i] = y0[i] + b1*k1_[i] + b2*k2_[i] + b3*k3_[i] + b4*k4_[i];
+            err[i] = e1*k1_[i] + e2*k2_[i] + e4*k4_[i];
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,385 @@
+use std::collections::VecDeque;
+
+use uom::si::f64::*;
+use uom::si::frequency::hertz;
+use uom::si::ratio::ratio;
+use uom::si::time::second;
+use uom::si::volume::cubic_meter;
+use uom::si::volume_rate::cubic_meter_per_second;
+use uom::si::volumetric_number_density::per_cubic_meter;
+use uom::si::volumetric_number_rate::per_cubic_meter_second;
+
+use crate::teh_o_prke_error::TehOPrkeError;
+use crate::zero_power_prke::multi_group_precursor_prke::MultiGroupPRKE;
+
+/// point kinetics for fuel dissolved in a circulating salt, where
+/// delayed neutron precursors are swept out of the core, decay in
+/// the external loop, and come back in after the loop transit time
+///
+/// dn/dt = (rho - beta)/Lambda n + sum_i lambda_i C_i + S
+///
+/// dC_i/dt = beta_i/Lambda n - lambda_i C_i - C_i/tau_c
+/// + C_i(t - tau_L) exp(-lambda_i tau_L)/tau_c
+///
+/// where tau_c = V_core/Q and tau_L = V_loop/Q are the core and
+/// loop transit times for a salt flowrate Q. The salt is taken as
+/// plug flow in the loop and well mixed in the core
+///
+/// the flowrate can be changed between timesteps (eg. a pump
+/// coastdown), and the loop delay then follows the salt. When the
+/// flow stops, precursors in the core stay and decay there, and
+/// those in the loop never come back
+#[derive(Debug,Clone)]
+pub struct CirculatingFuelPRKE<const G: usize> {
+    /// neutron population, precursors in the core and delayed
+    /// neutron data
+    pub prke: MultiGroupPRKE<G>,
+    /// volume of salt in the core
+    pub core_salt_volume: Volume,
+    /// volume of salt in the external loop
+    pub loop_salt_volume: Volume,
+    /// salt flowrate through core and loop, set this every
+    /// timestep for time varying flow
+    pub salt_volumetric_flowrate: VolumeRate,
+    /// simulation time, for the decay of precursors in the loop
+    current_time: Time,
+    /// number of loop transits made by the salt since time zero
+    current_loop_position: f64,
+    /// (time, loop position, precursor concentration) of the salt
+    /// leaving the core, covering the salt now in the loop
+    core_outlet_history: VecDeque<(Time, f64, [VolumetricNumberDensity;G])>,
+}
+
+impl<const G: usize> CirculatingFuelPRKE<G> {
+
+    /// constructs the circulating fuel PRKE from salt volumes and
+    /// flowrate, at the circulating fuel equilibrium for the
+    /// neutron population in prke
+    pub fn new(prke: MultiGroupPRKE<G>,
+        core_salt_volume: Volume,
+        loop_salt_volume: Volume,
+        salt_volumetric_flowrate: VolumeRate,
+        neutron_generation_time: Time) -> Result<Self, TehOPrkeError> {
+
+        if core_salt_volume.get::<cubic_meter>() <= 0.0
+            || loop_salt_volume.get::<cubic_meter>() <= 0.0 {
+                return Err(TehOPrkeError::GenericStringError(
+                    "core and loop salt volumes must be positive".to_string()
+                ));
+        }
+
+        if salt_volumetric_flowrate.get::<cubic_meter_per_second>() < 0.0 {
+            return Err(TehOPrkeError::GenericStringError(
+                "salt flowrate cannot be negative".to_string()
+            ));
+        }
+
+        let mut circulating_fuel_prke = Self {
+            prke,
+            core_salt_volume,
+            loop_salt_volume,
+            salt_volumetric_flowrate,
+            current_time: Time::new::<second>(0.0),
+            current_loop_position: 0.0,
+            core_outlet_history: VecDeque::new(),
+        };
+
+        circulating_fuel_prke.set_equilibrium_from_neutron_population_density(
+            prke.get_current_neutron_population_density(),
+            neutron_generation_time);
+
+        Ok(circulating_fuel_prke)
+    }
+
+    /// constructs the circulating fuel PRKE from fixed core and
+    /// loop transit times
+    ///
+    /// the volumes are set for a flowrate of 1 m3/s, so scale
+    /// salt_volumetric_flowrate from there to change the flow
+    pub fn new_from_transit_times(prke: MultiGroupPRKE<G>,
+        core_transit_time: Time,
+        loop_transit_time: Time,
+        neutron_generation_time: Time) -> Result<Self, TehOPrkeError> {
+
+        let salt_volumetric_flowrate = VolumeRate::new::<cubic_meter_per_second>(1.0);
+
+        Self::new(prke,
+            salt_volumetric_flowrate * core_transit_time,
+            salt_volumetric_flowrate * loop_transit_time,
+            salt_volumetric_flowrate,
+            neutron_generation_time)
+    }
+
+    /// returns the core transit time, V_core/Q, infinite with
+    /// no flow
+    pub fn get_core_transit_time(&self) -> Time {
+        self.core_salt_volume/self.salt_volumetric_flowrate
+    }
+
+    /// returns the loop transit time, V_loop/Q, infinite with
+    /// no flow
+    pub fn get_loop_transit_time(&self) -> Time {
+        self.loop_salt_volume/self.salt_volumetric_flowrate
+    }
+
+    /// returns Q/V_core, the fraction of core salt swept out
+    /// per unit time
+    fn get_core_flush_rate(&self) -> Frequency {
+        self.salt_volumetric_flowrate/self.core_salt_volume
+    }
+
+    /// returns the steady state precursor concentrations with
+    /// precursors drifting out of the core at the current flowrate
+    ///
+    /// C_i = beta_i/Lambda n/(lambda_i + (1 - exp(-lambda_i tau_L))/tau_c)
+    pub fn get_equilibrium_precursor_concentration_array(&self,
+        neutron_population_number_density: VolumetricNumberDensity,
+        neutron_generation_time: Time) -> [VolumetricNumberDensity;G] {
+
+        let mut precursor_concentration_array =
+            [VolumetricNumberDensity::new::<per_cubic_meter>(0.0);G];
+
+        for (i, precursor_concentration) in precursor_concentration_array.iter_mut().enumerate() {
+            let lambda_i = self.prke.decay_constant_array[i];
+            let beta_i = self.prke.delayed_fraction_array[i];
+
+            *precursor_concentration = (beta_i * neutron_population_number_density
+                /neutron_generation_time
+                /self.get_effective_removal_rate(lambda_i)).into();
+        }
+
+        precursor_concentration_array
+    }
+
+    /// sets the neutron population and the circulating fuel
+    /// equilibrium precursors, in the core and in the loop
+    pub fn set_equilibrium_from_neutron_population_density(&mut self,
+        neutron_population_number_density: VolumetricNumberDensity,
+        neutron_generation_time: Time) {
+
+        let precursor_concentration_array = self.get_equilibrium_precursor_concentration_array(
+            neutron_population_number_density,
+            neutron_generation_time);
+
+        self.prke.neutron_population_number_density = neutron_population_number_density;
+        self.prke.precursor_concentration_array = precursor_concentration_array;
+
+        // the salt now in the loop left the core over the last loop
+        // transit, with the same precursors as now
+        let loop_transit_time = if self.salt_volumetric_flowrate.get::<cubic_meter_per_second>() > 0.0 {
+            self.get_loop_transit_time()
+        } else {
+            Time::new::<second>(0.0)
+        };
+
+        self.core_outlet_history.clear();
+        self.core_outlet_history.push_back((self.current_time - loop_transit_time,
+            self.current_loop_position - 1.0,
+            precursor_concentration_array));
+        self.core_outlet_history.push_back((self.current_time,
+            self.current_loop_position,
+            precursor_concentration_array));
+    }
+
+    /// returns the reactivity lost to precursor drift at steady
+    /// state for the current flowrate, ie. the extra reactivity
+    /// needed to hold the core critical with the salt flowing
+    ///
+    /// rho_loss = beta - sum_i beta_i lambda_i
+    /// /(lambda_i + (1 - exp(-lambda_i tau_L))/tau_c)
+    ///
+    /// this is zero with no flow, and is what a pump trip puts
+    /// back in as the precursors stop leaving the core
+    pub fn get_reactivity_loss_from_precursor_drift(&self) -> Ratio {
+
+        let effective_delayed_fraction: Ratio = self.prke.delayed_fraction_array.iter()
+            .zip(self.prke.decay_constant_array.iter())
+            .map(|(&beta_i, &lambda_i)| {
+                beta_i * (lambda_i/self.get_effective_removal_rate(lambda_i))
+            })
+            .sum();
+
+        self.prke.get_total_delayed_fraction() - effective_delayed_fraction
+    }
+
+    /// lambda_i + (1 - exp(-lambda_i tau_L))/tau_c, the rate at which
+    /// the core loses precursors of group i, net of those returning
+    fn get_effective_removal_rate(&self, lambda_i: Frequency) -> Frequency {
+
+        if self.salt_volumetric_flowrate.get::<cubic_meter_per_second>() <= 0.0 {
+            return lambda_i;
+        }
+
+        let loop_survival_fraction = (-(lambda_i * self.get_loop_transit_time())
+            .get::<ratio>()).exp();
+
+        lambda_i + self.get_core_flush_rate() * (1.0 - loop_survival_fraction)
+    }
+
+    /// returns the precursor concentrations in the salt coming back
+    /// into the core at the given time and loop position, decayed
+    /// over their time in the loop
+    ///
+    /// this is the salt which left the core one loop transit ago,
+    /// found by interpolating the core outlet history in loop
+    /// position
+    fn get_returning_precursor_concentration_array(&self,
+        time: Time,
+        loop_position: f64) -> Result<[VolumetricNumberDensity;G], TehOPrkeError> {
+
+        let departure_loop_position = loop_position - 1.0;
+
+        let later_index = self.core_outlet_history.iter()
+            .position(|(_, history_loop_position, _)| {
+                *history_loop_position >= departure_loop_position
+            })
+            .filter(|&index| index > 0)
+            .ok_or_else(|| TehOPrkeError::GenericStringError(
+                "core outlet history does not cover the salt returning from \
+                the loop".to_string()
+            ))?;
+
+        let (earlier_time, earlier_loop_position, earlier_concentration_array) =
+            self.core_outlet_history[later_index - 1];
+        let (later_time, later_loop_position, later_concentration_array) =
+            self.core_outlet_history[later_index];
+
+        let weight = (departure_loop_position - earlier_loop_position)
+            /(later_loop_position - earlier_loop_position);
+
+        let departure_time = earlier_time + (later_time - earlier_time) * weight;
+        let time_in_loop = time - departure_time;
+
+        let mut returning_concentration_array = earlier_concentration_array;
+
+        for (i, returning_concentration) in returning_concentration_array.iter_mut().enumerate() {
+            let loop_survival_fraction = (-(self.prke.decay_constant_array[i] * time_in_loop)
+                .get::<ratio>()).exp();
+
+            *returning_concentration = (earlier_concentration_array[i]
+                + (later_concentration_array[i] - earlier_concentration_array[i]) * weight)
+                * loop_survival_fraction;
+        }
+
+        Ok(returning_concentration_array)
+    }
+
+    /// advances the neutron population and core precursors by one
+    /// timestep, and returns the neutron population
+    ///
+    /// this is backward Euler, with the precursors returning from
+    /// the loop at the end of the step taken as a source, so the
+    /// timestep must be positive and no longer than the loop
+    /// transit time. As
+    /// with the theta method, each precursor row gives C_i in terms
+    /// of n, and substituting into the neutron row gives n
+    pub fn solve_next_timestep_precursor_concentration_and_neutron_pop_vector_circulating_fuel(
+        &mut self,
+        timestep: Time,
+        reactivity: impl Into<Ratio>,
+        neutron_generation_time: Time,
+        background_source_rate: VolumetricNumberRate)
+        -> Result<VolumetricNumberDensity, TehOPrkeError> {
+            let reactivity: Ratio = reactivity.into();
+
+            if self.salt_volumetric_flowrate.get::<cubic_meter_per_second>() < 0.0 {
+                return Err(TehOPrkeError::GenericStringError(
+                    "salt flowrate cannot be negative".to_string()
+                ));
+            }
+
+            let dt: f64 = timestep.get::<second>();
+
+            // a zero step with the salt flowing would record the
+            // same loop position twice in the core outlet history,
+            // which the interpolation then divides by
+            if dt <= 0.0 || !dt.is_finite() {
+                return Err(TehOPrkeError::GenericStringError(
+                    "timestep must be positive".to_string()
+                ));
+            }
+
+            let flush_rate: f64 = self.get_core_flush_rate().get::<hertz>();
+
+            let next_time = self.current_time + timestep;
+            let next_loop_position = self.current_loop_position
+                + (self.salt_volumetric_flowrate * timestep/self.loop_salt_volume)
+                .get::<ratio>();
+
+            if next_loop_position - self.current_loop_position > 1.0 {
+                return Err(TehOPrkeError::GenericStringError(
+                    "timestep must not be longer than the loop transit time".to_string()
+                ));
+            }
+
+            // with no flow nothing comes back, so the history is
+            // not needed
+            let returning_concentration_array = if flush_rate > 0.0 {
+                self.get_returning_precursor_concentration_array(
+                    next_time,
+                    next_loop_position)?
+            } else {
+                self.prke.get_current_precursor_concentration_array()
+            };
+
+            let big_lambda: f64 = neutron_generation_time.get::<second>();
+            let total_delayed_fraction: f64 = self.prke.get_total_delayed_fraction()
+                .get::<ratio>();
+
+            // C_i,new = a_i + b_i n_new
+            let mut neutron_row_coefficient = 1.0
+                - dt * (reactivity.get::<ratio>() - total_delayed_fraction)/big_lambda;
+            let mut neutron_row_rhs = self.prke.neutron_population_number_density
+                .get::<per_cubic_meter>()
+                + background_source_rate.get::<per_cubic_meter_second>() * dt;
+
+            let mut precursor_intercept_array = [0.0;G];
+            let mut precursor_slope_array = [0.0;G];
+
+            for i in 0..G {
+                let lambda_i = self.prke.decay_constant_array[i].get::<hertz>();
+                let beta_i = self.prke.delayed_fraction_array[i].get::<ratio>();
+                let diagonal = 1.0 + dt * (lambda_i + flush_rate);
+
+                precursor_intercept_array[i] = (self.prke.precursor_concentration_array[i]
+                    .get::<per_cubic_meter>()
+                    + dt * flush_rate * returning_concentration_array[i].get::<per_cubic_meter>())
+                    /diagonal;
+                precursor_slope_array[i] = dt * beta_i/big_lambda/diagonal;
+
+                neutron_row_coefficient -= dt * lambda_i * precursor_slope_array[i];
+                neutron_row_rhs += dt * lambda_i * precursor_intercept_array[i];
+            }
+
+            let neutron_population_next_timestep = neutron_row_rhs/neutron_row_coefficient;
+
+            self.prke.neutron_population_number_density =
+                VolumetricNumberDensity::new::<per_cubic_meter>(neutron_population_next_timestep);
+
+            for i in 0..G {
+                self.prke.precursor_concentration_array[i] =
+                    VolumetricNumberDensity::new::<per_cubic_meter>(
+                        precursor_intercept_array[i]
+                        + precursor_slope_array[i] * neutron_population_next_timestep);
+            }
+
+            self.current_time = next_time;
+            self.current_loop_position = next_loop_position;
+
+            // record the salt leaving the core, and drop history older
+            // than one loop transit (keeping one point before it to
+            // interpolate from)
+            if flush_rate > 0.0 {
+                self.core_outlet_history.push_back((self.current_time,
+                    self.current_loop_position,
+                    self.prke.precursor_concentration_array));
+            }
+
+            while self.core_outlet_history.len() > 2
+                && self.core_outlet_history[1].1 <= self.current_loop_position - 1.0 {
+                    self.core_outlet_history.pop_front();
+            }
+
+            Ok(self.prke.neutron_population_number_density)
+    }
+}
@@ -0,0 +1,137 @@
+use std::f64::consts::LN_2;
+
+use uom::si::f64::*;
+use uom::si::time::second;
+use uom::si::ratio::ratio;
+
+use crate::teh_o_prke_error::TehOPrkeError;
+use crate::zero_power_prke::multi_group_precursor_prke::DecayConstant;
+use crate::zero_power_prke::six_group_precursor_prke::six_group_constants::FissioningNuclideType;
+
+// eight group delayed neutron model, as adopted for JEFF-3.1
+//
+// the eight group structure uses the same half lives for all
+// fissioning nuclides, only the relative abundances change
+//
+// Spriggs, G. D., Campbell, J. M., & Piksaikin, V. M. (2002).
+// An 8-group delayed neutron model based on a consistent set of
+// half-lives. Progress in Nuclear Energy, 41(1-4), 223-251.
+//
+// the relative abundances for thermal fission of u233, u235 and
+// pu239 are the JEFF-3.1 values from the same eight group model,
+// for other nuclides or incident energies, supply the abundances
+// (eg. from the MF1/MT455 section of the evaluated file) and use
+// new_eight_group_delayed_fraction_array
+impl FissioningNuclideType {
+
+    /// returns the JEFF-3.1 eight group relative abundances 
+    /// for thermal fission
+    ///
+    /// only u233, u235 and pu239 are tabulated here
+    pub fn get_eight_group_relative_abundance_array(&self) 
+        -> Result<[f64;8], TehOPrkeError> {
+        match self {
+            FissioningNuclideType::U233 => Ok(new_u233_eight_group_relative_abundance_array()),
+            FissioningNuclideType::U235 => Ok(new_u235_eight_group_relative_abundance_array()),
+            FissioningNuclideType::Pu239 => Ok(new_pu239_eight_group_relative_abundance_array()),
+            _ => Err(TehOPrkeError::GenericStringError(
+                format!("no eight group relative abundances for {:?}, \
+                    supply them with new_eight_group_delayed_fraction_array", self)
+            )),
+        }
+    }
+
+    /// returns the eight group delayed fraction array for 
+    /// thermal fission
+    ///
+    /// the JEFF-3.1 relative abundances are scaled by the total 
+    /// delayed fraction of Keepin's six group data for the same 
+    /// nuclide (see six_group_constants), so that the six and eight
+    /// group models have the same beta
+    pub fn get_eight_group_delayed_fraction_array(&self) 
+        -> Result<[Ratio;8], TehOPrkeError> {
+
+        let total_delayed_fraction: Ratio = 
+            self.get_delayed_fraction_array().into_iter().sum();
+
+        new_eight_group_delayed_fraction_array(
+            self.get_eight_group_relative_abundance_array()?,
+            total_delayed_fraction)
+    }
+}
+
+/// produces the eight group decay constant array of Spriggs et al.
+///
+/// these are the same for all nuclides
+pub fn new_eight_group_decay_constant_array() -> [DecayConstant;8] {
+
+    let half_life_array: [Time;8] =
+        [
+        Time::new::<second>(55.6),
+        Time::new::<second>(24.5),
+        Time::new::<second>(16.3),
+        Time::new::<second>(5.21),
+        Time::new::<second>(2.37),
+        Time::new::<second>(1.04),
+        Time::new::<second>(0.424),
+        Time::new::<second>(0.195),
+        ];
+
+    // lambda (decay constant) = ln(2)/(half life)
+    half_life_array.map(|half_life| LN_2/half_life)
+}
+
+/// JEFF-3.1 eight group relative abundances for u233 thermal fission
+pub fn new_u233_eight_group_relative_abundance_array() -> [f64;8] {
+    [0.0788, 0.1749, 0.1451, 0.2070, 0.3039, 0.0563, 0.0262, 0.0078]
+}
+
+/// JEFF-3.1 eight group relative abundances for u235 thermal fission
+pub fn new_u235_eight_group_relative_abundance_array() -> [f64;8] {
+    [0.0328, 0.1539, 0.0913, 0.1969, 0.3308, 0.0902, 0.0812, 0.0229]
+}
+
+/// JEFF-3.1 eight group relative abundances for pu239 thermal fission
+pub fn new_pu239_eight_group_relative_abundance_array() -> [f64;8] {
+    [0.0363, 0.2364, 0.1789, 0.1267, 0.3081, 0.0739, 0.0330, 0.0067]
+}
+
+/// produces an eight group delayed fraction array by scaling the
+/// relative abundances of each group by the total delayed fraction
+///
+/// the relative abundances must not be negative and must add up
+/// to one, evaluated files give them to about four decimal places
+/// so a round off of 1e-3 in the sum is allowed
+pub fn new_eight_group_delayed_fraction_array(
+    relative_abundance_array: [f64;8],
+    total_delayed_fraction: Ratio) -> Result<[Ratio;8], TehOPrkeError> {
+
+    if relative_abundance_array.iter().any(|relative_abundance| {
+        !relative_abundance.is_finite() || *relative_abundance < 0.0
+    }) {
+        return Err(TehOPrkeError::GenericStringError(
+            "relative abundances must be finite and not negative".to_string()
+        ));
+    }
+
+    let sum_of_relative_abundances: f64 = relative_abundance_array.iter().sum();
+
+    if (sum_of_relative_abundances - 1.0).abs() > 1e-3 {
+        return Err(TehOPrkeError::GenericStringError(
+            format!("relative abundances add up to {}, not one",
+                sum_of_relative_abundances)
+        ));
+    }
+
+    let total_delayed_fraction_float = total_delayed_fraction.get::<ratio>();
+
+    if !(total_delayed_fraction_float > 0.0 && total_delayed_fraction_float < 1.0) {
+        return Err(TehOPrkeError::GenericStringError(
+            "total delayed fraction must be between 0 and 1".to_string()
+        ));
+    }
+
+    Ok(relative_abundance_array.map(|relative_abundance|{
+        total_delayed_fraction * relative_abundance
+    }))
+}
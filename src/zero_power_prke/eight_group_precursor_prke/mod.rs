@@ -0,0 +1,55 @@
+use uom::si::f64::*;
+
+use crate::teh_o_prke_error::TehOPrkeError;
+use crate::zero_power_prke::multi_group_precursor_prke::MultiGroupPRKE;
+use crate::zero_power_prke::six_group_precursor_prke::six_group_constants::FissioningNuclideType;
+
+/// contains the eight group delayed precursor decay constants
+/// and JEFF-3.1 relative abundances, and builds the delayed 
+/// fractions from relative abundances
+pub mod eight_group_constants;
+pub use eight_group_constants::*;
+
+/// EightGroupPRKE 
+///
+/// this is the MultiGroupPRKE specialised to the eight delayed 
+/// neutron precursor groups of JEFF-3.1
+pub type EightGroupPRKE = MultiGroupPRKE<8>;
+
+impl EightGroupPRKE {
+
+    /// constructs an eight group PRKE for thermal fission of 
+    /// u233, u235 or pu239, with the JEFF-3.1 relative abundances
+    /// (see get_eight_group_delayed_fraction_array)
+    ///
+    /// starting neutron population of 1 per m3
+    pub fn new_from_nuclide(delayed_group_mode: FissioningNuclideType) 
+        -> Result<Self, TehOPrkeError> {
+
+        let delayed_fraction_array = 
+            delayed_group_mode.get_eight_group_delayed_fraction_array()?;
+
+        Ok(Self::new(new_eight_group_decay_constant_array(), 
+            delayed_fraction_array, 
+            delayed_group_mode))
+    }
+
+    /// constructs an eight group PRKE from the relative abundances 
+    /// and total delayed fraction of the fissioning nuclide, 
+    /// with the eight group decay constants
+    ///
+    /// starting neutron population of 1 per m3
+    pub fn new_from_relative_abundances(relative_abundance_array: [f64;8],
+        total_delayed_fraction: Ratio,
+        delayed_group_mode: FissioningNuclideType) 
+        -> Result<Self, TehOPrkeError> {
+
+        let delayed_fraction_array = new_eight_group_delayed_fraction_array(
+            relative_abundance_array, 
+            total_delayed_fraction)?;
+
+        Ok(Self::new(new_eight_group_decay_constant_array(), 
+            delayed_fraction_array, 
+            delayed_group_mode))
+    }
+}
@@ -1,7 +1,47 @@
-/// six group PRKE struct 
+
+/// PRKE struct with an arbitrary number of precursor groups
+///
+/// uses implicit or explicit calculation
+pub mod multi_group_precursor_prke;
+
+/// six group PRKE struct
 ///
 /// uses implicit calculation
 pub mod six_group_precursor_prke;
 
+/// eight group PRKE struct 
+///
+/// uses the JEFF-3.1 eight group delayed neutron half lives
+pub mod eight_group_precursor_prke;
+
+/// runs whole transients with any of the PRKE solvers, and
+/// returns the time history
+pub mod transient_runner;
+
+/// converts between neutron population and thermal power
+pub mod reactor_power;
+
+/// stochastic (birth death) point kinetics, for the counting
+/// statistics seen at low neutron populations
+pub mod stochastic_prke;
+
+/// zero power and closed loop reactor transfer functions, for
+/// frequency response (Bode) plots
+pub mod transfer_function;
+
+/// multipoint kinetics, with regions linked by a diffusive
+/// exchange of neutrons
+pub mod multipoint_prke;
+
+/// point kinetics for circulating fuel (molten salt) cores, where
+/// precursors drift out of the core and decay in the external loop
+pub mod circulating_fuel_prke;
+
 #[cfg(test)]
 mod tests;
+
+/// verification against published point kinetics benchmarks
+/// and exact or independently computed references, run with
+/// every solver
+#[cfg(test)]
+mod benchmarks;
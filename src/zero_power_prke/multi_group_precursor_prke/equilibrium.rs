@@ -0,0 +1,114 @@
+use uom::ConstZero;
+use uom::si::f64::*;
+use uom::si::ratio::ratio;
+
+use crate::teh_o_prke_error::TehOPrkeError;
+use crate::zero_power_prke::reactor_power::ReactorPowerModel;
+use super::MultiGroupPRKE;
+
+impl<const G: usize> MultiGroupPRKE<G> {
+
+    /// returns the precursor concentrations in equilibrium with
+    /// a given neutron population
+    ///
+    /// dC_i/dt = beta_i/Lambda n - lambda_i C_i = 0
+    ///
+    /// C_i = beta_i n / (lambda_i Lambda)
+    pub fn get_equilibrium_precursor_concentration_array(&self,
+        neutron_population_number_density: VolumetricNumberDensity,
+        neutron_generation_time: Time) -> [VolumetricNumberDensity;G] {
+
+        let mut precursor_concentration_array = [VolumetricNumberDensity::ZERO;G];
+
+        for (i, precursor_concentration) in
+            precursor_concentration_array.iter_mut().enumerate() {
+                *precursor_concentration = (self.delayed_fraction_array[i]
+                    * neutron_population_number_density
+                    /(self.decay_constant_array[i] * neutron_generation_time)).into();
+        }
+
+        precursor_concentration_array
+    }
+
+    /// places the PRKE at steady state for a given neutron
+    /// population, with precursors at equilibrium
+    ///
+    /// this is the critical steady state if there is no source
+    pub fn set_equilibrium_from_neutron_population_density(&mut self,
+        neutron_population_number_density: VolumetricNumberDensity,
+        neutron_generation_time: Time){
+
+        self.neutron_population_number_density = neutron_population_number_density;
+        self.precursor_concentration_array =
+            self.get_equilibrium_precursor_concentration_array(
+                neutron_population_number_density,
+                neutron_generation_time);
+    }
+
+    /// places the PRKE at critical steady state for a given
+    /// reactor power, the neutron population comes from the
+    /// reactor power model
+    ///
+    /// P = n v Sigma_f V E_f
+    pub fn set_equilibrium_from_reactor_power(&mut self,
+        reactor_power: Power,
+        neutron_generation_time: Time,
+        reactor_power_model: &ReactorPowerModel){
+
+        self.set_equilibrium_from_neutron_population_density(
+            reactor_power_model.get_neutron_population_number_density(reactor_power),
+            neutron_generation_time);
+    }
+
+    /// returns the steady state neutron population of a subcritical
+    /// core driven by a source
+    ///
+    /// dn/dt = (rho - beta)/Lambda n + sum_i lambda_i C_i + S = 0
+    ///
+    /// with precursors at equilibrium, sum_i lambda_i C_i = beta n/Lambda,
+    /// so
+    ///
+    /// n = - S Lambda / rho
+    ///
+    /// there is no steady state unless rho is negative
+    pub fn get_subcritical_equilibrium_neutron_population_density(
+        reactivity: impl Into<Ratio>,
+        neutron_generation_time: Time,
+        background_source_rate: VolumetricNumberRate)
+        -> Result<VolumetricNumberDensity, TehOPrkeError> {
+        let reactivity: Ratio = reactivity.into();
+
+        if reactivity.get::<ratio>() >= 0.0 {
+            return Err(TehOPrkeError::GenericStringError(
+                "source equilibrium only exists for negative reactivity".to_string()
+            ));
+        }
+
+        let neutron_population_number_density: VolumetricNumberDensity =
+            (-background_source_rate * neutron_generation_time / reactivity).into();
+
+        Ok(neutron_population_number_density)
+    }
+
+    /// places the PRKE at the steady state of a subcritical
+    /// core driven by a source
+    pub fn set_equilibrium_from_source(&mut self,
+        reactivity: impl Into<Ratio>,
+        neutron_generation_time: Time,
+        background_source_rate: VolumetricNumberRate)
+        -> Result<(), TehOPrkeError> {
+        let reactivity: Ratio = reactivity.into();
+
+        let neutron_population_number_density =
+            Self::get_subcritical_equilibrium_neutron_population_density(
+                reactivity,
+                neutron_generation_time,
+                background_source_rate)?;
+
+        self.set_equilibrium_from_neutron_population_density(
+            neutron_population_number_density,
+            neutron_generation_time);
+
+        Ok(())
+    }
+}
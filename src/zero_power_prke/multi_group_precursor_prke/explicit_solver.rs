@@ -0,0 +1,149 @@
+use ndarray::*;
+use uom::si::f64::*;
+use uom::si::time::second;
+use uom::si::volumetric_number_density::per_cubic_meter;
+
+use crate::teh_o_prke_error::TehOPrkeError;
+use crate::time_stepping::openfoam_rfk45::RKF45;
+use crate::time_stepping::openfoam_adaptive_solver::AdaptiveSolver;
+use crate::time_stepping::openfoam_ode_solver::{ODESolver, ODESolverMethod};
+use super::MultiGroupPRKE;
+impl<const G: usize> MultiGroupPRKE<G> {
+
+    /// solves for the neutron population and precursor concentration
+    /// using an explicit time marching scheme
+    pub fn solve_next_timestep_precursor_concentration_and_neutron_pop_vector_explicit(
+        &mut self,
+        timestep: Time,
+        reactivity: impl Into<Ratio>,
+        neutron_generation_time: Time,
+        background_source_rate: VolumetricNumberRate)
+        -> Result<Array1<VolumetricNumberDensity>,TehOPrkeError> {
+            let reactivity: Ratio = reactivity.into();
+
+            // first, get neutron population and precursor density
+            let current_neutron_pop_and_precursor_vector =
+                self.get_neutron_pop_and_precursor_vector();
+
+            let prke_ode_system = self.construct_ode_system(
+                reactivity,
+                neutron_generation_time,
+                background_source_rate);
+
+            // now the ode system needs to be solved because
+            // we need a stepsize dt
+            // as well as dydt = f(t,y)
+            //
+            // now, for prke, the dydt function is not
+            // explicitly based on time here
+            // it is based on a time changing reactivity based
+            // on user input and feedback mechanism.
+            //
+            // for the sake of this programming,
+            // dydt = f(y)
+            // so i need to make a dummy time to put this into the
+            // function
+            //
+
+            let dummy_time = timestep;
+            let current_neutron_pop_and_precursor_vector_si_units: Vec<f64>
+                = current_neutron_pop_and_precursor_vector.iter()
+                .map(|num_density|{
+                    num_density.get::<per_cubic_meter>()
+                })
+                .collect();
+
+            // k1_ is dydx0, the stages then work on the RKF45 
+            // buffers, so nothing is allocated within the step
+            let mut dydx0: Vec<f64> = vec![0.0; G+1];
+            prke_ode_system.derivatives(
+                dummy_time.get::<second>(),
+                &current_neutron_pop_and_precursor_vector_si_units,
+                &mut dydx0);
+
+            let mut next_timestep_neutron_pop_and_precursor_vector_si_units: Vec<f64>
+                = vec![0.0; G+1];
+
+            RKF45::new(prke_ode_system, G+1).solve(
+                dummy_time.get::<second>(),
+                &current_neutron_pop_and_precursor_vector_si_units,
+                &dydx0,
+                timestep.get::<second>(),
+                &mut next_timestep_neutron_pop_and_precursor_vector_si_units);
+
+            let precursor_and_neutron_pop_and_source_vector_next_timestep: Array1<VolumetricNumberDensity>
+                =
+                next_timestep_neutron_pop_and_precursor_vector_si_units
+                .into_iter()
+                .map(|num_density_f64|{
+                    VolumetricNumberDensity::new::<per_cubic_meter>(num_density_f64)
+                })
+                .collect();
+
+            self.set_neutron_pop_and_precursor_vector(
+                &precursor_and_neutron_pop_and_source_vector_next_timestep);
+            // return to environment
+            Ok(precursor_and_neutron_pop_and_source_vector_next_timestep)
+    }
+
+
+    /// solves for the neutron population and precursor concentration
+    /// using RKF45 with adaptive step size control
+    ///
+    /// the timestep is split into as many substeps as needed to meet
+    /// the tolerances in the adaptive solver, so large reactivity 
+    /// insertions do not need a hand tuned timestep
+    ///
+    /// returns the neutron population and precursor vector
+    /// as well as the accepted substeps
+    pub fn solve_next_timestep_precursor_concentration_and_neutron_pop_vector_explicit_adaptive(
+        &mut self,
+        timestep: Time,
+        reactivity: impl Into<Ratio>,
+        neutron_generation_time: Time,
+        background_source_rate: VolumetricNumberRate,
+        adaptive_solver: &AdaptiveSolver)
+        -> Result<(Array1<VolumetricNumberDensity>, Vec<Time>),TehOPrkeError> {
+            let reactivity: Ratio = reactivity.into();
+
+            let prke_ode_system = self.construct_ode_system(
+                reactivity,
+                neutron_generation_time,
+                background_source_rate);
+
+            let mut ode_solver = ODESolver::new_with_adaptive_solver(
+                prke_ode_system, ODESolverMethod::RKF45, *adaptive_solver);
+
+            let mut neutron_pop_and_precursor_vector_si_units: Vec<f64>
+                = self.get_neutron_pop_and_precursor_vector().iter()
+                .map(|num_density|{
+                    num_density.get::<per_cubic_meter>()
+                })
+                .collect();
+
+            // first try is the whole timestep, 
+            // the adaptive solver shrinks it as needed
+            let mut dt_try: f64 = timestep.get::<second>();
+
+            let accepted_substep_list: Vec<Time> = ode_solver.solve(
+                0.0,
+                timestep.get::<second>(),
+                &mut neutron_pop_and_precursor_vector_si_units,
+                &mut dt_try)?
+                .into_iter()
+                .map(Time::new::<second>)
+                .collect();
+
+            let precursor_and_neutron_pop_and_source_vector_next_timestep: Array1<VolumetricNumberDensity>
+                = neutron_pop_and_precursor_vector_si_units
+                .into_iter()
+                .map(VolumetricNumberDensity::new::<per_cubic_meter>)
+                .collect();
+
+            self.set_neutron_pop_and_precursor_vector(
+                &precursor_and_neutron_pop_and_source_vector_next_timestep);
+
+            Ok((precursor_and_neutron_pop_and_source_vector_next_timestep, accepted_substep_list))
+    }
+
+}
@@ -0,0 +1,343 @@
+use ndarray::*;
+use uom::si::f64::*;
+use uom::si::frequency::hertz;
+use uom::si::time::second;
+use uom::si::volumetric_number_density::per_cubic_meter;
+use uom::si::volumetric_number_rate::per_cubic_meter_second;
+use uom::si::ratio::ratio;
+
+use crate::teh_o_prke_error::TehOPrkeError;
+use super::MultiGroupPRKE;
+
+impl<const G: usize> MultiGroupPRKE<G> {
+
+
+    /// returns the next timestep neutron source vector
+    ///
+    /// also updates the current precursor and concentration vector
+    ///
+    /// this timestepping is implicit rather than explicit
+    ///
+    /// this is backward Euler, ie. the theta method with theta = 1,
+    /// the coefficient matrix is solved in closed form as it is
+    /// an arrowhead matrix (see the theta method)
+    pub fn solve_next_timestep_precursor_concentration_and_neutron_pop_vector_implicit(
+        &mut self,
+        timestep: Time,
+        reactivity: impl Into<Ratio>,
+        neutron_generation_time: Time,
+        background_source_rate: VolumetricNumberRate)
+        -> Result<Array1<VolumetricNumberDensity>,TehOPrkeError> {
+            let reactivity: Ratio = reactivity.into();
+
+            self.solve_next_timestep_precursor_concentration_and_neutron_pop_vector_theta_method(
+                timestep,
+                reactivity,
+                reactivity,
+                neutron_generation_time,
+                background_source_rate,
+                1.0)
+    }
+
+    /// returns the next timestep neutron source vector using the
+    /// theta method, and updates the current precursor and 
+    /// concentration vector
+    ///
+    /// y_new - y_old = dt [theta f(t + dt, y_new) + (1 - theta) f(t, y_old)]
+    ///
+    /// theta = 1 is backward Euler (first order, damps oscillations),
+    /// theta = 0.5 is Crank-Nicolson (second order), theta = 0 is
+    /// forward Euler
+    ///
+    /// reactivity varies linearly across the step, from 
+    /// reactivity_at_start to reactivity_at_end, so a reactivity 
+    /// ramp keeps second order accuracy with Crank-Nicolson
+    ///
+    /// the coefficient matrix (see construct_coefficient_matrix_theta) 
+    /// is an arrowhead matrix, a full top row, a full left column 
+    /// and a diagonal. So each precursor row 
+    ///
+    /// -theta dt beta_i/Lambda n + (1 + theta dt lambda_i) C_i = rhs_i
+    ///
+    /// gives C_i in terms of n, and substituting into the top row
+    /// gives n directly. This is O(G) with no matrix assembled, 
+    /// no heap allocation (except for the returned vector) and
+    /// no LAPACK
+    pub fn solve_next_timestep_precursor_concentration_and_neutron_pop_vector_theta_method(
+        &mut self,
+        timestep: Time,
+        reactivity_at_start: impl Into<Ratio>,
+        reactivity_at_end: impl Into<Ratio>,
+        neutron_generation_time: Time,
+        background_source_rate: VolumetricNumberRate,
+        theta: f64)
+        -> Result<Array1<VolumetricNumberDensity>,TehOPrkeError> {
+            let reactivity_at_start: Ratio = reactivity_at_start.into();
+            let reactivity_at_end: Ratio = reactivity_at_end.into();
+
+            if !(0.0..=1.0).contains(&theta) {
+                return Err(TehOPrkeError::GenericStringError(
+                    "theta must be between 0 and 1".to_string()
+                ));
+            }
+
+            // all SI units
+            let dt: f64 = timestep.get::<second>();
+            let dt_over_big_lambda: f64 = (timestep/neutron_generation_time).get::<ratio>();
+            let total_delayed_fraction: Ratio = self.get_total_delayed_fraction();
+            let rho_minus_beta_at_start: f64 = (reactivity_at_start - total_delayed_fraction)
+                .get::<ratio>();
+            let rho_minus_beta_at_end: f64 = (reactivity_at_end - total_delayed_fraction)
+                .get::<ratio>();
+
+            let neutron_pop_old: f64 = 
+                self.neutron_population_number_density.get::<per_cubic_meter>();
+
+            // right hand side, the explicit part of the step
+            //
+            // n_old + (1 - theta) dt [(rho - beta)/Lambda n_old + sum_i lambda_i C_i_old] + S dt
+            // C_i_old + (1 - theta) dt [beta_i/Lambda n_old - lambda_i C_i_old]
+            let mut neutron_pop_rhs: f64 = neutron_pop_old
+                + (1.0 - theta) * dt_over_big_lambda * rho_minus_beta_at_start * neutron_pop_old
+                + background_source_rate.get::<per_cubic_meter_second>() * dt;
+
+            let mut precursor_rhs_array: [f64;G] = [0.0;G];
+
+            for (i, precursor_rhs) in precursor_rhs_array.iter_mut().enumerate() {
+                let dt_lambda_i: f64 = dt * self.decay_constant_array[i].get::<hertz>();
+                let dt_beta_i_over_big_lambda: f64 = 
+                    dt_over_big_lambda * self.delayed_fraction_array[i].get::<ratio>();
+                let precursor_old: f64 = 
+                    self.precursor_concentration_array[i].get::<per_cubic_meter>();
+
+                neutron_pop_rhs += (1.0 - theta) * dt_lambda_i * precursor_old;
+
+                *precursor_rhs = precursor_old 
+                    + (1.0 - theta) * (dt_beta_i_over_big_lambda * neutron_pop_old 
+                        - dt_lambda_i * precursor_old);
+            }
+
+            // top left entry, 1 - theta dt/Lambda (rho - beta)
+            let mut top_left_coefficient: f64 = 
+                1.0 - theta * dt_over_big_lambda * rho_minus_beta_at_end;
+            let mut reduced_neutron_pop_rhs: f64 = neutron_pop_rhs;
+
+            // eliminate each precursor row into the top row
+            //
+            // top row entry: -theta dt lambda_i
+            // left column entry: -theta dt beta_i/Lambda
+            // diagonal entry: 1 + theta dt lambda_i
+            for (i, precursor_rhs) in precursor_rhs_array.iter().enumerate() {
+                let theta_dt_lambda_i: f64 = 
+                    theta * dt * self.decay_constant_array[i].get::<hertz>();
+                let top_row_coefficient: f64 = -theta_dt_lambda_i;
+                let diagonal_coefficient: f64 = 1.0 + theta_dt_lambda_i;
+                let left_column_coefficient: f64 = 
+                    -theta * dt_over_big_lambda * self.delayed_fraction_array[i].get::<ratio>();
+
+                top_left_coefficient -= 
+                    top_row_coefficient * left_column_coefficient/diagonal_coefficient;
+                reduced_neutron_pop_rhs -= 
+                    top_row_coefficient * precursor_rhs/diagonal_coefficient;
+            }
+
+            if top_left_coefficient == 0.0 || !top_left_coefficient.is_finite() {
+                return Err(TehOPrkeError::GenericStringError(
+                    "implicit PRKE coefficient matrix is singular, \
+                    try a shorter timestep".to_string()
+                ));
+            }
+
+            let neutron_pop_next_timestep: f64 = reduced_neutron_pop_rhs/top_left_coefficient;
+
+            // back substitute for the precursors
+            let mut precursor_and_neutron_pop_and_source_vector_next_timestep: Array1<VolumetricNumberDensity>
+                = Array::zeros(G + 1);
+
+            precursor_and_neutron_pop_and_source_vector_next_timestep[0] =
+                VolumetricNumberDensity::new::<per_cubic_meter>(neutron_pop_next_timestep);
+
+            for i in 0..G {
+                let theta_dt_lambda_i: f64 = 
+                    theta * dt * self.decay_constant_array[i].get::<hertz>();
+                let left_column_coefficient: f64 = 
+                    -theta * dt_over_big_lambda * self.delayed_fraction_array[i].get::<ratio>();
+
+                let precursor_next_timestep: f64 = 
+                    (precursor_rhs_array[i] - left_column_coefficient * neutron_pop_next_timestep)
+                    /(1.0 + theta_dt_lambda_i);
+
+                precursor_and_neutron_pop_and_source_vector_next_timestep[i+1] =
+                    VolumetricNumberDensity::new::<per_cubic_meter>(precursor_next_timestep);
+            }
+
+            // edit the neutron population and precursor concentrations
+
+            self.set_neutron_pop_and_precursor_vector(
+                &precursor_and_neutron_pop_and_source_vector_next_timestep);
+
+            // return to environment
+            Ok(precursor_and_neutron_pop_and_source_vector_next_timestep)
+
+    }
+
+    /// constructs the vector for delayed neutron precursor concentration
+    /// and neutron population concentration
+    ///
+    /// note that the first precursor concentration group is that
+    /// with the longest half life
+    ///
+    pub fn construct_present_timestep_concentration_and_neutron_pop_vector(
+        precursor_concentration_array: [VolumetricNumberDensity;G],
+        neutron_population_number_density: VolumetricNumberDensity,
+        background_source_rate: VolumetricNumberRate,
+        timestep: Time
+        ) ->
+        Array1<VolumetricNumberDensity>{
+
+            let array_width_and_height = G + 1;
+            let mut precursor_and_neutron_pop_and_source_vector: Array1<VolumetricNumberDensity> =
+                Array::zeros(array_width_and_height);
+
+            for (i, delayed_neutron_precursor_concentration) in
+                precursor_concentration_array.iter().enumerate() {
+                    precursor_and_neutron_pop_and_source_vector[i+1] =
+                        *delayed_neutron_precursor_concentration;
+            }
+
+
+            let background_source_term: VolumetricNumberDensity =
+                (background_source_rate * timestep).into();
+
+            precursor_and_neutron_pop_and_source_vector[0] =
+                background_source_term
+                + neutron_population_number_density;
+
+
+            precursor_and_neutron_pop_and_source_vector
+    }
+
+    /// constructs the matrix required for
+    /// solution of implicit multi group PRKE
+    pub fn construct_coefficient_matrix(&self,
+        timestep: Time,
+        reactivity: Ratio,
+        neutron_generation_time: Time) -> Array2<Ratio> {
+
+        // preliminaries
+        let timestep_to_neutron_generation_time_ratio: Ratio =
+            timestep/neutron_generation_time;
+
+        let total_delayed_fraction: Ratio = self.get_total_delayed_fraction();
+
+        // top row coefficient
+        let top_left_coefficient = Ratio::new::<ratio>(1.0) -
+            timestep_to_neutron_generation_time_ratio*(
+                reactivity - total_delayed_fraction);
+
+        let array_width_and_height = G + 1;
+
+        let mut coefficient_matrix: Array2<Ratio> =
+        Array::zeros((array_width_and_height, array_width_and_height));
+
+        // okay i won't pivot this time to avoid confusion
+
+        coefficient_matrix[[0,0]] = top_left_coefficient;
+
+        for (i, (&lambda_i, &beta_i)) in self.decay_constant_array.iter()
+            .zip(self.delayed_fraction_array.iter())
+            .enumerate() {
+
+                let delta_t_lambda_i: Ratio = timestep * lambda_i;
+
+                // start changing the top row
+                coefficient_matrix[[0,i+1]] = -delta_t_lambda_i;
+                // then the left column
+                coefficient_matrix[[i+1,0]] = -timestep_to_neutron_generation_time_ratio*beta_i;
+                // Lastly, the diagonal
+                coefficient_matrix[[i+1,i+1]] = Ratio::new::<ratio>(1.0)+delta_t_lambda_i;
+        }
+
+        // return the coefficient_matrix
+        coefficient_matrix
+    }
+
+    /// constructs the right hand side vector for the theta method
+    ///
+    /// this is the present timestep neutron population and precursor
+    /// vector, plus the explicit (1 - theta) part of the step and the
+    /// background source
+    ///
+    /// n + (1 - theta) dt [(rho - beta)/Lambda n + sum_i lambda_i C_i] + S dt
+    ///
+    /// C_i + (1 - theta) dt [beta_i/Lambda n - lambda_i C_i]
+    ///
+    /// the reactivity here is that at the start of the timestep,
+    /// with theta = 1, this is the same as 
+    /// construct_present_timestep_concentration_and_neutron_pop_vector
+    pub fn construct_present_timestep_concentration_and_neutron_pop_vector_theta(
+        &self,
+        reactivity_at_start: Ratio,
+        neutron_generation_time: Time,
+        background_source_rate: VolumetricNumberRate,
+        timestep: Time,
+        theta: f64) -> Array1<VolumetricNumberDensity> {
+
+            let explicit_weight: Ratio = Ratio::new::<ratio>(1.0 - theta);
+            let neutron_population_number_density = self.neutron_population_number_density;
+
+            let mut precursor_and_neutron_pop_and_source_vector =
+                Self::construct_present_timestep_concentration_and_neutron_pop_vector(
+                    self.precursor_concentration_array,
+                    neutron_population_number_density,
+                    background_source_rate,
+                    timestep);
+
+            let rho_minus_beta_over_big_lambda: Frequency =
+                (reactivity_at_start - self.get_total_delayed_fraction())/neutron_generation_time;
+
+            let mut neutron_pop_explicit_change: VolumetricNumberDensity =
+                (explicit_weight * timestep * rho_minus_beta_over_big_lambda
+                 * neutron_population_number_density).into();
+
+            for (i, (&lambda_i, &beta_i)) in self.decay_constant_array.iter()
+                .zip(self.delayed_fraction_array.iter())
+                .enumerate() {
+
+                    let precursor_concentration = self.precursor_concentration_array[i];
+
+                    let decay_change: VolumetricNumberDensity =
+                        (explicit_weight * timestep * lambda_i * precursor_concentration).into();
+                    let production_change: VolumetricNumberDensity =
+                        (explicit_weight * timestep/neutron_generation_time * beta_i
+                         * neutron_population_number_density).into();
+
+                    neutron_pop_explicit_change += decay_change;
+                    precursor_and_neutron_pop_and_source_vector[i+1] +=
+                        production_change - decay_change;
+            }
+
+            precursor_and_neutron_pop_and_source_vector[0] += neutron_pop_explicit_change;
+
+            precursor_and_neutron_pop_and_source_vector
+    }
+
+    /// constructs the matrix required for solution of the multi 
+    /// group PRKE with the theta method
+    ///
+    /// the reactivity here is that at the end of the timestep,
+    /// with theta = 1, this is the same as construct_coefficient_matrix
+    pub fn construct_coefficient_matrix_theta(&self,
+        timestep: Time,
+        reactivity_at_end: Ratio,
+        neutron_generation_time: Time,
+        theta: f64) -> Array2<Ratio> {
+
+        // weighting the timestep by theta gives the theta method 
+        // matrix from the backward Euler one
+        self.construct_coefficient_matrix(
+            theta * timestep,
+            reactivity_at_end,
+            neutron_generation_time)
+    }
+}
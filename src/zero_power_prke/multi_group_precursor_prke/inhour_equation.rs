@@ -0,0 +1,262 @@
+use uom::si::f64::*;
+use uom::si::frequency::hertz;
+use uom::si::ratio::ratio;
+use uom::si::time::second;
+
+use crate::teh_o_prke_error::TehOPrkeError;
+use super::MultiGroupPRKE;
+
+/// roots of the inhour equation and the amplitudes of each mode
+///
+/// for a step reactivity from a critical steady state
+/// (precursors at equilibrium, no source), the neutron population
+/// evolves as
+///
+/// n(t) = n(0) sum_j A_j exp(omega_j t)
+///
+/// where omega_j are the G+1 roots of the inhour equation
+///
+/// rho = omega Lambda + sum_i beta_i omega/(omega + lambda_i)
+#[derive(Debug,Clone)]
+pub struct InhourEquationSolution {
+    /// roots of the inhour equation, sorted from the largest
+    /// (the stable root) to the smallest (the prompt decay root)
+    pub root_list: Vec<Frequency>,
+
+    /// amplitudes A_j of each mode, in the same order as the roots
+    pub amplitude_list: Vec<Ratio>,
+}
+
+impl InhourEquationSolution {
+
+    /// returns the largest root, which dominates the
+    /// asymptotic behaviour of the neutron population
+    pub fn get_stable_root(&self) -> Frequency {
+        self.root_list[0]
+    }
+
+    /// returns the stable reactor period, 1/omega_1
+    ///
+    /// at zero reactivity, the period is infinite
+    pub fn get_stable_period(&self) -> Time {
+
+        let stable_root = self.get_stable_root();
+
+        if stable_root.get::<hertz>() == 0.0 {
+            return Time::new::<second>(f64::INFINITY);
+        }
+
+        1.0/stable_root
+    }
+
+    /// returns the most negative root, which governs how fast
+    /// the prompt jump (or drop) settles
+    pub fn get_prompt_decay_root(&self) -> Frequency {
+        self.root_list[self.root_list.len() - 1]
+    }
+
+    /// returns n(t)/n(0) for the step reactivity
+    ///
+    /// n(t)/n(0) = sum_j A_j exp(omega_j t)
+    pub fn get_neutron_population_ratio(&self, time: Time) -> Ratio {
+
+        self.root_list.iter().zip(self.amplitude_list.iter())
+            .map(|(&omega_j, &amplitude_j)|{
+                let omega_j_t: Ratio = omega_j * time;
+                amplitude_j * omega_j_t.get::<ratio>().exp()
+            }).sum()
+    }
+}
+
+impl<const G: usize> MultiGroupPRKE<G> {
+
+    /// evaluates the right hand side of the inhour equation
+    ///
+    /// rho(omega) = omega Lambda + sum_i beta_i omega/(omega + lambda_i)
+    pub fn calc_inhour_equation_reactivity(&self,
+        omega: Frequency,
+        neutron_generation_time: Time) -> Ratio {
+
+        let mut reactivity: Ratio = omega * neutron_generation_time;
+
+        for (&beta_i, &lambda_i) in self.delayed_fraction_array.iter()
+            .zip(self.decay_constant_array.iter()) {
+                reactivity += beta_i * omega/(omega + lambda_i);
+        }
+
+        reactivity
+    }
+
+    /// returns the reactivity that gives a stable period T
+    ///
+    /// rho = Lambda/T + sum_i beta_i /(1 + lambda_i T)
+    ///
+    /// an infinite period gives zero reactivity
+    pub fn get_reactivity_from_stable_period(&self,
+        stable_period: Time,
+        neutron_generation_time: Time) -> Ratio {
+
+        let stable_root: Frequency = 1.0/stable_period;
+
+        self.calc_inhour_equation_reactivity(stable_root, neutron_generation_time)
+    }
+
+    /// finds all G+1 roots of the inhour equation for a given
+    /// reactivity, as well as the mode amplitudes for a step
+    /// reactivity insertion from critical steady state
+    ///
+    /// the inhour equation is strictly increasing between its
+    /// poles at omega = -lambda_i, so exactly one root lies
+    /// between each pair of poles, one above -lambda_min
+    /// (the stable root) and one below -lambda_max
+    /// (the prompt decay root). Each root is found by bisection.
+    ///
+    /// decay constants must all be distinct
+    pub fn solve_inhour_equation(&self,
+        reactivity: impl Into<Ratio>,
+        neutron_generation_time: Time)
+        -> Result<InhourEquationSolution, TehOPrkeError> {
+        let reactivity: Ratio = reactivity.into();
+
+        let big_lambda = neutron_generation_time.get::<second>();
+
+        if big_lambda <= 0.0 {
+            return Err(TehOPrkeError::GenericStringError(
+                "neutron generation time must be positive for the inhour equation"
+                .to_string()
+            ));
+        }
+
+        let rho = reactivity.get::<ratio>();
+
+        // sorted decay constants, smallest first
+        let mut lambda_list: Vec<f64> = self.decay_constant_array.iter()
+            .map(|lambda_i| lambda_i.get::<hertz>())
+            .collect();
+        lambda_list.sort_by(|a, b| a.total_cmp(b));
+
+        for lambda_pair in lambda_list.windows(2) {
+            if lambda_pair[0] == lambda_pair[1] {
+                return Err(TehOPrkeError::GenericStringError(
+                    "decay constants must be distinct to solve the inhour equation"
+                    .to_string()
+                ));
+            }
+        }
+
+        if lambda_list.iter().any(|&lambda_i| lambda_i <= 0.0) {
+            return Err(TehOPrkeError::GenericStringError(
+                "decay constants must be positive to solve the inhour equation"
+                .to_string()
+            ));
+        }
+
+        // inhour equation minus reactivity, in SI units
+        let inhour_residual = |omega: f64| -> f64 {
+            self.calc_inhour_equation_reactivity(
+                Frequency::new::<hertz>(omega),
+                neutron_generation_time).get::<ratio>() - rho
+        };
+
+        let mut root_list_float: Vec<f64> = Vec::with_capacity(G+1);
+
+        // the stable root lies in (-lambda_min, infinity)
+        {
+            let lower_bound = -lambda_list[0];
+            let mut upper_bound: f64 = 1.0;
+            while inhour_residual(upper_bound) < 0.0 {
+                upper_bound *= 2.0;
+            }
+
+            let stable_root = if rho == 0.0 {
+                // exactly zero, bisection would only get close
+                0.0
+            } else {
+                Self::inhour_bisection(&inhour_residual, lower_bound, upper_bound)
+            };
+            root_list_float.push(stable_root);
+        }
+
+        // roots between poles -lambda_(k+1) < omega < -lambda_(k)
+        for lambda_pair in lambda_list.windows(2) {
+            let lower_bound = -lambda_pair[1];
+            let upper_bound = -lambda_pair[0];
+            root_list_float.push(
+                Self::inhour_bisection(&inhour_residual, lower_bound, upper_bound)
+            );
+        }
+
+        // the prompt decay root lies in (-infinity, -lambda_max)
+        {
+            let upper_bound = -lambda_list[lambda_list.len()-1];
+            let mut lower_bound = 2.0 * upper_bound;
+            while inhour_residual(lower_bound) > 0.0 {
+                lower_bound *= 2.0;
+            }
+            root_list_float.push(
+                Self::inhour_bisection(&inhour_residual, lower_bound, upper_bound)
+            );
+        }
+
+        // amplitudes for a step from critical equilibrium:
+        //
+        // A_j = [Lambda + sum_i beta_i/(omega_j + lambda_i)]
+        // / [Lambda + sum_i beta_i lambda_i/(omega_j + lambda_i)^2]
+        let amplitude_list: Vec<Ratio> = root_list_float.iter()
+            .map(|&omega_j|{
+                let mut numerator = big_lambda;
+                let mut denominator = big_lambda;
+
+                for (&beta_i, &lambda_i) in self.delayed_fraction_array.iter()
+                    .zip(self.decay_constant_array.iter()) {
+                        let beta_i = beta_i.get::<ratio>();
+                        let lambda_i = lambda_i.get::<hertz>();
+
+                        numerator += beta_i/(omega_j + lambda_i);
+                        denominator += beta_i * lambda_i/(omega_j + lambda_i).powi(2);
+                }
+
+                Ratio::new::<ratio>(numerator/denominator)
+            }).collect();
+
+        let root_list: Vec<Frequency> = root_list_float.into_iter()
+            .map(Frequency::new::<hertz>)
+            .collect();
+
+        Ok(InhourEquationSolution {
+            root_list,
+            amplitude_list,
+        })
+    }
+
+    /// bisection on an interval where the residual goes from
+    /// negative (at lower bound) to positive (at upper bound)
+    ///
+    /// the bounds themselves are never evaluated since they may
+    /// be poles of the inhour equation
+    fn inhour_bisection(inhour_residual: &impl Fn(f64) -> f64,
+        lower_bound: f64,
+        upper_bound: f64) -> f64 {
+
+        let mut lower_bound = lower_bound;
+        let mut upper_bound = upper_bound;
+        let max_iterations = 500;
+
+        for _ in 0..max_iterations {
+            let midpoint = 0.5 * (lower_bound + upper_bound);
+
+            if midpoint <= lower_bound || midpoint >= upper_bound {
+                // no more floating point numbers in between
+                break;
+            }
+
+            if inhour_residual(midpoint) < 0.0 {
+                lower_bound = midpoint;
+            } else {
+                upper_bound = midpoint;
+            }
+        }
+
+        0.5 * (lower_bound + upper_bound)
+    }
+}
@@ -0,0 +1,244 @@
+use uom::si::f64::*;
+use uom::si::frequency::hertz;
+use uom::si::power::watt;
+use uom::si::ratio::ratio;
+use uom::si::time::second;
+use uom::si::volumetric_number_density::per_cubic_meter;
+use uom::si::volumetric_number_rate::per_cubic_meter_second;
+
+use crate::teh_o_prke_error::TehOPrkeError;
+use super::MultiGroupPRKE;
+
+impl<const G: usize> MultiGroupPRKE<G> {
+
+    /// inverse point kinetics, works like a digital reactivity meter
+    ///
+    /// given a recorded neutron density trace, this returns the
+    /// reactivity at each sample time using the delayed neutron
+    /// data stored in this PRKE (the neutron population and precursor
+    /// concentrations stored in the struct are not used or changed)
+    ///
+    /// rho(t) = beta + Lambda/n dn/dt - Lambda/n (sum_i lambda_i C_i + S)
+    ///
+    /// the trace is assumed to start from steady state, so the
+    /// precursors start at equilibrium, C_i = beta_i n / (lambda_i Lambda)
+    ///
+    /// the sample times need not be evenly spaced
+    pub fn calc_reactivity_history_from_neutron_density(&self,
+        neutron_generation_time: Time,
+        time_list: &[Time],
+        neutron_density_list: &[VolumetricNumberDensity],
+        background_source_rate: Option<VolumetricNumberRate>)
+        -> Result<Vec<Ratio>, TehOPrkeError> {
+
+        let neutron_density_list_si_units: Vec<f64> = neutron_density_list.iter()
+            .map(|neutron_density| neutron_density.get::<per_cubic_meter>())
+            .collect();
+
+        let background_source_rate_si_units = match background_source_rate {
+            Some(source_rate) => source_rate.get::<per_cubic_meter_second>(),
+            None => 0.0,
+        };
+
+        self.inverse_kinetics_reactivity_history(
+            neutron_generation_time,
+            time_list,
+            &neutron_density_list_si_units,
+            background_source_rate_si_units)
+    }
+
+    /// inverse point kinetics for a recorded power trace
+    ///
+    /// power is taken to be proportional to neutron density, so only
+    /// the shape of the trace matters
+    ///
+    /// the background source is given relative to the first sample,
+    /// ie. S/n(t0), so a source which would raise the neutron density
+    /// by 1% of its starting value every second is 0.01 Hz
+    pub fn calc_reactivity_history_from_power(&self,
+        neutron_generation_time: Time,
+        time_list: &[Time],
+        power_list: &[Power],
+        relative_background_source_rate: Option<Frequency>)
+        -> Result<Vec<Ratio>, TehOPrkeError> {
+
+        let power_list_si_units: Vec<f64> = power_list.iter()
+            .map(|power| power.get::<watt>())
+            .collect();
+
+        // convert S/n(t0) into power units per second
+        let background_source_rate_si_units =
+            match (relative_background_source_rate, power_list_si_units.first()) {
+                (Some(relative_source_rate), Some(starting_power)) => {
+                    relative_source_rate.get::<hertz>() * starting_power
+                },
+                _ => 0.0,
+            };
+
+        self.inverse_kinetics_reactivity_history(
+            neutron_generation_time,
+            time_list,
+            &power_list_si_units,
+            background_source_rate_si_units)
+    }
+
+    /// inverse kinetics in SI units, the signal may be neutron
+    /// density or anything proportional to it, as long as the
+    /// source is in the same units per second
+    ///
+    /// between samples, the signal is taken to vary linearly, so the
+    /// precursor equations can be integrated exactly:
+    ///
+    /// C_i(t + h) = C_i(t) exp(-lambda_i h)
+    /// + beta_i/Lambda [n(t) a_i + (n(t+h) - n(t)) b_i/h ]
+    ///
+    /// a_i = (1 - exp(-lambda_i h))/lambda_i
+    ///
+    /// b_i = h/lambda_i - (1 - exp(-lambda_i h))/lambda_i^2
+    ///
+    /// dn/dt uses a three point (second order) finite difference
+    /// which works for non uniform spacing
+    fn inverse_kinetics_reactivity_history(&self,
+        neutron_generation_time: Time,
+        time_list: &[Time],
+        signal_list: &[f64],
+        background_source_rate: f64)
+        -> Result<Vec<Ratio>, TehOPrkeError> {
+
+        let number_of_samples = signal_list.len();
+
+        if time_list.len() != number_of_samples {
+            return Err(TehOPrkeError::GenericStringError(
+                "time list and signal list must be of the same length".to_string()
+            ));
+        }
+
+        if number_of_samples < 2 {
+            return Err(TehOPrkeError::GenericStringError(
+                "at least two samples are needed for inverse kinetics".to_string()
+            ));
+        }
+
+        let big_lambda = neutron_generation_time.get::<second>();
+
+        if big_lambda <= 0.0 {
+            return Err(TehOPrkeError::GenericStringError(
+                "neutron generation time must be positive".to_string()
+            ));
+        }
+
+        if signal_list.iter().any(|&signal| signal <= 0.0) {
+            return Err(TehOPrkeError::GenericStringError(
+                "neutron density or power must be positive for inverse kinetics"
+                .to_string()
+            ));
+        }
+
+        let time_list_si_units: Vec<f64> = time_list.iter()
+            .map(|time| time.get::<second>())
+            .collect();
+
+        if time_list_si_units.windows(2).any(|time_pair| time_pair[1] <= time_pair[0]) {
+            return Err(TehOPrkeError::GenericStringError(
+                "sample times must be strictly increasing".to_string()
+            ));
+        }
+
+        let total_delayed_fraction = self.get_total_delayed_fraction().get::<ratio>();
+        let delayed_fraction_array: [f64;G] =
+            self.delayed_fraction_array.map(|beta_i| beta_i.get::<ratio>());
+        let decay_constant_array: [f64;G] =
+            self.decay_constant_array.map(|lambda_i| lambda_i.get::<hertz>());
+
+        // precursors start at equilibrium
+        let mut precursor_array: [f64;G] = [0.0;G];
+        for (i, precursor_concentration) in precursor_array.iter_mut().enumerate() {
+            *precursor_concentration = delayed_fraction_array[i] * signal_list[0]
+                /(decay_constant_array[i] * big_lambda);
+        }
+
+        let mut reactivity_history: Vec<Ratio> = Vec::with_capacity(number_of_samples);
+
+        for k in 0..number_of_samples {
+
+            // integrate precursors from the previous sample
+            if k > 0 {
+                let h = time_list_si_units[k] - time_list_si_units[k-1];
+                let signal_start = signal_list[k-1];
+                let signal_end = signal_list[k];
+
+                for (i, precursor_concentration) in precursor_array.iter_mut().enumerate() {
+                    let lambda_i = decay_constant_array[i];
+                    let decay_factor = (-lambda_i * h).exp();
+
+                    let a_i = (1.0 - decay_factor)/lambda_i;
+                    let b_i = h/lambda_i - (1.0 - decay_factor)/lambda_i.powi(2);
+
+                    *precursor_concentration = *precursor_concentration * decay_factor
+                        + delayed_fraction_array[i]/big_lambda
+                        * (signal_start * a_i + (signal_end - signal_start) * b_i/h);
+                }
+            }
+
+            let signal_derivative = Self::nonuniform_finite_difference(
+                &time_list_si_units, signal_list, k);
+
+            let total_decay_rate: f64 = precursor_array.iter()
+                .zip(decay_constant_array.iter())
+                .map(|(precursor_concentration, lambda_i)| lambda_i * precursor_concentration)
+                .sum();
+
+            let signal = signal_list[k];
+
+            let reactivity = total_delayed_fraction
+                + big_lambda/signal * signal_derivative
+                - big_lambda/signal * (total_decay_rate + background_source_rate);
+
+            reactivity_history.push(Ratio::new::<ratio>(reactivity));
+        }
+
+        Ok(reactivity_history)
+    }
+
+    /// derivative at sample k using a three point finite difference
+    /// on non uniform spacing
+    ///
+    /// interior points use the centred stencil, the end points use
+    /// one sided stencils, and for only two samples, the two point
+    /// difference is used
+    fn nonuniform_finite_difference(time_list: &[f64],
+        signal_list: &[f64],
+        k: usize) -> f64 {
+
+        let number_of_samples = signal_list.len();
+
+        if number_of_samples == 2 {
+            return (signal_list[1] - signal_list[0])/(time_list[1] - time_list[0]);
+        }
+
+        if k == 0 {
+            let h1 = time_list[1] - time_list[0];
+            let h2 = time_list[2] - time_list[1];
+
+            -(2.0 * h1 + h2)/(h1 * (h1 + h2)) * signal_list[0]
+                + (h1 + h2)/(h1 * h2) * signal_list[1]
+                - h1/(h2 * (h1 + h2)) * signal_list[2]
+
+        } else if k == number_of_samples - 1 {
+            let h1 = time_list[k-1] - time_list[k-2];
+            let h2 = time_list[k] - time_list[k-1];
+
+            h2/(h1 * (h1 + h2)) * signal_list[k-2]
+                - (h1 + h2)/(h1 * h2) * signal_list[k-1]
+                + (2.0 * h2 + h1)/(h2 * (h1 + h2)) * signal_list[k]
+
+        } else {
+            let h1 = time_list[k] - time_list[k-1];
+            let h2 = time_list[k+1] - time_list[k];
+
+            -h2/(h1 * (h1 + h2)) * signal_list[k-1]
+                + (h2 - h1)/(h1 * h2) * signal_list[k]
+                + h1/(h2 * (h1 + h2)) * signal_list[k+1]
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use ndarray::*;
+use uom::si::f64::*;
+use uom::si::frequency::hertz;
+use uom::si::ratio::ratio;
+use uom::si::time::second;
+use uom::si::volumetric_number_density::per_cubic_meter;
+use uom::si::volumetric_number_rate::per_cubic_meter_second;
+
+use crate::linear_algebra::matrix_exponential;
+use crate::teh_o_prke_error::TehOPrkeError;
+use super::MultiGroupPRKE;
+
+/// stores exp(A dt) for the matrix exponential PRKE stepper
+///
+/// computing the matrix exponential is the expensive part,
+/// so it is only recomputed when the timestep, reactivity,
+/// generation time or delayed neutron data changes
+///
+/// the delayed data is part of the key, so one cache can be
+/// shared between PRKEs, or kept while the delayed data is
+/// updated (eg. as fission fractions drift), without reusing
+/// a stale matrix
+///
+/// this is kept outside MultiGroupPRKE so that the PRKE itself
+/// stays Copy
+#[derive(Debug,Clone,Default)]
+pub struct MatrixExponentialCache {
+    /// [timestep, reactivity, generation time, beta_1 ... beta_G,
+    /// lambda_1 ... lambda_G] in SI units which the cached matrix
+    /// was computed for
+    cached_parameters: Option<Vec<f64>>,
+    /// exp(A dt) for the augmented system [n, C_1 ... C_G, S dt]
+    state_transition_matrix: Array2<f64>,
+}
+
+impl MatrixExponentialCache {
+
+    /// constructs an empty cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// returns true if exp(A dt) is already stored for these
+    /// parameters and this PRKE's delayed neutron data
+    pub fn is_cached_for<const G: usize>(&self,
+        prke: &MultiGroupPRKE<G>,
+        timestep: Time,
+        reactivity: impl Into<Ratio>,
+        neutron_generation_time: Time) -> bool {
+        let reactivity: Ratio = reactivity.into();
+
+        self.cached_parameters.as_ref() == Some(&Self::parameter_key(
+            prke,
+            timestep,
+            reactivity,
+            neutron_generation_time))
+    }
+
+    /// clears the stored matrix
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    fn parameter_key<const G: usize>(prke: &MultiGroupPRKE<G>,
+        timestep: Time,
+        reactivity: Ratio,
+        neutron_generation_time: Time) -> Vec<f64> {
+        [
+            timestep.get::<second>(),
+            reactivity.get::<ratio>(),
+            neutron_generation_time.get::<second>(),
+        ].into_iter()
+            .chain(prke.delayed_fraction_array.iter().map(|beta_i| beta_i.get::<ratio>()))
+            .chain(prke.decay_constant_array.iter().map(|lambda_i| lambda_i.get::<hertz>()))
+            .collect()
+    }
+}
+
+impl<const G: usize> MultiGroupPRKE<G> {
+
+    /// advances the neutron population and precursor concentrations
+    /// exactly over a timestep where reactivity, generation time and
+    /// source are constant
+    ///
+    /// with y = [n, C_1 ... C_G], the PRKE is
+    ///
+    /// dy/dt = A y + b
+    ///
+    /// where A is the jacobian (construct_jacobian_matrix) and
+    /// b = [S, 0 ... 0]. Appending the constant s = S dt to y gives
+    ///
+    /// d/dt [y, s] = [[A, e_0/dt], [0, 0]] [y, s]
+    ///
+    /// so [y, s](t + dt) = exp([[A dt, e_0], [0, 0]]) [y, s](t)
+    ///
+    /// the source is kept out of the matrix so that a strong source
+    /// does not inflate its norm (which costs accuracy in the 
+    /// squaring), and so the cached matrix does not depend on it
+    ///
+    /// the only error is round off, so long steps (seconds or more)
+    /// can be taken during stretches of constant reactivity, eg.
+    /// xenon transients. The matrix exponential is cached, and
+    /// reused for as long as the parameters and delayed data do
+    /// not change
+    pub fn solve_next_timestep_precursor_concentration_and_neutron_pop_vector_matrix_exponential(
+        &mut self,
+        timestep: Time,
+        reactivity: impl Into<Ratio>,
+        neutron_generation_time: Time,
+        background_source_rate: VolumetricNumberRate,
+        matrix_exponential_cache: &mut MatrixExponentialCache)
+        -> Result<Array1<VolumetricNumberDensity>,TehOPrkeError> {
+            let reactivity: Ratio = reactivity.into();
+
+            let parameter_key = MatrixExponentialCache::parameter_key(
+                self,
+                timestep,
+                reactivity,
+                neutron_generation_time);
+
+            let augmented_size = G + 2;
+
+            if matrix_exponential_cache.cached_parameters.as_ref() != Some(&parameter_key)
+                || matrix_exponential_cache.state_transition_matrix.nrows() != augmented_size {
+
+                    let dt: f64 = timestep.get::<second>();
+
+                    let jacobian_matrix = self.construct_jacobian_matrix(
+                        reactivity,
+                        neutron_generation_time);
+
+                    let mut augmented_matrix_times_dt: Array2<f64> =
+                        Array2::zeros((augmented_size, augmented_size));
+
+                    for i in 0..(G + 1) {
+                        for j in 0..(G + 1) {
+                            augmented_matrix_times_dt[[i,j]] =
+                                jacobian_matrix[[i,j]].get::<hertz>() * dt;
+                        }
+                    }
+
+                    augmented_matrix_times_dt[[0, G + 1]] = 1.0;
+
+                    matrix_exponential_cache.state_transition_matrix =
+                        matrix_exponential(&augmented_matrix_times_dt)?;
+                    matrix_exponential_cache.cached_parameters = Some(parameter_key);
+            }
+
+            let state_transition_matrix =
+                &matrix_exponential_cache.state_transition_matrix;
+
+            // [n, C_1 ... C_G, S dt]
+            let background_source_term: f64 =
+                background_source_rate.get::<per_cubic_meter_second>()
+                * timestep.get::<second>();
+
+            let current_augmented_vector_si_units: Vec<f64>
+                = self.get_neutron_pop_and_precursor_vector().iter()
+                .map(|num_density| num_density.get::<per_cubic_meter>())
+                .chain(std::iter::once(background_source_term))
+                .collect();
+
+            let precursor_and_neutron_pop_and_source_vector_next_timestep: Array1<VolumetricNumberDensity>
+                = (0..(G + 1)).map(|i| {
+                    let number_density_float: f64 = state_transition_matrix.row(i).iter()
+                        .zip(current_augmented_vector_si_units.iter())
+                        .map(|(transition_entry, y_j)| transition_entry * y_j)
+                        .sum();
+                    VolumetricNumberDensity::new::<per_cubic_meter>(number_density_float)
+                }).collect();
+
+            self.set_neutron_pop_and_precursor_vector(
+                &precursor_and_neutron_pop_and_source_vector_next_timestep);
+
+            Ok(precursor_and_neutron_pop_and_source_vector_next_timestep)
+    }
+}
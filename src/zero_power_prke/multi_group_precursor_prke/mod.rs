@@ -0,0 +1,223 @@
+use ndarray::*;
+use uom::ConstZero;
+use uom::si::f64::*;
+use uom::si::volumetric_number_density::per_cubic_meter;
+use uom::si::ratio::ratio;
+
+use crate::zero_power_prke::six_group_precursor_prke::six_group_constants::FissioningNuclideType;
+
+/// Decay Constant is essentially the same units as frequency
+pub type DecayConstant = Frequency;
+
+/// PRKE with G delayed neutron precursor groups
+///
+/// the number of precursor groups is chosen at compile time through
+/// the const generic G, so that 1 group, 6 group or 8 group data
+/// can all go through the same implicit and explicit solvers
+///
+/// SixGroupPRKE is simply MultiGroupPRKE<6>
+///
+/// the solvers take reactivity either as a uom Ratio (delta k/k)
+/// or as a Reactivity, which can be built from dollars, cents or pcm
+#[derive(Debug,Clone,Copy)]
+pub struct MultiGroupPRKE<const G: usize> {
+    /// contains an array for the various half lives
+    /// of the delayed precursors
+    pub decay_constant_array: [DecayConstant;G],
+    /// contains delayed fraction arrays for the delayed precursors
+    /// this is different for u235, u233 and Pu239
+    pub delayed_fraction_array: [Ratio;G],
+    /// determines the set of delayed group constants based on your choice
+    /// of fissile isotope
+    pub delayed_group_mode: FissioningNuclideType,
+
+    /// neutron population number density
+    pub neutron_population_number_density: VolumetricNumberDensity,
+
+    /// delayed neutron precursor concentrations
+    ///
+    /// note that the first precursor concentration group is that
+    /// with the longest half life
+    pub precursor_concentration_array: [VolumetricNumberDensity;G],
+}
+
+/// contains time stepping implicit solvers for MultiGroupPRKE
+pub mod implicit_solver;
+
+/// contains time stepping explicit solvers for MultiGroupPRKE
+pub mod explicit_solver;
+
+/// contains time stepping through the ODESolver framework, 
+/// so that any of its methods (eg. Rodas34) can be used
+pub mod ode_solver;
+
+/// contains the inhour equation solver, for stable reactor
+/// periods and reactivity versus period curves
+pub mod inhour_equation;
+pub use inhour_equation::InhourEquationSolution;
+
+/// contains inverse point kinetics, which recovers reactivity
+/// from a recorded neutron density or power trace
+pub mod inverse_kinetics;
+
+/// contains functions to start the PRKE at steady state, with
+/// precursors in equilibrium with the neutron population
+pub mod equilibrium;
+
+/// contains the matrix exponential stepper, which is exact for
+/// constant reactivity over a timestep
+pub mod matrix_exponential;
+pub use matrix_exponential::MatrixExponentialCache;
+
+/// contains the prompt jump solver, where the neutron population
+/// is algebraic and only the precursors are time stepped
+pub mod prompt_jump;
+
+impl<const G: usize> MultiGroupPRKE<G> {
+
+    /// constructs a new PRKE struct given delayed group data
+    ///
+    /// starting neutron population is 1 per m3 and all
+    /// precursor concentrations are zero
+    pub fn new(decay_constant_array: [DecayConstant;G],
+        delayed_fraction_array: [Ratio;G],
+        delayed_group_mode: FissioningNuclideType) -> Self {
+
+        Self {
+            decay_constant_array,
+            delayed_fraction_array,
+            delayed_group_mode,
+            neutron_population_number_density:
+                VolumetricNumberDensity::new::<per_cubic_meter>(1.0),
+            precursor_concentration_array:
+                [VolumetricNumberDensity::ZERO;G],
+        }
+    }
+
+    /// returns the number of delayed neutron precursor groups
+    pub fn get_number_of_precursor_groups(&self) -> usize {
+        G
+    }
+
+    /// obtains current neutron population
+    pub fn get_current_neutron_population_density(&self) -> VolumetricNumberDensity {
+        self.neutron_population_number_density
+    }
+
+    /// obtains current delayed neutron precursor concentrations
+    pub fn get_current_precursor_concentration_array(&self)
+        -> [VolumetricNumberDensity;G] {
+        self.precursor_concentration_array
+    }
+
+    /// returns the neutron population and precursor concentrations
+    /// as a vector of length G+1
+    ///
+    /// the arrangement is
+    /// [neutron population,
+    /// precursor grp 1,
+    /// ...
+    /// precursor grp G]
+    pub fn get_neutron_pop_and_precursor_vector(&self)
+        -> Array1<VolumetricNumberDensity> {
+
+        let mut neutron_pop_and_precursor_vector: Array1<VolumetricNumberDensity>
+            = Array::zeros(G + 1);
+
+        neutron_pop_and_precursor_vector[0] = self.neutron_population_number_density;
+
+        for (i, precursor_concentration) in
+            self.precursor_concentration_array.iter().enumerate() {
+                neutron_pop_and_precursor_vector[i+1] = *precursor_concentration;
+        }
+
+        neutron_pop_and_precursor_vector
+    }
+
+    /// sets the neutron population and precursor concentrations
+    /// from a vector of length G+1
+    ///
+    /// the arrangement is the same as
+    /// get_neutron_pop_and_precursor_vector
+    pub fn set_neutron_pop_and_precursor_vector(&mut self,
+        neutron_pop_and_precursor_vector: &Array1<VolumetricNumberDensity>){
+
+        self.neutron_population_number_density =
+            neutron_pop_and_precursor_vector[0];
+
+        for (i, precursor_concentration) in
+            self.precursor_concentration_array.iter_mut().enumerate() {
+                *precursor_concentration = neutron_pop_and_precursor_vector[i+1];
+        }
+    }
+
+    /// total delayed fraction
+    pub fn get_total_delayed_fraction(&self) -> Ratio {
+
+        let total_delayed_fraction: Ratio =
+            self.delayed_fraction_array.into_iter().sum();
+
+        total_delayed_fraction
+    }
+
+    /// returns the one group equivalent of this PRKE
+    ///
+    /// the total delayed fraction is kept, and the one group
+    /// decay constant is chosen such that the mean precursor
+    /// lifetime weighted by delayed fraction is kept, ie
+    ///
+    /// beta/lambda = sum_i beta_i/lambda_i
+    ///
+    /// the precursor concentration is the sum of all group
+    /// concentrations
+    pub fn get_one_group_equivalent(&self) -> MultiGroupPRKE<1> {
+
+        let total_delayed_fraction = self.get_total_delayed_fraction();
+
+        let beta_weighted_precursor_lifetime: Time =
+            self.delayed_fraction_array.iter()
+            .zip(self.decay_constant_array.iter())
+            .map(|(&beta_i, &lambda_i)|{
+                let weighted_lifetime: Time = beta_i/lambda_i;
+                weighted_lifetime
+            }).sum();
+
+        let one_group_decay_constant: DecayConstant =
+            total_delayed_fraction/beta_weighted_precursor_lifetime;
+
+        let total_precursor_concentration: VolumetricNumberDensity =
+            self.precursor_concentration_array.into_iter().sum();
+
+        MultiGroupPRKE::<1> {
+            decay_constant_array: [one_group_decay_constant],
+            delayed_fraction_array: [total_delayed_fraction],
+            delayed_group_mode: self.delayed_group_mode,
+            neutron_population_number_density:
+                self.neutron_population_number_density,
+            precursor_concentration_array: [total_precursor_concentration],
+        }
+    }
+
+    /// enables you to convert reactivity into keff, useful for calculating
+    /// the neutron generation time
+    pub fn get_keff_from_reactivity(reactivity: impl Into<Ratio>) -> Ratio {
+        let reactivity: Ratio = reactivity.into();
+
+        // reactivity is rho
+        //
+        // rho = (k-1)/k
+        //
+        // k * rho = k - 1
+        // k * rho - k = - 1
+        // k - k * rho = 1
+        // k * (1 - rho) = 1
+        // k = 1/(1 - rho)
+        //
+
+        let ratio_one = Ratio::new::<ratio>(1.0);
+
+        ratio_one/(ratio_one - reactivity)
+
+    }
+
+}
@@ -0,0 +1,271 @@
+use ndarray::*;
+use uom::si::f64::*;
+use uom::si::frequency::hertz;
+use uom::si::ratio::ratio;
+use uom::si::time::second;
+use uom::si::volumetric_number_density::per_cubic_meter;
+use uom::si::volumetric_number_rate::per_cubic_meter_second;
+
+use crate::reactivity_profile::ReactivityProfile;
+use crate::teh_o_prke_error::TehOPrkeError;
+use crate::time_stepping::openfoam_adaptive_solver::AdaptiveSolver;
+use crate::time_stepping::openfoam_ode_solver::{ODESolver, ODESolverMethod};
+use crate::time_stepping::openfoam_ode_system::ODESystem;
+use super::MultiGroupPRKE;
+
+impl<const G: usize> MultiGroupPRKE<G> {
+
+    /// solves for the neutron population and precursor concentration
+    /// using any of the ODESolver methods with adaptive step size
+    /// control
+    ///
+    /// the Rosenbrock and Rodas methods handle the stiffness of the 
+    /// PRKE (prompt neutron lifetimes are much shorter than 
+    /// precursor half lives), so they can take much larger steps 
+    /// than the explicit methods, they are given the analytic 
+    /// jacobian of construct_ode_system
+    ///
+    /// returns the neutron population and precursor vector
+    /// as well as the accepted substeps
+    pub fn solve_next_timestep_precursor_concentration_and_neutron_pop_vector_with_ode_solver(
+        &mut self,
+        timestep: Time,
+        reactivity: impl Into<Ratio>,
+        neutron_generation_time: Time,
+        background_source_rate: VolumetricNumberRate,
+        ode_solver_method: ODESolverMethod,
+        adaptive_solver: &AdaptiveSolver)
+        -> Result<(Array1<VolumetricNumberDensity>, Vec<Time>),TehOPrkeError> {
+            let reactivity: Ratio = reactivity.into();
+
+            let prke_ode_system = self.construct_ode_system(
+                reactivity,
+                neutron_generation_time,
+                background_source_rate);
+
+            let mut ode_solver = ODESolver::new_with_adaptive_solver(
+                prke_ode_system, ode_solver_method, *adaptive_solver);
+
+            let mut neutron_pop_and_precursor_vector_si_units: Vec<f64>
+                = self.get_neutron_pop_and_precursor_vector().iter()
+                .map(|num_density|{
+                    num_density.get::<per_cubic_meter>()
+                })
+                .collect();
+
+            let mut dt_try: f64 = timestep.get::<second>();
+
+            let accepted_substep_list: Vec<Time> = ode_solver.solve(
+                0.0,
+                timestep.get::<second>(),
+                &mut neutron_pop_and_precursor_vector_si_units,
+                &mut dt_try)?
+                .into_iter()
+                .map(Time::new::<second>)
+                .collect();
+
+            let precursor_and_neutron_pop_and_source_vector_next_timestep: Array1<VolumetricNumberDensity>
+                = neutron_pop_and_precursor_vector_si_units
+                .into_iter()
+                .map(VolumetricNumberDensity::new::<per_cubic_meter>)
+                .collect();
+
+            self.set_neutron_pop_and_precursor_vector(
+                &precursor_and_neutron_pop_and_source_vector_next_timestep);
+
+            Ok((precursor_and_neutron_pop_and_source_vector_next_timestep, accepted_substep_list))
+    }
+
+    /// solves for the neutron population and precursor concentration
+    /// over [current_time, current_time + timestep] using any of the
+    /// ODESolver methods, with reactivity taken from a profile
+    ///
+    /// the profile is evaluated at every stage time of the solver
+    /// rather than held constant over the timestep, so ramps and 
+    /// oscillations do not lose accuracy to long timesteps
+    ///
+    /// returns the neutron population and precursor vector
+    /// as well as the accepted substeps
+    #[allow(clippy::too_many_arguments)]
+    pub fn solve_next_timestep_precursor_concentration_and_neutron_pop_vector_with_reactivity_profile(
+        &mut self,
+        current_time: Time,
+        timestep: Time,
+        reactivity_profile: &ReactivityProfile,
+        neutron_generation_time: Time,
+        background_source_rate: VolumetricNumberRate,
+        ode_solver_method: ODESolverMethod,
+        adaptive_solver: &AdaptiveSolver)
+        -> Result<(Array1<VolumetricNumberDensity>, Vec<Time>),TehOPrkeError> {
+
+            reactivity_profile.validate()?;
+
+            let prke_ode_system = self.construct_ode_system_with_reactivity_profile(
+                reactivity_profile,
+                neutron_generation_time,
+                background_source_rate);
+
+            let mut ode_solver = ODESolver::new_with_adaptive_solver(
+                prke_ode_system, ode_solver_method, *adaptive_solver);
+
+            let mut neutron_pop_and_precursor_vector_si_units: Vec<f64>
+                = self.get_neutron_pop_and_precursor_vector().iter()
+                .map(|num_density|{
+                    num_density.get::<per_cubic_meter>()
+                })
+                .collect();
+
+            let mut dt_try: f64 = timestep.get::<second>();
+
+            let accepted_substep_list: Vec<Time> = ode_solver.solve(
+                current_time.get::<second>(),
+                (current_time + timestep).get::<second>(),
+                &mut neutron_pop_and_precursor_vector_si_units,
+                &mut dt_try)?
+                .into_iter()
+                .map(Time::new::<second>)
+                .collect();
+
+            let precursor_and_neutron_pop_and_source_vector_next_timestep: Array1<VolumetricNumberDensity>
+                = neutron_pop_and_precursor_vector_si_units
+                .into_iter()
+                .map(VolumetricNumberDensity::new::<per_cubic_meter>)
+                .collect();
+
+            self.set_neutron_pop_and_precursor_vector(
+                &precursor_and_neutron_pop_and_source_vector_next_timestep);
+
+            Ok((precursor_and_neutron_pop_and_source_vector_next_timestep, accepted_substep_list))
+    }
+
+    /// constructs the jacobian of the PRKE, dfdy, for the 
+    /// neutron population and precursor vector [n, C_1 ... C_G]
+    ///
+    /// dn/dt = (rho - beta)/Lambda n(t) + sum_i^n lambda_i C_i + S
+    /// d C_i/dt = beta_i/Lambda n(t) - lambda_i C_i
+    ///
+    /// the system is linear, so this is exact, and it is the 
+    /// same matrix as in construct_coefficient_matrix with the 
+    /// timestep taken out:
+    ///
+    /// coefficient_matrix = I - dt * jacobian
+    pub fn construct_jacobian_matrix(&self,
+        reactivity: Ratio,
+        neutron_generation_time: Time) -> Array2<Frequency> {
+
+        let total_delayed_fraction: Ratio = self.get_total_delayed_fraction();
+
+        let array_width_and_height = G + 1;
+
+        let mut jacobian_matrix: Array2<Frequency> =
+            Array::zeros((array_width_and_height, array_width_and_height));
+
+        jacobian_matrix[[0,0]] =
+            (reactivity - total_delayed_fraction)/neutron_generation_time;
+
+        for (i, (&lambda_i, &beta_i)) in self.decay_constant_array.iter()
+            .zip(self.delayed_fraction_array.iter())
+            .enumerate() {
+
+                // top row
+                jacobian_matrix[[0,i+1]] = lambda_i;
+                // left column
+                jacobian_matrix[[i+1,0]] = beta_i/neutron_generation_time;
+                // diagonal
+                jacobian_matrix[[i+1,i+1]] = -lambda_i;
+        }
+
+        jacobian_matrix
+    }
+
+    /// returns the PRKE as an ODESystem in SI units, for the 
+    /// neutron population and precursor vector [n, C_1 ... C_G]
+    ///
+    /// the reactivity, generation time and source are moved into 
+    /// the closures, so the ODESystem can be handed to an ODESolver
+    /// (or the RKF45 struct) and stepped on its own, the analytic 
+    /// jacobian comes along with it
+    pub fn construct_ode_system(&self,
+        reactivity: Ratio,
+        neutron_generation_time: Time,
+        background_source_rate: VolumetricNumberRate) -> ODESystem {
+
+            self.construct_ode_system_with_reactivity_profile(
+                &ReactivityProfile::Constant(reactivity),
+                neutron_generation_time,
+                background_source_rate)
+    }
+
+    /// returns the PRKE as an ODESystem in SI units, with the 
+    /// reactivity following a profile
+    ///
+    /// the independent variable is the time in seconds on the 
+    /// profile's clock, so the ODESystem must be solved from the 
+    /// current time rather than from zero
+    ///
+    /// the jacobian includes dfdt, which comes from the rate of 
+    /// change of reactivity:
+    ///
+    /// d/dt [dn/dt] = (d rho/dt)/Lambda n(t)
+    pub fn construct_ode_system_with_reactivity_profile(&self,
+        reactivity_profile: &ReactivityProfile,
+        neutron_generation_time: Time,
+        background_source_rate: VolumetricNumberRate) -> ODESystem {
+
+            let total_delayed_fraction: f64 = self.get_total_delayed_fraction().get::<ratio>();
+            let big_lambda: f64 = neutron_generation_time.get::<second>();
+            let beta_by_big_lambda_array: [f64;G] =
+                self.delayed_fraction_array.map(|beta_i|{
+                    (beta_i/neutron_generation_time).get::<hertz>()
+                });
+            let decay_constant_array: [f64;G] =
+                self.decay_constant_array.map(|lambda_i| lambda_i.get::<hertz>());
+            let background_source_rate: f64 =
+                background_source_rate.get::<per_cubic_meter_second>();
+
+            let rho_minus_beta_over_big_lambda = {
+                let reactivity_profile = reactivity_profile.clone();
+                move |t: f64| -> f64 {
+                    (reactivity_profile.get_reactivity(Time::new::<second>(t)).get::<ratio>()
+                     - total_delayed_fraction)/big_lambda
+                }
+            };
+
+            // dn/dt = (rho - beta)/Lambda n(t) + sum_i^n lambda_i C_i + S
+            // d C_i/dt = beta_i/Lambda n(t) - lambda_i C_i
+            let prke_derivatives = {
+                let rho_minus_beta_over_big_lambda = rho_minus_beta_over_big_lambda.clone();
+                move |t: f64, y: &[f64], dydt: &mut [f64]| {
+
+                    dydt[0] = rho_minus_beta_over_big_lambda(t) * y[0] + background_source_rate;
+
+                    for i in 0..G {
+                        dydt[0] += decay_constant_array[i] * y[i+1];
+                        dydt[i+1] = beta_by_big_lambda_array[i] * y[0]
+                            - decay_constant_array[i] * y[i+1];
+                    }
+                }
+            };
+
+            // only the top left entry changes with time
+            let jacobian_matrix_si_units: Array2<f64> = self.construct_jacobian_matrix(
+                Ratio::new::<ratio>(0.0),
+                neutron_generation_time)
+                .map(|jacobian_entry| jacobian_entry.get::<hertz>());
+
+            let reactivity_profile = reactivity_profile.clone();
+
+            let prke_jacobian = move |t: f64, y: &[f64], dfdt: &mut [f64], dfdy: &mut Array2<f64>| {
+                let reactivity_rate: f64 = reactivity_profile
+                    .get_reactivity_rate(Time::new::<second>(t)).get::<hertz>();
+
+                dfdt.fill(0.0);
+                dfdt[0] = reactivity_rate/big_lambda * y[0];
+
+                dfdy.assign(&jacobian_matrix_si_units);
+                dfdy[[0,0]] = rho_minus_beta_over_big_lambda(t);
+            };
+
+            ODESystem::new_with_jacobian(prke_derivatives, prke_jacobian)
+    }
+}
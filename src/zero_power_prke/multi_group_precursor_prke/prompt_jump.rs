@@ -0,0 +1,163 @@
+use ndarray::*;
+use uom::si::f64::*;
+use uom::si::frequency::hertz;
+use uom::si::ratio::ratio;
+use uom::si::time::second;
+use uom::si::volumetric_number_density::per_cubic_meter;
+use uom::si::volumetric_number_rate::per_cubic_meter_second;
+
+use crate::teh_o_prke_error::TehOPrkeError;
+use super::MultiGroupPRKE;
+
+impl<const G: usize> MultiGroupPRKE<G> {
+
+    /// returns the neutron population under the prompt jump
+    /// approximation (zero prompt neutron lifetime) for the
+    /// current precursor concentrations
+    ///
+    /// setting dn/dt = 0 in
+    ///
+    /// dn/dt = (rho - beta)/Lambda n(t) + sum_i lambda_i C_i + S
+    ///
+    /// gives
+    ///
+    /// n = Lambda (sum_i lambda_i C_i + S)/(beta - rho)
+    ///
+    /// this only holds below prompt critical, so rho >= beta is
+    /// refused
+    pub fn get_prompt_jump_neutron_population_density(&self,
+        reactivity: impl Into<Ratio>,
+        neutron_generation_time: Time,
+        background_source_rate: VolumetricNumberRate)
+        -> Result<VolumetricNumberDensity, TehOPrkeError> {
+            let reactivity: Ratio = reactivity.into();
+
+            let beta_minus_rho: Ratio =
+                self.check_prompt_jump_reactivity(reactivity)?;
+
+            let total_decay_rate: VolumetricNumberRate = self.decay_constant_array.iter()
+                .zip(self.precursor_concentration_array.iter())
+                .map(|(&lambda_i, &precursor_concentration)| {
+                    let decay_rate: VolumetricNumberRate =
+                        (lambda_i * precursor_concentration).into();
+                    decay_rate
+                })
+                .sum();
+
+            let neutron_population_number_density: VolumetricNumberDensity =
+                (neutron_generation_time * (total_decay_rate + background_source_rate)
+                 /beta_minus_rho).into();
+
+            Ok(neutron_population_number_density)
+    }
+
+    /// advances the PRKE with the prompt jump approximation,
+    /// only the precursor equations are integrated and the neutron
+    /// population follows algebraically
+    ///
+    /// substituting n into the precursor equations:
+    ///
+    /// dC_i/dt = beta_i/(beta - rho) (sum_j lambda_j C_j + S) - lambda_i C_i
+    ///
+    /// this is stepped with backward Euler, the reactivity is that
+    /// at the end of the step. The matrix is a diagonal plus a rank
+    /// one update, so with Q = sum_j lambda_j C_j (new timestep),
+    ///
+    /// C_i = (rhs_i + u_i Q)/a_i
+    ///
+    /// Q = [sum_i lambda_i rhs_i/a_i] / [1 - sum_i lambda_i u_i/a_i]
+    ///
+    /// where a_i = 1 + dt lambda_i, u_i = dt beta_i/(beta - rho) and
+    /// rhs_i = C_i_old + u_i S. Like the implicit solver, this is O(G)
+    ///
+    /// the prompt timescale is not resolved, so timesteps of seconds
+    /// can be taken for slow transients (xenon, load follow). Note
+    /// the first step jumps n to the prompt jump value
+    ///
+    /// rho >= beta is refused, as is a timestep so long that
+    /// backward Euler cannot follow a growing transient
+    /// (dt longer than about the stable period)
+    pub fn solve_next_timestep_precursor_concentration_and_neutron_pop_vector_prompt_jump(
+        &mut self,
+        timestep: Time,
+        reactivity: impl Into<Ratio>,
+        neutron_generation_time: Time,
+        background_source_rate: VolumetricNumberRate)
+        -> Result<Array1<VolumetricNumberDensity>,TehOPrkeError> {
+            let reactivity: Ratio = reactivity.into();
+
+            let beta_minus_rho: f64 =
+                self.check_prompt_jump_reactivity(reactivity)?.get::<ratio>();
+
+            // all SI units
+            let dt: f64 = timestep.get::<second>();
+            let source: f64 = background_source_rate.get::<per_cubic_meter_second>();
+
+            let mut reduced_decay_rate_rhs: f64 = 0.0;
+            let mut reduced_decay_rate_coefficient: f64 = 1.0;
+
+            for i in 0..G {
+                let lambda_i: f64 = self.decay_constant_array[i].get::<hertz>();
+                let a_i: f64 = 1.0 + dt * lambda_i;
+                let u_i: f64 = dt * self.delayed_fraction_array[i].get::<ratio>()/beta_minus_rho;
+                let rhs_i: f64 = self.precursor_concentration_array[i].get::<per_cubic_meter>()
+                    + u_i * source;
+
+                reduced_decay_rate_rhs += lambda_i * rhs_i/a_i;
+                reduced_decay_rate_coefficient -= lambda_i * u_i/a_i;
+            }
+
+            if reduced_decay_rate_coefficient <= 0.0 {
+                return Err(TehOPrkeError::GenericStringError(
+                    "timestep is too long for the prompt jump solver at this \
+                    reactivity, try a shorter timestep".to_string()
+                ));
+            }
+
+            // Q = sum_j lambda_j C_j at the new timestep
+            let total_decay_rate: f64 = reduced_decay_rate_rhs/reduced_decay_rate_coefficient;
+
+            let mut precursor_and_neutron_pop_and_source_vector_next_timestep: Array1<VolumetricNumberDensity>
+                = Array::zeros(G + 1);
+
+            for i in 0..G {
+                let lambda_i: f64 = self.decay_constant_array[i].get::<hertz>();
+                let a_i: f64 = 1.0 + dt * lambda_i;
+                let u_i: f64 = dt * self.delayed_fraction_array[i].get::<ratio>()/beta_minus_rho;
+                let rhs_i: f64 = self.precursor_concentration_array[i].get::<per_cubic_meter>()
+                    + u_i * source;
+
+                precursor_and_neutron_pop_and_source_vector_next_timestep[i+1] =
+                    VolumetricNumberDensity::new::<per_cubic_meter>(
+                        (rhs_i + u_i * total_decay_rate)/a_i);
+            }
+
+            precursor_and_neutron_pop_and_source_vector_next_timestep[0] =
+                VolumetricNumberDensity::new::<per_cubic_meter>(
+                    neutron_generation_time.get::<second>() * (total_decay_rate + source)
+                    /beta_minus_rho);
+
+            self.set_neutron_pop_and_precursor_vector(
+                &precursor_and_neutron_pop_and_source_vector_next_timestep);
+
+            Ok(precursor_and_neutron_pop_and_source_vector_next_timestep)
+    }
+
+    /// returns beta - rho, or an error at or above prompt critical
+    fn check_prompt_jump_reactivity(&self,
+        reactivity: Ratio) -> Result<Ratio, TehOPrkeError> {
+
+        let beta_minus_rho: Ratio = self.get_total_delayed_fraction() - reactivity;
+
+        if beta_minus_rho.get::<ratio>() <= 0.0 {
+            return Err(TehOPrkeError::GenericStringError(
+                format!("prompt jump approximation needs rho < beta, \
+                    rho = {}, beta = {}",
+                    reactivity.get::<ratio>(),
+                    self.get_total_delayed_fraction().get::<ratio>())
+            ));
+        }
+
+        Ok(beta_minus_rho)
+    }
+}
@@ -0,0 +1,275 @@
+use ndarray::*;
+use uom::si::f64::*;
+use uom::si::frequency::hertz;
+use uom::si::ratio::ratio;
+use uom::si::time::second;
+use uom::si::volumetric_number_density::per_cubic_meter;
+use uom::si::volumetric_number_rate::per_cubic_meter_second;
+
+use crate::linear_algebra::matrix_exponential;
+use crate::teh_o_prke_error::TehOPrkeError;
+use crate::zero_power_prke::multi_group_precursor_prke::MultiGroupPRKE;
+
+/// multipoint kinetics, with regions coupled by a diffusive
+/// exchange of neutrons
+///
+/// the core is split into regions, eg. the left and right halves
+/// of a core with left and right control rods, each with its own
+/// neutron population, precursors, reactivity and generation time.
+/// For region j,
+///
+/// dn_j/dt = (rho_j - beta_j)/Lambda_j n_j + sum_i lambda_i C_ij
+/// + sum_k alpha_jk (n_k - n_j) + S_j
+///
+/// dC_ij/dt = beta_ij/Lambda_j n_j - lambda_i C_ij
+///
+/// alpha_jk is an exchange rate, like a diffusion term between
+/// neighbouring nodes: region j gains neutrons from region k in
+/// proportion to the difference in their populations, and at once.
+/// So rho_j is the region's reactivity when all regions have the
+/// same neutron population (a flat shape)
+///
+/// this is not the coupled core model of Avery, where region k
+/// drives fissions in region j through a coupling reactivity k_jk
+/// after a transit delay. There is no transit delay here, and
+/// alpha_jk is not a k_jk (a k_jk model would need alpha_jk n_k
+/// without the - alpha_jk n_j, and the delays in the jacobian)
+///
+/// with one region, this is MultiGroupPRKE. With tight coupling
+/// (alpha much larger than the prompt period), the regions move
+/// together as one point reactor with the mean reactivity, and the
+/// looser the coupling, the more the shape tilts towards the more
+/// reactive regions
+#[derive(Debug,Clone)]
+pub struct MultipointPRKE<const G: usize> {
+    /// the point kinetics of each region, holding its delayed
+    /// neutron data, neutron population and precursors
+    pub region_list: Vec<MultiGroupPRKE<G>>,
+    /// exchange rates alpha_jk from region k into region j,
+    /// the diagonal is not used
+    pub coupling_coefficient_matrix: Array2<Frequency>,
+}
+
+impl<const G: usize> MultipointPRKE<G> {
+
+    /// constructs the multipoint PRKE from the regions and their
+    /// coupling coefficients
+    ///
+    /// set each region to equilibrium beforehand with its own
+    /// generation time, at the same neutron population if the
+    /// regions start critical with a flat shape
+    pub fn new(region_list: Vec<MultiGroupPRKE<G>>,
+        coupling_coefficient_matrix: Array2<Frequency>) -> Result<Self, TehOPrkeError> {
+
+        let number_of_regions = region_list.len();
+
+        if number_of_regions == 0 {
+            return Err(TehOPrkeError::GenericStringError(
+                "multipoint PRKE needs at least one region".to_string()
+            ));
+        }
+
+        if coupling_coefficient_matrix.dim() != (number_of_regions, number_of_regions) {
+            return Err(TehOPrkeError::GenericStringError(
+                format!("coupling coefficient matrix must be {} by {}",
+                    number_of_regions, number_of_regions)
+            ));
+        }
+
+        let has_negative_coupling = coupling_coefficient_matrix.indexed_iter()
+            .any(|((j, k), alpha_jk)| j != k && alpha_jk.get::<hertz>() < 0.0);
+
+        if has_negative_coupling {
+            return Err(TehOPrkeError::GenericStringError(
+                "coupling coefficients cannot be negative".to_string()
+            ));
+        }
+
+        Ok(Self { region_list, coupling_coefficient_matrix })
+    }
+
+    /// constructs two regions coupled both ways by the same
+    /// coupling coefficient, eg. two halves of a core
+    pub fn new_two_region(first_region: MultiGroupPRKE<G>,
+        second_region: MultiGroupPRKE<G>,
+        coupling_coefficient: Frequency) -> Result<Self, TehOPrkeError> {
+
+        let mut coupling_coefficient_matrix: Array2<Frequency> = Array::zeros((2, 2));
+        coupling_coefficient_matrix[[0, 1]] = coupling_coefficient;
+        coupling_coefficient_matrix[[1, 0]] = coupling_coefficient;
+
+        Self::new(vec![first_region, second_region], coupling_coefficient_matrix)
+    }
+
+    /// returns the number of regions
+    pub fn get_number_of_regions(&self) -> usize {
+        self.region_list.len()
+    }
+
+    /// returns the neutron population of each region
+    pub fn get_neutron_population_density_list(&self) -> Vec<VolumetricNumberDensity> {
+        self.region_list.iter()
+            .map(|region| region.get_current_neutron_population_density())
+            .collect()
+    }
+
+    /// returns the mean neutron population over the regions
+    ///
+    /// this is the core average for regions of equal volume
+    pub fn get_mean_neutron_population_density(&self) -> VolumetricNumberDensity {
+        let total_neutron_population_density: VolumetricNumberDensity =
+            self.get_neutron_population_density_list().into_iter().sum();
+
+        total_neutron_population_density/self.get_number_of_regions() as f64
+    }
+
+    /// returns the peak to mean neutron population, which is one
+    /// for a flat shape and goes up as the shape tilts
+    pub fn get_peak_to_mean_ratio(&self) -> Ratio {
+        let peak_neutron_population_density = self.get_neutron_population_density_list()
+            .into_iter()
+            .fold(VolumetricNumberDensity::new::<per_cubic_meter>(0.0),
+                |peak, neutron_population_density| peak.max(neutron_population_density));
+
+        peak_neutron_population_density/self.get_mean_neutron_population_density()
+    }
+
+    /// constructs the jacobian of the whole multipoint system, the
+    /// state being [n_1, C_11 ... C_G1, n_2, C_12 ... C_G2, ...]
+    ///
+    /// each diagonal block is the region's own jacobian (see
+    /// MultiGroupPRKE::construct_jacobian_matrix) and the coupling
+    /// only links the neutron populations
+    ///
+    /// the reactivities may be Ratio or Reactivity
+    pub fn construct_jacobian_matrix<R: Into<Ratio> + Copy>(&self,
+        reactivity_list: &[R],
+        neutron_generation_time_list: &[Time]) -> Result<Array2<Frequency>, TehOPrkeError> {
+
+        let number_of_regions = self.get_number_of_regions();
+
+        if reactivity_list.len() != number_of_regions
+            || neutron_generation_time_list.len() != number_of_regions {
+                return Err(TehOPrkeError::GenericStringError(
+                    format!("need one reactivity and one generation time for \
+                        each of the {} regions", number_of_regions)
+                ));
+        }
+
+        let block_size = G + 1;
+        let system_size = number_of_regions * block_size;
+
+        let mut jacobian_matrix: Array2<Frequency> =
+            Array::zeros((system_size, system_size));
+
+        for (j, region) in self.region_list.iter().enumerate() {
+
+            let region_jacobian_matrix = region.construct_jacobian_matrix(
+                reactivity_list[j].into(),
+                neutron_generation_time_list[j]);
+
+            jacobian_matrix
+                .slice_mut(s![j * block_size..(j + 1) * block_size,
+                    j * block_size..(j + 1) * block_size])
+                .assign(&region_jacobian_matrix);
+
+            for k in (0..number_of_regions).filter(|&k| k != j) {
+                let alpha_jk = self.coupling_coefficient_matrix[[j, k]];
+
+                jacobian_matrix[[j * block_size, k * block_size]] += alpha_jk;
+                jacobian_matrix[[j * block_size, j * block_size]] -= alpha_jk;
+            }
+        }
+
+        Ok(jacobian_matrix)
+    }
+
+    /// advances every region exactly over a timestep where the
+    /// reactivities, generation times and sources are constant,
+    /// returns the neutron population of each region and updates
+    /// the regions
+    ///
+    /// as in the single region matrix exponential stepper, the
+    /// sources are appended to the state so that
+    ///
+    /// [y, s](t + dt) = exp([[A dt, B], [0, 0]]) [y, s](t)
+    ///
+    /// with s_j = S_j dt. Nothing is cached here, as region
+    /// reactivities usually change every step (eg. rods moving
+    /// on one side of the core)
+    pub fn solve_next_timestep_precursor_concentration_and_neutron_pop_vector_matrix_exponential<R: Into<Ratio> + Copy>(
+        &mut self,
+        timestep: Time,
+        reactivity_list: &[R],
+        neutron_generation_time_list: &[Time],
+        background_source_rate_list: &[VolumetricNumberRate])
+        -> Result<Vec<VolumetricNumberDensity>, TehOPrkeError> {
+
+            let number_of_regions = self.get_number_of_regions();
+
+            if background_source_rate_list.len() != number_of_regions {
+                return Err(TehOPrkeError::GenericStringError(
+                    format!("need one background source rate for each of the \
+                        {} regions", number_of_regions)
+                ));
+            }
+
+            let jacobian_matrix = self.construct_jacobian_matrix(
+                reactivity_list,
+                neutron_generation_time_list)?;
+
+            let dt: f64 = timestep.get::<second>();
+            let block_size = G + 1;
+            let system_size = number_of_regions * block_size;
+            let augmented_size = system_size + number_of_regions;
+
+            let mut augmented_matrix_times_dt: Array2<f64> =
+                Array2::zeros((augmented_size, augmented_size));
+
+            for ((i, j), jacobian_entry) in jacobian_matrix.indexed_iter() {
+                augmented_matrix_times_dt[[i, j]] = jacobian_entry.get::<hertz>() * dt;
+            }
+
+            for j in 0..number_of_regions {
+                augmented_matrix_times_dt[[j * block_size, system_size + j]] = 1.0;
+            }
+
+            let state_transition_matrix = matrix_exponential(&augmented_matrix_times_dt)?;
+
+            let current_augmented_vector_si_units: Vec<f64> = self.region_list.iter()
+                .flat_map(|region| region.get_neutron_pop_and_precursor_vector())
+                .map(|num_density| num_density.get::<per_cubic_meter>())
+                .chain(background_source_rate_list.iter().map(|background_source_rate| {
+                    background_source_rate.get::<per_cubic_meter_second>() * dt
+                }))
+                .collect();
+
+            for (j, region) in self.region_list.iter_mut().enumerate() {
+
+                let neutron_pop_and_precursor_vector_next_timestep: Array1<VolumetricNumberDensity>
+                    = (j * block_size..(j + 1) * block_size).map(|i| {
+                        let number_density_float: f64 = state_transition_matrix.row(i).iter()
+                            .zip(current_augmented_vector_si_units.iter())
+                            .map(|(transition_entry, y_k)| transition_entry * y_k)
+                            .sum();
+                        VolumetricNumberDensity::new::<per_cubic_meter>(number_density_float)
+                    }).collect();
+
+                region.set_neutron_pop_and_precursor_vector(
+                    &neutron_pop_and_precursor_vector_next_timestep);
+            }
+
+            Ok(self.get_neutron_population_density_list())
+    }
+
+    /// returns the effective reactivity of the whole core when the
+    /// coupling is tight, ie. the mean of the region reactivities,
+    /// for regions of equal volume and generation time
+    pub fn get_tightly_coupled_reactivity<R: Into<Ratio> + Copy>(reactivity_list: &[R]) -> Ratio {
+        let total_reactivity: f64 = reactivity_list.iter()
+            .map(|&reactivity| reactivity.into().get::<ratio>())
+            .sum();
+
+        Ratio::new::<ratio>(total_reactivity/reactivity_list.len() as f64)
+    }
+}
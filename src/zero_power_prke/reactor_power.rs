@@ -0,0 +1,129 @@
+use uom::ConstZero;
+use uom::si::energy::megaelectronvolt;
+use uom::si::f64::*;
+use uom::si::velocity::meter_per_second;
+
+use crate::teh_o_prke_error::TehOPrkeError;
+use crate::zero_power_prke::multi_group_precursor_prke::MultiGroupPRKE;
+use crate::zero_power_prke::transient_runner::{StopCondition, TransientHistory};
+
+/// converts between the neutron population number density of the
+/// PRKE and thermal (fission) power
+///
+/// P = n v Sigma_f V E_f
+///
+/// where v is the neutron speed, Sigma_f the macroscopic fission
+/// cross section, V the core volume and E_f the energy released
+/// per fission
+///
+/// this is prompt fission power only, decay heat is not included
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub struct ReactorPowerModel {
+    /// energy released per fission, E_f
+    pub energy_per_fission: Energy,
+    /// macroscopic fission cross section, Sigma_f
+    pub macroscopic_fission_xs: LinearNumberDensity,
+    /// neutron speed, v
+    pub neutron_speed: Velocity,
+    /// core volume, V
+    pub reactor_volume: Volume,
+}
+
+impl ReactorPowerModel {
+
+    /// constructs a power model, all the quantities must be
+    /// positive
+    pub fn new(energy_per_fission: Energy,
+        macroscopic_fission_xs: LinearNumberDensity,
+        neutron_speed: Velocity,
+        reactor_volume: Volume) -> Result<Self, TehOPrkeError> {
+
+        if neutron_speed <= Velocity::ZERO
+            || macroscopic_fission_xs <= LinearNumberDensity::ZERO
+            || reactor_volume <= Volume::ZERO
+            || energy_per_fission <= Energy::ZERO {
+                return Err(TehOPrkeError::GenericStringError(
+                    "neutron speed, fission cross section, volume and energy \
+                    per fission must all be positive".to_string()
+                ));
+        }
+
+        Ok(Self {
+            energy_per_fission,
+            macroscopic_fission_xs,
+            neutron_speed,
+            reactor_volume,
+        })
+    }
+
+    /// constructs a power model for a thermal reactor, with
+    /// 200 MeV per fission and thermal neutrons at 2200 m/s
+    pub fn new_thermal_reactor(macroscopic_fission_xs: LinearNumberDensity,
+        reactor_volume: Volume) -> Result<Self, TehOPrkeError> {
+
+        Self::new(Energy::new::<megaelectronvolt>(200.0),
+            macroscopic_fission_xs,
+            Velocity::new::<meter_per_second>(2200.0),
+            reactor_volume)
+    }
+
+    /// returns the fission rate per unit volume for a neutron 
+    /// population, n v Sigma_f
+    ///
+    /// this is what drives fission product buildup, eg. xenon 135
+    pub fn get_fission_rate_density(&self,
+        neutron_population_number_density: VolumetricNumberDensity) -> VolumetricNumberRate {
+
+        (neutron_population_number_density * self.neutron_speed
+            * self.macroscopic_fission_xs).into()
+    }
+
+    /// returns the thermal power for a neutron population
+    pub fn get_power(&self,
+        neutron_population_number_density: VolumetricNumberDensity) -> Power {
+
+        self.get_fission_rate_density(neutron_population_number_density) 
+            * self.reactor_volume * self.energy_per_fission
+    }
+
+    /// returns the neutron population for a thermal power
+    pub fn get_neutron_population_number_density(&self,
+        reactor_power: Power) -> VolumetricNumberDensity {
+
+        (reactor_power/(self.neutron_speed * self.macroscopic_fission_xs
+                        * self.reactor_volume * self.energy_per_fission)).into()
+    }
+
+    /// returns a stop condition for the TransientRunner which trips
+    /// when power rises above the given value
+    pub fn get_power_above_stop_condition(&self,
+        reactor_power: Power) -> StopCondition {
+
+        StopCondition::NeutronPopulationAbove(
+            self.get_neutron_population_number_density(reactor_power))
+    }
+}
+
+impl<const G: usize> MultiGroupPRKE<G> {
+
+    /// returns the current thermal power
+    pub fn get_reactor_power(&self,
+        reactor_power_model: &ReactorPowerModel) -> Power {
+
+        reactor_power_model.get_power(self.get_current_neutron_population_density())
+    }
+}
+
+impl<const G: usize> TransientHistory<G> {
+
+    /// returns the thermal power at each output time
+    pub fn get_power_list(&self,
+        reactor_power_model: &ReactorPowerModel) -> Vec<Power> {
+
+        self.snapshot_list.iter()
+            .map(|snapshot| {
+                reactor_power_model.get_power(snapshot.neutron_population_number_density)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,210 @@
+use uom::ConstZero;
+use uom::si::f64::*;
+use uom::si::ratio::ratio;
+
+use crate::teh_o_prke_error::TehOPrkeError;
+use super::{DecayConstant, FissioningNuclideType, SixGroupPRKE};
+
+/// effective six group delayed neutron data for a core where
+/// several nuclides fission at once
+///
+/// the user supplies fission fractions for each nuclide, and
+/// the effective beta_i and lambda_i are fission fraction weighted:
+///
+/// beta_i (eff) = sum_k f_k beta_ik
+///
+/// lambda_i (eff) = beta_i (eff) / (sum_k f_k beta_ik/lambda_ik)
+///
+/// the decay constant is weighted such that the precursor inventory
+/// (beta_i/lambda_i) at equilibrium is kept
+///
+/// strictly speaking, the weights should be the fraction of neutrons
+/// produced by each nuclide (fission fraction times nu), if you want
+/// that, just supply f_k nu_k as the fission fraction
+#[derive(Debug,Clone)]
+pub struct MixedNuclideDelayedNeutronData {
+    /// fission fractions for each fissioning nuclide
+    ///
+    /// these need not add up to one, they are normalised
+    /// when calculating effective delayed neutron data
+    pub fission_fraction_list: Vec<(FissioningNuclideType, Ratio)>,
+}
+
+impl MixedNuclideDelayedNeutronData {
+
+    /// constructs the mixed nuclide delayed neutron data
+    /// from a list of fission fractions
+    pub fn new(fission_fraction_list: Vec<(FissioningNuclideType, Ratio)>)
+        -> Result<Self, TehOPrkeError> {
+
+        let mixed_nuclide_data = Self { fission_fraction_list };
+
+        // checks if fractions are okay
+        mixed_nuclide_data.get_normalised_fission_fraction_list()?;
+
+        Ok(mixed_nuclide_data)
+    }
+
+    /// sets the fission fraction of one nuclide
+    ///
+    /// if the nuclide is not in the list, it is added,
+    /// this is meant to be updated during a run, eg. as Pu builds up
+    pub fn set_fission_fraction(&mut self,
+        nuclide: FissioningNuclideType,
+        fission_fraction: Ratio) -> Result<(), TehOPrkeError> {
+
+        if fission_fraction.get::<ratio>() < 0.0 {
+            return Err(TehOPrkeError::GenericStringError(
+                "fission fraction cannot be negative".to_string()
+            ));
+        }
+
+        match self.fission_fraction_list.iter_mut()
+            .find(|(listed_nuclide, _)| *listed_nuclide == nuclide) {
+                Some((_, listed_fission_fraction)) => {
+                    *listed_fission_fraction = fission_fraction;
+                },
+                None => {
+                    self.fission_fraction_list.push((nuclide, fission_fraction));
+                },
+        }
+
+        Ok(())
+    }
+
+    /// returns the fission fractions normalised such that they
+    /// add up to one
+    pub fn get_normalised_fission_fraction_list(&self)
+        -> Result<Vec<(FissioningNuclideType, Ratio)>, TehOPrkeError> {
+
+        let mut total_fission_fraction = Ratio::ZERO;
+
+        for (nuclide, fission_fraction) in self.fission_fraction_list.iter() {
+
+            if fission_fraction.get::<ratio>() < 0.0 {
+                return Err(TehOPrkeError::GenericStringError(
+                    format!("fission fraction for {:?} cannot be negative", nuclide)
+                ));
+            }
+            total_fission_fraction += *fission_fraction;
+        }
+
+        if total_fission_fraction.get::<ratio>() <= 0.0 {
+            return Err(TehOPrkeError::GenericStringError(
+                "fission fractions must add up to more than zero".to_string()
+            ));
+        }
+
+        let normalised_fission_fraction_list = self.fission_fraction_list.iter()
+            .map(|&(nuclide, fission_fraction)|{
+                (nuclide, fission_fraction/total_fission_fraction)
+            }).collect();
+
+        Ok(normalised_fission_fraction_list)
+    }
+
+    /// returns the fission fraction weighted delayed fraction array
+    pub fn get_effective_delayed_fraction_array(&self)
+        -> Result<[Ratio;6], TehOPrkeError> {
+
+        let mut effective_delayed_fraction_array = [Ratio::ZERO;6];
+
+        for (nuclide, fission_fraction) in
+            self.get_normalised_fission_fraction_list()? {
+
+                let delayed_fraction_array = nuclide.get_delayed_fraction_array();
+
+                for (effective_beta_i, beta_i) in
+                    effective_delayed_fraction_array.iter_mut()
+                    .zip(delayed_fraction_array.iter()) {
+                        *effective_beta_i += fission_fraction * *beta_i;
+                }
+        }
+
+        Ok(effective_delayed_fraction_array)
+    }
+
+    /// returns the effective decay constant array
+    ///
+    /// lambda_i (eff) = beta_i (eff) / (sum_k f_k beta_ik/lambda_ik)
+    pub fn get_effective_decay_constant_array(&self)
+        -> Result<[DecayConstant;6], TehOPrkeError> {
+
+        let effective_delayed_fraction_array =
+            self.get_effective_delayed_fraction_array()?;
+
+        // this is sum_k f_k beta_ik/lambda_ik
+        let mut weighted_precursor_lifetime_array = [Time::ZERO;6];
+
+        for (nuclide, fission_fraction) in
+            self.get_normalised_fission_fraction_list()? {
+
+                let delayed_fraction_array = nuclide.get_delayed_fraction_array();
+                let decay_constant_array = nuclide.get_decay_constant_array();
+
+                for (i, weighted_precursor_lifetime) in
+                    weighted_precursor_lifetime_array.iter_mut().enumerate() {
+                        *weighted_precursor_lifetime +=
+                            fission_fraction * delayed_fraction_array[i]
+                            / decay_constant_array[i];
+                }
+        }
+
+        let mut effective_decay_constant_array = [DecayConstant::ZERO;6];
+
+        for (i, effective_decay_constant) in
+            effective_decay_constant_array.iter_mut().enumerate() {
+                *effective_decay_constant =
+                    effective_delayed_fraction_array[i]
+                    / weighted_precursor_lifetime_array[i];
+        }
+
+        Ok(effective_decay_constant_array)
+    }
+
+    /// returns the nuclide with the largest fission fraction
+    pub fn get_dominant_nuclide(&self) -> Result<FissioningNuclideType, TehOPrkeError> {
+
+        let normalised_fission_fraction_list =
+            self.get_normalised_fission_fraction_list()?;
+
+        let mut dominant_nuclide_and_fraction = normalised_fission_fraction_list[0];
+
+        for nuclide_and_fraction in normalised_fission_fraction_list.into_iter() {
+            if nuclide_and_fraction.1 > dominant_nuclide_and_fraction.1 {
+                dominant_nuclide_and_fraction = nuclide_and_fraction;
+            }
+        }
+
+        Ok(dominant_nuclide_and_fraction.0)
+    }
+
+    /// constructs a SixGroupPRKE from the effective delayed neutron data
+    ///
+    /// the delayed_group_mode is set to the nuclide with the
+    /// largest fission fraction
+    pub fn new_six_group_prke(&self) -> Result<SixGroupPRKE, TehOPrkeError> {
+
+        Ok(SixGroupPRKE::new(
+            self.get_effective_decay_constant_array()?,
+            self.get_effective_delayed_fraction_array()?,
+            self.get_dominant_nuclide()?))
+    }
+
+    /// updates the delayed neutron data of an existing SixGroupPRKE
+    ///
+    /// the neutron population and precursor concentrations are kept,
+    /// so this can be called during a run as fission fractions drift
+    pub fn update_six_group_prke_delayed_data(&self,
+        prke_six_group: &mut SixGroupPRKE) -> Result<(), TehOPrkeError> {
+
+        prke_six_group.decay_constant_array =
+            self.get_effective_decay_constant_array()?;
+        prke_six_group.delayed_fraction_array =
+            self.get_effective_delayed_fraction_array()?;
+        prke_six_group.delayed_group_mode =
+            self.get_dominant_nuclide()?;
+
+        Ok(())
+    }
+}
@@ -1,54 +1,117 @@
 use uom::ConstZero;
 use uom::si::f64::*;
 use uom::si::volumetric_number_density::per_cubic_meter;
-use uom::si::ratio::ratio;
 
-/// contains six group delayed precursor decay constants and 
+/// contains six group delayed precursor decay constants and
 /// delayed fraction
 pub mod six_group_constants;
-use six_group_constants::FissioningNuclideType;
+pub use six_group_constants::*;
+
+/// fission fraction weighted six group delayed neutron data
+/// for cores with several fissioning nuclides
+pub mod mixed_nuclide_delayed_data;
+pub use mixed_nuclide_delayed_data::MixedNuclideDelayedNeutronData;
+
+use crate::teh_o_prke_error::TehOPrkeError;
+pub use crate::zero_power_prke::multi_group_precursor_prke::DecayConstant;
+use crate::zero_power_prke::multi_group_precursor_prke::MultiGroupPRKE;
+use crate::zero_power_prke::reactor_power::ReactorPowerModel;
 
-/// Decay Constant is essentially the same units as frequency
-pub type DecayConstant = Frequency;
 /// SixGroupPRKE
-#[derive(Debug,Clone,Copy)]
-pub struct SixGroupPRKE {
-    /// contains an array for the various half lives 
-    /// of the delayed precursors
-    pub decay_constant_array: [DecayConstant;6],
-    /// contains delayed fraction arrays for the delayed precursors 
-    /// this is different for u235, u233 and Pu239
-    pub delayed_fraction_array: [Ratio;6],
-    /// determines the set of delayed group constants based on your choice 
-    /// of fissile isotope
-    pub delayed_group_mode: FissioningNuclideType,
-
-    /// precursor_and_neutron_pop_and_source_array 
-    pub precursor_and_neutron_pop_and_source_array: [VolumetricNumberDensity;7],
+///
+/// this is the MultiGroupPRKE specialised to six delayed
+/// neutron precursor groups
+pub type SixGroupPRKE = MultiGroupPRKE<6>;
+
+impl SixGroupPRKE {
+
+    /// constructs a six group PRKE with Keepin's data 
+    /// for the chosen fissioning nuclide
+    ///
+    /// starting neutron population of 1 per m3
+    pub fn new_from_nuclide(delayed_group_mode: FissioningNuclideType) -> Self {
+
+        let decay_constant_array = delayed_group_mode.get_decay_constant_array();
+        let delayed_fraction_array = delayed_group_mode.get_delayed_fraction_array();
+
+        Self::new(decay_constant_array, delayed_fraction_array, delayed_group_mode)
+    }
+
+    /// constructs a six group PRKE at steady state for a given
+    /// neutron population, precursors are at equilibrium
+    ///
+    /// C_i = beta_i n / (lambda_i Lambda)
+    pub fn new_at_equilibrium(delayed_group_mode: FissioningNuclideType,
+        neutron_population_number_density: VolumetricNumberDensity,
+        neutron_generation_time: Time) -> Self {
+
+        let mut prke_six_group = Self::new_from_nuclide(delayed_group_mode);
+
+        prke_six_group.set_equilibrium_from_neutron_population_density(
+            neutron_population_number_density,
+            neutron_generation_time);
+
+        prke_six_group
+    }
+
+    /// constructs a six group PRKE at critical steady state for a
+    /// given reactor power, using a reactor power model
+    pub fn new_at_equilibrium_from_reactor_power(delayed_group_mode: FissioningNuclideType,
+        reactor_power: Power,
+        neutron_generation_time: Time,
+        reactor_power_model: &ReactorPowerModel) -> Self {
+
+        let mut prke_six_group = Self::new_from_nuclide(delayed_group_mode);
+
+        prke_six_group.set_equilibrium_from_reactor_power(
+            reactor_power,
+            neutron_generation_time,
+            reactor_power_model);
+
+        prke_six_group
+    }
+
+    /// constructs a six group PRKE at the steady state of a
+    /// subcritical core driven by a source
+    ///
+    /// n = - S Lambda / rho
+    pub fn new_at_source_equilibrium(delayed_group_mode: FissioningNuclideType,
+        reactivity: impl Into<Ratio>,
+        neutron_generation_time: Time,
+        background_source_rate: VolumetricNumberRate)
+        -> Result<Self, TehOPrkeError> {
+
+        let mut prke_six_group = Self::new_from_nuclide(delayed_group_mode);
+
+        prke_six_group.set_equilibrium_from_source(
+            reactivity,
+            neutron_generation_time,
+            background_source_rate)?;
+
+        Ok(prke_six_group)
+    }
 }
 
 
 
 
-/// default is to use u235 decay constants and delayed fraction, with 
+/// default is to use u235 decay constants and delayed fraction, with
 /// starting neutron population of 1 per m3
 impl Default for SixGroupPRKE {
     fn default() -> Self {
         let delayed_group_mode = FissioningNuclideType::U235;
 
-        // the arrangement is 
-        // [precursor grp 1, 
-        // precursor grp 2, 
-        // precursor grp 3, 
-        // precursor grp 4, 
-        // precursor grp 5, 
-        // precursor grp 6,
-        // neutron population]
+        // the arrangement is
+        // [precursor grp 1,
+        // precursor grp 2,
+        // precursor grp 3,
+        // precursor grp 4,
+        // precursor grp 5,
+        // precursor grp 6]
 
 
-        let precursor_and_neutron_pop_and_source_array: [VolumetricNumberDensity;7] = 
+        let precursor_concentration_array: [VolumetricNumberDensity;6] =
             [
-            VolumetricNumberDensity::new::<per_cubic_meter>(1.0) ,
             VolumetricNumberDensity::ZERO,
             VolumetricNumberDensity::ZERO,
             VolumetricNumberDensity::ZERO,
@@ -56,6 +119,8 @@ impl Default for SixGroupPRKE {
             VolumetricNumberDensity::ZERO,
             VolumetricNumberDensity::ZERO,
             ];
+        let neutron_population_number_density =
+            VolumetricNumberDensity::new::<per_cubic_meter>(1.0);
         let decay_constant_array = delayed_group_mode.get_decay_constant_array();
         let delayed_fraction_array = delayed_group_mode.get_delayed_fraction_array();
 
@@ -63,58 +128,10 @@ impl Default for SixGroupPRKE {
             decay_constant_array,
             delayed_fraction_array,
             delayed_group_mode,
-            precursor_and_neutron_pop_and_source_array,
+            neutron_population_number_density,
+            precursor_concentration_array,
         }
 
     }
 }
 
-/// contains time stepping implicit solvers for SixGroupPRKE
-pub mod implicit_solver;
-
-/// contains time stepping explicit solvers for SixGroupPRKE 
-pub mod explicit_solver;
-
-impl SixGroupPRKE {
-
-    /// obtains current neutron population 
-    pub fn get_current_neutron_population_density(&self) -> VolumetricNumberDensity {
-        self.precursor_and_neutron_pop_and_source_array[0]
-    }
-
-    /// total delayed fraction 
-    pub fn get_total_delayed_fraction(&self) -> Ratio {
-
-        let total_delayed_fraction: Ratio = 
-            self.delayed_fraction_array.clone().into_iter().sum();
-
-        total_delayed_fraction
-    }
-
-
-    /// enables you to convert reactivity into keff, useful for calculating 
-    /// the neutron generation time
-    pub fn get_keff_from_reactivity(reactivity: Ratio) -> Ratio {
-
-        // reactivity is rho 
-        //
-        // rho = (k-1)/k
-        //
-        // k * rho = k - 1
-        // k * rho - k = - 1
-        // k - k * rho = 1
-        // k * (1 - rho) = 1
-        // k = 1/(1 - rho) 
-        //
-
-        let ratio_one = Ratio::new::<ratio>(1.0);
-
-        let keff = ratio_one/(ratio_one - reactivity);
-
-        keff
-
-    }
-    
-}
-
-
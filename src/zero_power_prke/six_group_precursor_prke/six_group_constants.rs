@@ -1,6 +1,5 @@
 use std::f64::consts::LN_2;
 
-use uom::ConstZero;
 use uom::si::f64::*;
 use uom::si::time::second;
 use uom::si::ratio::ratio;
@@ -8,65 +7,66 @@ use uom::si::ratio::ratio;
 use crate::zero_power_prke::six_group_precursor_prke::DecayConstant;
 
 /// different nuclides or fuels have different delayed groups
-#[derive(Debug,Clone,Copy)]
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
 pub enum FissioningNuclideType {
     /// chooses the U233 group of delayed constants
     U233,
     /// chooses the U235 group of delayed constants
     U235,
     /// chooses the Pu239 group of delayed constants
-    Pu239
+    Pu239,
+    /// chooses the U238 group of delayed constants
+    /// (fast fission)
+    U238,
+    /// chooses the Pu241 group of delayed constants
+    Pu241,
+    /// chooses the Th232 group of delayed constants
+    /// (fast fission)
+    Th232,
 }
 
 impl FissioningNuclideType {
     /// returns a new decay constant array based on nuclide
-    /// (for this version, all decay constant arrays are the same)
+    ///
+    /// these are Keepin's six group decay constants for thermal fission,
+    /// except for u238 and th232 which only undergo fast fission
     pub fn get_decay_constant_array(&self) -> [DecayConstant;6] {
         match self {
-            FissioningNuclideType::U233 => {
-                return new_decay_constant_array();
-            },
-            FissioningNuclideType::U235 => {
-                return new_decay_constant_array();
-            },
-            FissioningNuclideType::Pu239 => {
-                return new_decay_constant_array();
-            },
+            FissioningNuclideType::U233 => new_u233_decay_constant_array(),
+            FissioningNuclideType::U235 => new_decay_constant_array(),
+            FissioningNuclideType::Pu239 => new_pu239_decay_constant_array(),
+            FissioningNuclideType::U238 => new_u238_decay_constant_array(),
+            FissioningNuclideType::Pu241 => new_pu241_decay_constant_array(),
+            FissioningNuclideType::Th232 => new_th232_decay_constant_array(),
         }
     }
 
     /// returns a delayed fraction array based on nuclide 
     pub fn get_delayed_fraction_array(&self) -> [Ratio;6] {
         match self {
-            FissioningNuclideType::U233 => {
-                return new_u233_delayed_neutron_fraction_array();
-            },
-            FissioningNuclideType::U235 => {
-                return new_u235_delayed_neutron_fraction_array();
-            },
-            FissioningNuclideType::Pu239 => {
-                return new_pu239_delayed_neutron_fraction_array();
-            },
+            FissioningNuclideType::U233 => new_u233_delayed_neutron_fraction_array(),
+            FissioningNuclideType::U235 => new_u235_delayed_neutron_fraction_array(),
+            FissioningNuclideType::Pu239 => new_pu239_delayed_neutron_fraction_array(),
+            FissioningNuclideType::U238 => new_u238_delayed_neutron_fraction_array(),
+            FissioningNuclideType::Pu241 => new_pu241_delayed_neutron_fraction_array(),
+            FissioningNuclideType::Th232 => new_th232_delayed_neutron_fraction_array(),
         }
     }
 }
 
 
 /// produces a new decay constant array
+///
+/// these are the decay constants for u235 thermal fission
 pub fn new_decay_constant_array() -> [DecayConstant;6] {
 
-    // from table 5.1 (not sure where from, duderstadt?)
-    // need to double check
-    let mut decay_constant_array: [DecayConstant;6] = 
-        [
-        Frequency::ZERO,
-        Frequency::ZERO,
-        Frequency::ZERO,
-        Frequency::ZERO,
-        Frequency::ZERO,
-        Frequency::ZERO,
-        ];
-
+    // Keepin, G. R., Wimett, T. F., & Zeigler, R. K. (1957). 
+    // Delayed neutrons from fissionable isotopes of uranium, 
+    // plutonium, and thorium. Physical Review, 107(4), 1044, 
+    // group half lives for thermal fission of u235
+    //
+    // Keepin gives 55.72, 22.72, 6.22, 2.30, 0.610 and 0.230 s,
+    // these are rounded to two significant figures
     let half_life_array: [Time;6] = 
         [
         Time::new::<second>(56.0),
@@ -77,20 +77,114 @@ pub fn new_decay_constant_array() -> [DecayConstant;6] {
         Time::new::<second>(0.23),
         ];
 
-    // crude, but gets the job done
+    // lambda (decay constant) = ln(2)/(half life)
+    half_life_array.map(|half_life| LN_2/half_life)
+}
+
+/// produces a new decay constant array for u233 thermal fission
+pub fn new_u233_decay_constant_array() -> [DecayConstant;6] {
+
+    // Keepin, G. R., Wimett, T. F., & Zeigler, R. K. (1957). 
+    // Delayed neutrons from fissionable isotopes of uranium, 
+    // plutonium, and thorium. Physical Review, 107(4), 1044, 
+    // group half lives for thermal fission of u233
+    let half_life_array: [Time;6] = 
+        [
+        Time::new::<second>(55.00),
+        Time::new::<second>(20.57),
+        Time::new::<second>(5.00),
+        Time::new::<second>(2.13),
+        Time::new::<second>(0.615),
+        Time::new::<second>(0.277),
+        ];
+
+    // lambda (decay constant) = ln(2)/(half life)
+    half_life_array.map(|half_life| LN_2/half_life)
+}
+
+/// produces a new decay constant array for pu239 thermal fission
+pub fn new_pu239_decay_constant_array() -> [DecayConstant;6] {
+
+    // Keepin, G. R., Wimett, T. F., & Zeigler, R. K. (1957). 
+    // Delayed neutrons from fissionable isotopes of uranium, 
+    // plutonium, and thorium. Physical Review, 107(4), 1044, 
+    // group half lives for thermal fission of pu239
+    let half_life_array: [Time;6] = 
+        [
+        Time::new::<second>(54.28),
+        Time::new::<second>(23.04),
+        Time::new::<second>(5.60),
+        Time::new::<second>(2.13),
+        Time::new::<second>(0.618),
+        Time::new::<second>(0.257),
+        ];
+
+    // lambda (decay constant) = ln(2)/(half life)
+    half_life_array.map(|half_life| LN_2/half_life)
+}
+
+/// produces a new decay constant array for u238 fast fission
+pub fn new_u238_decay_constant_array() -> [DecayConstant;6] {
+
+    // Keepin, G. R., Wimett, T. F., & Zeigler, R. K. (1957). 
+    // Delayed neutrons from fissionable isotopes of uranium, 
+    // plutonium, and thorium. Physical Review, 107(4), 1044, 
+    // group half lives for fast fission of u238
+    let half_life_array: [Time;6] = 
+        [
+        Time::new::<second>(52.38),
+        Time::new::<second>(21.58),
+        Time::new::<second>(5.00),
+        Time::new::<second>(1.93),
+        Time::new::<second>(0.490),
+        Time::new::<second>(0.172),
+        ];
+
+    // lambda (decay constant) = ln(2)/(half life)
+    half_life_array.map(|half_life| LN_2/half_life)
+}
+
+/// produces a new decay constant array for pu241 thermal fission
+pub fn new_pu241_decay_constant_array() -> [DecayConstant;6] {
+
+    // pu241 is not in Keepin et al. (1957), these are the group 
+    // half lives for thermal fission of pu241 tabulated in 
     //
+    // Keepin, G. R. (1965). Physics of nuclear kinetics. 
+    // Addison-Wesley.
+    let half_life_array: [Time;6] = 
+        [
+        Time::new::<second>(54.0),
+        Time::new::<second>(23.2),
+        Time::new::<second>(5.60),
+        Time::new::<second>(1.97),
+        Time::new::<second>(0.43),
+        Time::new::<second>(0.20),
+        ];
+
     // lambda (decay constant) = ln(2)/(half life)
-    decay_constant_array[0] = LN_2/half_life_array[0];
-    decay_constant_array[1] = LN_2/half_life_array[1];
-    decay_constant_array[2] = LN_2/half_life_array[2];
-    decay_constant_array[3] = LN_2/half_life_array[3];
-    decay_constant_array[4] = LN_2/half_life_array[4];
-    decay_constant_array[5] = LN_2/half_life_array[5];
+    half_life_array.map(|half_life| LN_2/half_life)
+}
 
-    // done!
-    decay_constant_array
-        
+/// produces a new decay constant array for th232 fast fission
+pub fn new_th232_decay_constant_array() -> [DecayConstant;6] {
+
+    // Keepin, G. R., Wimett, T. F., & Zeigler, R. K. (1957). 
+    // Delayed neutrons from fissionable isotopes of uranium, 
+    // plutonium, and thorium. Physical Review, 107(4), 1044, 
+    // group half lives for fast fission of th232
+    let half_life_array: [Time;6] = 
+        [
+        Time::new::<second>(56.03),
+        Time::new::<second>(20.75),
+        Time::new::<second>(5.74),
+        Time::new::<second>(2.16),
+        Time::new::<second>(0.571),
+        Time::new::<second>(0.211),
+        ];
 
+    // lambda (decay constant) = ln(2)/(half life)
+    half_life_array.map(|half_life| LN_2/half_life)
 }
 
 /// produces a new delayed fraction for u233 
@@ -142,3 +236,59 @@ pub fn new_pu239_delayed_neutron_fraction_array() -> [Ratio;6] {
     delayed_neutron_array
 }
 
+/// produces a new delayed fraction for u238 (fast fission)
+///
+/// total delayed fraction is 0.0148, relative abundances from 
+/// Keepin et al. (1957) are 0.013, 0.137, 0.162, 0.388, 0.225, 0.075
+pub fn new_u238_delayed_neutron_fraction_array() -> [Ratio;6] {
+
+    let delayed_neutron_array: [Ratio;6] = 
+        [
+        Ratio::new::<ratio>(0.0001924),
+        Ratio::new::<ratio>(0.0020276),
+        Ratio::new::<ratio>(0.0023976),
+        Ratio::new::<ratio>(0.0057424),
+        Ratio::new::<ratio>(0.00333),
+        Ratio::new::<ratio>(0.00111),
+        ];
+
+    delayed_neutron_array
+}
+
+/// produces a new delayed fraction for pu241 (thermal fission)
+///
+/// total delayed fraction is 0.0054, relative abundances from 
+/// Keepin (1965) are 0.010, 0.229, 0.173, 0.390, 0.182, 0.016
+pub fn new_pu241_delayed_neutron_fraction_array() -> [Ratio;6] {
+
+    let delayed_neutron_array: [Ratio;6] = 
+        [
+        Ratio::new::<ratio>(0.000054),
+        Ratio::new::<ratio>(0.0012366),
+        Ratio::new::<ratio>(0.0009342),
+        Ratio::new::<ratio>(0.002106),
+        Ratio::new::<ratio>(0.0009828),
+        Ratio::new::<ratio>(0.0000864),
+        ];
+
+    delayed_neutron_array
+}
+
+/// produces a new delayed fraction for th232 (fast fission)
+///
+/// total delayed fraction is 0.0203, relative abundances from 
+/// Keepin et al. (1957) are 0.034, 0.150, 0.155, 0.446, 0.172, 0.043
+pub fn new_th232_delayed_neutron_fraction_array() -> [Ratio;6] {
+
+    let delayed_neutron_array: [Ratio;6] = 
+        [
+        Ratio::new::<ratio>(0.0006902),
+        Ratio::new::<ratio>(0.003045),
+        Ratio::new::<ratio>(0.0031465),
+        Ratio::new::<ratio>(0.0090538),
+        Ratio::new::<ratio>(0.0034916),
+        Ratio::new::<ratio>(0.0008729),
+        ];
+
+    delayed_neutron_array
+}
@@ -0,0 +1,619 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rand_distr::{Binomial, Distribution, Poisson};
+use uom::si::f64::*;
+use uom::si::frequency::hertz;
+use uom::si::ratio::ratio;
+use uom::si::time::second;
+use uom::si::volume::cubic_meter;
+use uom::si::volumetric_number_density::per_cubic_meter;
+use uom::si::volumetric_number_rate::per_cubic_meter_second;
+
+use crate::teh_o_prke_error::TehOPrkeError;
+use crate::zero_power_prke::multi_group_precursor_prke::{DecayConstant, MultiGroupPRKE};
+
+/// how the stochastic PRKE samples events
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub enum StochasticMethod {
+    /// exact stochastic simulation (Gillespie), one event at a
+    /// time, only practical for small populations such as the
+    /// source range
+    ///
+    /// an interval needing more than max_events_per_interval
+    /// events is an error
+    Gillespie,
+    /// tau leaping, the number of prompt births, removals and
+    /// precursor births in a leap is Poisson distributed and
+    /// precursor decays are binomial, for larger populations
+    ///
+    /// the given time is the longest leap allowed, leaps are
+    /// shortened to keep the populations from changing much
+    /// within one, and small populations go back to Gillespie
+    TauLeaping(Time),
+}
+
+/// stochastic point kinetics with G delayed neutron precursor groups
+///
+/// neutrons and precursors are whole numbers of particles in the
+/// core volume, and change through a birth death process with the
+/// rates
+///
+/// neutron removal: N/Lambda
+///
+/// prompt neutron birth: (1 - beta + rho) N/Lambda
+///
+/// precursor birth: beta_i N/Lambda
+///
+/// precursor decay (giving a delayed neutron): lambda_i C_i
+///
+/// source neutron: S V
+///
+/// so that the mean follows the deterministic PRKE. Each neutron
+/// removal is counted by the detector with probability
+/// detector_efficiency
+///
+/// births are one neutron at a time, so fission multiplicity is
+/// not modelled and the variance is somewhat lower than in a
+/// real core
+///
+/// the random number generator is seeded, so runs are repeatable
+#[derive(Debug,Clone)]
+pub struct StochasticPRKE<const G: usize> {
+    /// decay constants of the delayed precursors
+    pub decay_constant_array: [DecayConstant;G],
+    /// delayed fractions of the delayed precursors
+    pub delayed_fraction_array: [Ratio;G],
+    /// number of neutrons in the core
+    pub neutron_population: u64,
+    /// number of delayed neutron precursors in the core
+    pub precursor_population_array: [u64;G],
+    /// core volume, converts populations to number densities
+    pub reactor_volume: Volume,
+    /// probability that a neutron removal is counted
+    pub detector_efficiency: Ratio,
+    /// most Gillespie events allowed in one call to advance,
+    /// so that a population too large for Gillespie gives an
+    /// error rather than running for hours
+    pub max_events_per_interval: u64,
+    rng: StdRng,
+}
+
+impl<const G: usize> StochasticPRKE<G> {
+
+    /// constructs a stochastic PRKE with no neutrons or precursors
+    pub fn new(decay_constant_array: [DecayConstant;G],
+        delayed_fraction_array: [Ratio;G],
+        reactor_volume: Volume,
+        detector_efficiency: Ratio,
+        seed: u64) -> Result<Self, TehOPrkeError> {
+
+        if reactor_volume.get::<cubic_meter>() <= 0.0 {
+            return Err(TehOPrkeError::GenericStringError(
+                "reactor volume must be positive".to_string()
+            ));
+        }
+
+        if !(0.0..=1.0).contains(&detector_efficiency.get::<ratio>()) {
+            return Err(TehOPrkeError::GenericStringError(
+                "detector efficiency must be between 0 and 1".to_string()
+            ));
+        }
+
+        Ok(Self {
+            decay_constant_array,
+            delayed_fraction_array,
+            neutron_population: 0,
+            precursor_population_array: [0;G],
+            reactor_volume,
+            detector_efficiency,
+            max_events_per_interval: DEFAULT_MAX_EVENTS_PER_INTERVAL,
+            rng: StdRng::seed_from_u64(seed),
+        })
+    }
+
+    /// constructs a stochastic PRKE with the same delayed group
+    /// data and (rounded) state as a deterministic PRKE,
+    /// eg. a SixGroupPRKE at source equilibrium
+    pub fn new_from_prke(prke: &MultiGroupPRKE<G>,
+        reactor_volume: Volume,
+        detector_efficiency: Ratio,
+        seed: u64) -> Result<Self, TehOPrkeError> {
+
+        let mut stochastic_prke = Self::new(
+            prke.decay_constant_array,
+            prke.delayed_fraction_array,
+            reactor_volume,
+            detector_efficiency,
+            seed)?;
+
+        stochastic_prke.neutron_population = stochastic_prke.get_population(
+            prke.neutron_population_number_density)?;
+
+        for (i, precursor_concentration) in
+            prke.precursor_concentration_array.iter().enumerate() {
+                stochastic_prke.precursor_population_array[i] =
+                    stochastic_prke.get_population(*precursor_concentration)?;
+        }
+
+        Ok(stochastic_prke)
+    }
+
+    /// returns the (rounded) number of particles in the core for a
+    /// number density, which must be finite and not negative
+    ///
+    /// a plain cast would turn NaN and negative densities into
+    /// zero and very large ones into u64::MAX
+    fn get_population(&self,
+        number_density: VolumetricNumberDensity) -> Result<u64, TehOPrkeError> {
+
+        let population = (number_density.get::<per_cubic_meter>()
+            * self.reactor_volume.get::<cubic_meter>()).round();
+
+        if !population.is_finite() || population < 0.0 || population >= u64::MAX as f64 {
+            return Err(TehOPrkeError::GenericStringError(
+                format!("cannot convert a population of {} to a whole number \
+                    of particles", population)
+            ));
+        }
+
+        Ok(population as u64)
+    }
+
+    /// returns the neutron population as a number density
+    pub fn get_current_neutron_population_density(&self) -> VolumetricNumberDensity {
+        VolumetricNumberDensity::new::<per_cubic_meter>(
+            self.neutron_population as f64/self.reactor_volume.get::<cubic_meter>())
+    }
+
+    /// returns the precursor populations as number densities
+    pub fn get_current_precursor_concentration_array(&self)
+        -> [VolumetricNumberDensity;G] {
+
+        let volume = self.reactor_volume.get::<cubic_meter>();
+
+        self.precursor_population_array.map(|precursor_population| {
+            VolumetricNumberDensity::new::<per_cubic_meter>(
+                precursor_population as f64/volume)
+        })
+    }
+
+    /// advances the populations by time_interval at constant
+    /// reactivity, and returns the detector counts in the interval
+    ///
+    /// if this returns an error, the populations are left as
+    /// they were at the start of the interval
+    pub fn advance(&mut self,
+        time_interval: Time,
+        reactivity: impl Into<Ratio>,
+        neutron_generation_time: Time,
+        background_source_rate: VolumetricNumberRate,
+        stochastic_method: StochasticMethod) -> Result<u64, TehOPrkeError> {
+        let reactivity: Ratio = reactivity.into();
+
+        let event_rates = self.get_event_rate_constants(
+            reactivity,
+            neutron_generation_time,
+            background_source_rate)?;
+
+        let time_interval = time_interval.get::<second>();
+
+        if time_interval < 0.0 {
+            return Err(TehOPrkeError::GenericStringError(
+                "time interval must not be negative".to_string()
+            ));
+        }
+
+        let neutron_population = self.neutron_population;
+        let precursor_population_array = self.precursor_population_array;
+        let mut events_left = self.max_events_per_interval;
+
+        let detector_counts = match stochastic_method {
+            StochasticMethod::Gillespie => {
+                self.advance_gillespie(time_interval, &event_rates, &mut events_left)
+            },
+            StochasticMethod::TauLeaping(leap_time) => {
+                let leap_time = leap_time.get::<second>();
+
+                if leap_time <= 0.0 {
+                    return Err(TehOPrkeError::GenericStringError(
+                        "tau leaping time must be positive".to_string()
+                    ));
+                }
+
+                self.advance_tau_leaping(time_interval, leap_time, &event_rates,
+                    &mut events_left)
+            },
+        };
+
+        if detector_counts.is_err() {
+            self.neutron_population = neutron_population;
+            self.precursor_population_array = precursor_population_array;
+        }
+
+        detector_counts
+    }
+
+    /// advances the populations over a number of counting intervals
+    /// at constant reactivity, and returns the counts in each,
+    /// as a source range detector would show them
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_counts_per_interval(&mut self,
+        counting_interval: Time,
+        number_of_intervals: usize,
+        reactivity: impl Into<Ratio>,
+        neutron_generation_time: Time,
+        background_source_rate: VolumetricNumberRate,
+        stochastic_method: StochasticMethod) -> Result<Vec<u64>, TehOPrkeError> {
+        let reactivity: Ratio = reactivity.into();
+
+        (0..number_of_intervals).map(|_| {
+            self.advance(counting_interval,
+                reactivity,
+                neutron_generation_time,
+                background_source_rate,
+                stochastic_method)
+        }).collect()
+    }
+
+    /// returns the per particle rate constants (in 1/s) for prompt
+    /// birth, removal, precursor birth and decay, and the source
+    /// rate in neutrons per second
+    fn get_event_rate_constants(&self,
+        reactivity: Ratio,
+        neutron_generation_time: Time,
+        background_source_rate: VolumetricNumberRate)
+        -> Result<EventRateConstants<G>, TehOPrkeError> {
+
+        let generation_time = neutron_generation_time.get::<second>();
+
+        if generation_time <= 0.0 {
+            return Err(TehOPrkeError::GenericStringError(
+                "neutron generation time must be positive".to_string()
+            ));
+        }
+
+        let total_delayed_fraction: f64 = self.delayed_fraction_array.iter()
+            .map(|beta_i| beta_i.get::<ratio>())
+            .sum();
+
+        let prompt_birth_rate_constant =
+            (1.0 - total_delayed_fraction + reactivity.get::<ratio>())/generation_time;
+
+        if prompt_birth_rate_constant < 0.0 {
+            return Err(TehOPrkeError::GenericStringError(
+                "reactivity is too negative for the birth death model".to_string()
+            ));
+        }
+
+        let source_rate = background_source_rate.get::<per_cubic_meter_second>()
+            * self.reactor_volume.get::<cubic_meter>();
+
+        if source_rate < 0.0 {
+            return Err(TehOPrkeError::GenericStringError(
+                "background source rate must not be negative".to_string()
+            ));
+        }
+
+        Ok(EventRateConstants {
+            prompt_birth: prompt_birth_rate_constant,
+            removal: 1.0/generation_time,
+            precursor_birth: self.delayed_fraction_array
+                .map(|beta_i| beta_i.get::<ratio>()/generation_time),
+            precursor_decay: self.decay_constant_array
+                .map(|lambda_i| lambda_i.get::<hertz>()),
+            source: source_rate,
+        })
+    }
+
+    /// exact stochastic simulation over time_interval, each event
+    /// takes one off events_left, and running out is an error
+    fn advance_gillespie(&mut self,
+        time_interval: f64,
+        event_rates: &EventRateConstants<G>,
+        events_left: &mut u64) -> Result<u64, TehOPrkeError> {
+
+        let detector_efficiency = self.detector_efficiency.get::<ratio>();
+        let mut detector_counts: u64 = 0;
+        let mut time_left = time_interval;
+
+        loop {
+            let neutron_population = self.neutron_population as f64;
+
+            let prompt_birth_rate = event_rates.prompt_birth * neutron_population;
+            let removal_rate = event_rates.removal * neutron_population;
+            let precursor_birth_rate_array = event_rates.precursor_birth
+                .map(|rate_constant| rate_constant * neutron_population);
+            let mut precursor_decay_rate_array = [0.0;G];
+            for (i, precursor_decay_rate) in
+                precursor_decay_rate_array.iter_mut().enumerate() {
+                    *precursor_decay_rate = event_rates.precursor_decay[i]
+                        * self.precursor_population_array[i] as f64;
+            }
+
+            let total_rate = event_rates.source + prompt_birth_rate + removal_rate
+                + precursor_birth_rate_array.iter().sum::<f64>()
+                + precursor_decay_rate_array.iter().sum::<f64>();
+
+            if total_rate <= 0.0 {
+                break;
+            }
+
+            // waiting time to the next event is exponential,
+            // 1 - u avoids ln(0)
+            let waiting_time = -(1.0 - self.rng.gen::<f64>()).ln()/total_rate;
+
+            if waiting_time > time_left {
+                break;
+            }
+            time_left -= waiting_time;
+
+            if *events_left == 0 {
+                return Err(TehOPrkeError::GenericStringError(
+                    format!("Gillespie needed more than {} events in the interval, \
+                        use tau leaping for populations this large",
+                        self.max_events_per_interval)
+                ));
+            }
+            *events_left -= 1;
+
+            // pick the event in proportion to its rate
+            let mut event_selector = self.rng.gen::<f64>() * total_rate;
+
+            if event_selector < event_rates.source {
+                self.neutron_population += 1;
+                continue;
+            }
+            event_selector -= event_rates.source;
+
+            if event_selector < prompt_birth_rate {
+                self.neutron_population += 1;
+                continue;
+            }
+            event_selector -= prompt_birth_rate;
+
+            if event_selector < removal_rate {
+                self.neutron_population -= 1;
+                if self.rng.gen_bool(detector_efficiency) {
+                    detector_counts += 1;
+                }
+                continue;
+            }
+            event_selector -= removal_rate;
+
+            let mut event_found = false;
+
+            for i in 0..G {
+                if event_selector < precursor_birth_rate_array[i] {
+                    self.precursor_population_array[i] += 1;
+                    event_found = true;
+                    break;
+                }
+                event_selector -= precursor_birth_rate_array[i];
+
+                if event_selector < precursor_decay_rate_array[i] {
+                    self.precursor_population_array[i] -= 1;
+                    self.neutron_population += 1;
+                    event_found = true;
+                    break;
+                }
+                event_selector -= precursor_decay_rate_array[i];
+            }
+
+            // round off can leave the selector just past the last
+            // event, in which case the event is simply resampled
+            if !event_found {
+                time_left += waiting_time;
+            }
+        }
+
+        Ok(detector_counts)
+    }
+
+    /// leaps forward at most leap_time at a time
+    ///
+    /// the length of each leap is picked afresh from the current
+    /// populations (Cao, Gillespie and Petzold 2006, efficient step
+    /// size selection for the tau-leaping simulation method), so
+    /// that the mean and spread of the change in each population
+    /// stay within a fraction LEAP_ERROR_CONTROL of it. Leaps
+    /// shrink as populations fall and grow back as they rise, and
+    /// where a leap would be shorter than a few events, the next
+    /// GILLESPIE_EVENTS_PER_LEAP or so events are simulated one at
+    /// a time instead
+    ///
+    /// each precursor decays within a leap with probability
+    /// 1 - exp(-lambda_i tau), so the decays are binomial and can
+    /// never take a group below zero. A leap that removes more
+    /// neutrons than there are is thrown away and simulated one
+    /// event at a time over the same length. Redrawing until the
+    /// leap fits would condition the sample on staying positive,
+    /// and clamping at zero biases the mean upwards
+    fn advance_tau_leaping(&mut self,
+        time_interval: f64,
+        leap_time: f64,
+        event_rates: &EventRateConstants<G>,
+        events_left: &mut u64) -> Result<u64, TehOPrkeError> {
+
+        let detector_efficiency = self.detector_efficiency.get::<ratio>();
+        let mut detector_counts: u64 = 0;
+        let mut time_left = time_interval;
+
+        while time_left > 0.0 {
+            let total_rate = self.get_total_event_rate(event_rates);
+
+            if total_rate <= 0.0 {
+                break;
+            }
+
+            let tau = self.get_leap_time_bound(event_rates)
+                .min(leap_time)
+                .min(time_left);
+
+            if tau * total_rate < GILLESPIE_EVENTS_PER_LEAP {
+                let gillespie_time = (GILLESPIE_EVENTS_PER_LEAP/total_rate)
+                    .min(time_left);
+                detector_counts += self.advance_gillespie(
+                    gillespie_time, event_rates, events_left)?;
+                time_left -= gillespie_time;
+                continue;
+            }
+
+            let neutron_population = self.neutron_population as f64;
+
+            let source_neutrons = self.sample_poisson(event_rates.source * tau)?;
+            let prompt_births = self.sample_poisson(
+                event_rates.prompt_birth * neutron_population * tau)?;
+            let removals = self.sample_poisson(
+                event_rates.removal * neutron_population * tau)?;
+
+            let mut precursor_births_array = [0;G];
+            let mut precursor_decays_array = [0;G];
+
+            for i in 0..G {
+                precursor_births_array[i] = self.sample_poisson(
+                    event_rates.precursor_birth[i] * neutron_population * tau)?;
+                precursor_decays_array[i] = self.sample_binomial(
+                    self.precursor_population_array[i],
+                    -(-event_rates.precursor_decay[i] * tau).exp_m1())?;
+            }
+
+            let delayed_neutrons: u64 = precursor_decays_array.iter().sum();
+
+            // prompt births and removals can both be many times N
+            // in a leap, only the net change has to keep N positive
+            let neutrons_gained = self.neutron_population + source_neutrons
+                + prompt_births + delayed_neutrons;
+
+            if removals > neutrons_gained {
+                detector_counts += self.advance_gillespie(
+                    tau, event_rates, events_left)?;
+                time_left -= tau;
+                continue;
+            }
+
+            for i in 0..G {
+                self.precursor_population_array[i] = self.precursor_population_array[i]
+                    + precursor_births_array[i] - precursor_decays_array[i];
+            }
+
+            self.neutron_population = neutrons_gained - removals;
+
+            detector_counts += self.sample_binomial(removals, detector_efficiency)?;
+
+            time_left -= tau;
+        }
+
+        Ok(detector_counts)
+    }
+
+    /// sum of all event rates in 1/s at the current populations
+    fn get_total_event_rate(&self, event_rates: &EventRateConstants<G>) -> f64 {
+
+        let neutron_population = self.neutron_population as f64;
+
+        let precursor_rate: f64 = (0..G).map(|i| {
+            event_rates.precursor_birth[i] * neutron_population
+                + event_rates.precursor_decay[i]
+                * self.precursor_population_array[i] as f64
+        }).sum();
+
+        event_rates.source
+            + (event_rates.prompt_birth + event_rates.removal) * neutron_population
+            + precursor_rate
+    }
+
+    /// longest leap for which the expected change in each population
+    /// x, and its standard deviation, stay below
+    /// max(LEAP_ERROR_CONTROL x, 1)
+    fn get_leap_time_bound(&self, event_rates: &EventRateConstants<G>) -> f64 {
+
+        let get_bound = |population: f64, mean_rate: f64, variance_rate: f64| -> f64 {
+            let allowed_change = (LEAP_ERROR_CONTROL * population).max(1.0);
+
+            let mean_bound = if mean_rate != 0.0 {
+                allowed_change/mean_rate.abs()
+            } else {
+                f64::INFINITY
+            };
+
+            let variance_bound = if variance_rate > 0.0 {
+                allowed_change * allowed_change/variance_rate
+            } else {
+                f64::INFINITY
+            };
+
+            mean_bound.min(variance_bound)
+        };
+
+        let neutron_population = self.neutron_population as f64;
+        let mut neutron_mean_rate = event_rates.source
+            + (event_rates.prompt_birth - event_rates.removal) * neutron_population;
+        let mut neutron_variance_rate = event_rates.source
+            + (event_rates.prompt_birth + event_rates.removal) * neutron_population;
+        let mut leap_time_bound = f64::INFINITY;
+
+        for i in 0..G {
+            let precursor_population = self.precursor_population_array[i] as f64;
+            let precursor_birth_rate = event_rates.precursor_birth[i] * neutron_population;
+            let precursor_decay_rate = event_rates.precursor_decay[i] * precursor_population;
+
+            neutron_mean_rate += precursor_decay_rate;
+            neutron_variance_rate += precursor_decay_rate;
+
+            leap_time_bound = leap_time_bound.min(get_bound(
+                precursor_population,
+                precursor_birth_rate - precursor_decay_rate,
+                precursor_birth_rate + precursor_decay_rate));
+        }
+
+        leap_time_bound.min(get_bound(
+            neutron_population, neutron_mean_rate, neutron_variance_rate))
+    }
+
+    fn sample_poisson(&mut self, mean: f64) -> Result<u64, TehOPrkeError> {
+
+        if mean <= 0.0 {
+            return Ok(0);
+        }
+
+        let poisson = Poisson::new(mean)
+            .map_err(|error| TehOPrkeError::GenericStringError(error.to_string()))?;
+
+        Ok(poisson.sample(&mut self.rng) as u64)
+    }
+
+    fn sample_binomial(&mut self, trials: u64,
+        probability: f64) -> Result<u64, TehOPrkeError> {
+
+        if trials == 0 {
+            return Ok(0);
+        }
+
+        let binomial = Binomial::new(trials, probability)
+            .map_err(|error| TehOPrkeError::GenericStringError(error.to_string()))?;
+
+        Ok(binomial.sample(&mut self.rng))
+    }
+}
+
+/// default for StochasticPRKE::max_events_per_interval, a few
+/// seconds of Gillespie
+pub const DEFAULT_MAX_EVENTS_PER_INTERVAL: u64 = 100_000_000;
+
+/// largest change in a population within a leap, as a fraction
+/// of the population, used to pick the leap length
+const LEAP_ERROR_CONTROL: f64 = 0.03;
+
+/// a leap expected to hold fewer events than this is done one
+/// event at a time instead, for about this many events
+const GILLESPIE_EVENTS_PER_LEAP: f64 = 100.0;
+
+/// per particle event rate constants, in 1/s, and the source
+/// rate in neutrons per second
+struct EventRateConstants<const G: usize> {
+    prompt_birth: f64,
+    removal: f64,
+    precursor_birth: [f64;G],
+    precursor_decay: [f64;G],
+    source: f64,
+}
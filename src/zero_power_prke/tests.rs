@@ -63,9 +63,10 @@ pub fn prke_test_zero_reactivity(){
     let delayed_group_mode = FissioningNuclideType::U235;
 
 
-    let precursor_and_neutron_pop_and_source_array: [VolumetricNumberDensity;7] = 
+    let neutron_population_number_density = 
+        VolumetricNumberDensity::new::<per_cubic_meter>(1.0);
+    let precursor_concentration_array: [VolumetricNumberDensity;6] = 
         [
-        VolumetricNumberDensity::new::<per_cubic_meter>(1.0),
         VolumetricNumberDensity::ZERO,
         VolumetricNumberDensity::ZERO,
         VolumetricNumberDensity::ZERO,
@@ -80,7 +81,8 @@ pub fn prke_test_zero_reactivity(){
         decay_constant_array,
         delayed_fraction_array,
         delayed_group_mode,
-        precursor_and_neutron_pop_and_source_array,
+        neutron_population_number_density,
+        precursor_concentration_array,
     };
 
     let timestep = Time::new::<millisecond>(50.0);
@@ -153,3 +155,3166 @@ pub fn prke_test_zero_reactivity(){
         1.17917639e-7,
         epsilon = 1e-11);
 }
+
+#[test]
+pub fn one_group_equivalent_keeps_total_delayed_fraction(){
+
+    use approx::assert_abs_diff_eq;
+    use uom::si::frequency::hertz;
+
+    let prke_six_group = SixGroupPRKE::default();
+    let prke_one_group = prke_six_group.get_one_group_equivalent();
+
+    assert_eq!(prke_one_group.get_number_of_precursor_groups(), 1);
+
+    assert_abs_diff_eq!(
+        prke_one_group.get_total_delayed_fraction().get::<ratio>(),
+        0.00650,
+        epsilon = 1e-12);
+
+    // for the u235 six group data, the one group decay constant 
+    // is about 0.0766 per second
+    assert_abs_diff_eq!(
+        prke_one_group.decay_constant_array[0].get::<hertz>(),
+        0.0766,
+        epsilon = 1e-3);
+}
+
+#[test]
+pub fn one_group_prke_implicit_and_explicit_solvers_agree(){
+    // the same one group PRKE goes through both the implicit 
+    // and explicit solvers, and should give about the same 
+    // neutron population after a small reactivity insertion
+
+    use uom::si::time::{millisecond, microsecond, second};
+    use approx::assert_relative_eq;
+
+    let mut prke_implicit = SixGroupPRKE::default().get_one_group_equivalent();
+    let mut prke_explicit = prke_implicit;
+
+    let neutron_generation_time = Time::new::<microsecond>(100.0);
+    let reactivity = Ratio::new::<ratio>(0.001);
+    let background_source_rate = VolumetricNumberRate::ZERO;
+
+    let implicit_timestep = Time::new::<microsecond>(10.0);
+    let explicit_timestep = Time::new::<millisecond>(1.0);
+    let simulation_time = Time::new::<second>(1.0);
+
+    let number_of_implicit_timesteps = 
+        (simulation_time/implicit_timestep).get::<ratio>().round() as usize;
+    let number_of_explicit_timesteps = 
+        (simulation_time/explicit_timestep).get::<ratio>().round() as usize;
+
+    for _ in 0..number_of_implicit_timesteps {
+        prke_implicit.solve_next_timestep_precursor_concentration_and_neutron_pop_vector_implicit(
+            implicit_timestep, 
+            reactivity, 
+            neutron_generation_time, 
+            background_source_rate).unwrap();
+    }
+
+    for _ in 0..number_of_explicit_timesteps {
+        prke_explicit.solve_next_timestep_precursor_concentration_and_neutron_pop_vector_explicit(
+            explicit_timestep, 
+            reactivity, 
+            neutron_generation_time, 
+            background_source_rate).unwrap();
+    }
+
+    assert_relative_eq!(
+        prke_implicit.get_current_neutron_population_density().get::<per_cubic_meter>(),
+        prke_explicit.get_current_neutron_population_density().get::<per_cubic_meter>(),
+        max_relative = 1e-3);
+}
+
+#[test]
+pub fn eight_group_delayed_fractions_from_relative_abundances(){
+
+    use approx::assert_abs_diff_eq;
+    use uom::si::frequency::hertz;
+    use crate::zero_power_prke::eight_group_precursor_prke::*;
+
+    // Spriggs et al. group 1 half life is 55.6 s 
+    // lambda = ln 2/ 55.6 s = 0.01247 /s
+    assert_abs_diff_eq!(
+        new_eight_group_decay_constant_array()[0].get::<hertz>(),
+        0.01247,
+        epsilon = 1e-5);
+
+    // the delayed fractions add up to the total
+    let relative_abundance_array = [0.125;8];
+    let total_delayed_fraction = Ratio::new::<ratio>(0.0065);
+    let delayed_fraction_array = new_eight_group_delayed_fraction_array(
+        relative_abundance_array, total_delayed_fraction).unwrap();
+    let test_delayed_frac: Ratio = delayed_fraction_array.into_iter().sum();
+
+    assert_abs_diff_eq!(
+        test_delayed_frac.get::<ratio>(),
+        0.0065,
+        epsilon = 1e-12);
+
+    // relative abundances must add up to one and not be negative
+    assert!(new_eight_group_delayed_fraction_array(
+        [0.1;8], total_delayed_fraction).is_err());
+    assert!(new_eight_group_delayed_fraction_array(
+        [0.25, 0.25, 0.25, 0.25, 0.25, 0.0, 0.0, -0.25],
+        total_delayed_fraction).is_err());
+    assert!(new_eight_group_delayed_fraction_array(
+        relative_abundance_array, Ratio::new::<ratio>(-0.0065)).is_err());
+
+}
+
+#[test]
+pub fn eight_group_jeff_3_1_relative_abundances(){
+
+    use approx::assert_abs_diff_eq;
+    use crate::zero_power_prke::eight_group_precursor_prke::*;
+
+    // tabulated JEFF-3.1 values for thermal fission
+    let u235_relative_abundance_array = 
+        FissioningNuclideType::U235.get_eight_group_relative_abundance_array().unwrap();
+    assert_eq!(u235_relative_abundance_array, 
+        [0.0328, 0.1539, 0.0913, 0.1969, 0.3308, 0.0902, 0.0812, 0.0229]);
+
+    let u233_relative_abundance_array = 
+        FissioningNuclideType::U233.get_eight_group_relative_abundance_array().unwrap();
+    assert_eq!(u233_relative_abundance_array, 
+        [0.0788, 0.1749, 0.1451, 0.2070, 0.3039, 0.0563, 0.0262, 0.0078]);
+
+    let pu239_relative_abundance_array = 
+        FissioningNuclideType::Pu239.get_eight_group_relative_abundance_array().unwrap();
+    assert_eq!(pu239_relative_abundance_array, 
+        [0.0363, 0.2364, 0.1789, 0.1267, 0.3081, 0.0739, 0.0330, 0.0067]);
+
+    for relative_abundance_array in [u235_relative_abundance_array,
+        u233_relative_abundance_array,
+        pu239_relative_abundance_array] {
+        let sum_of_relative_abundances: f64 = relative_abundance_array.iter().sum();
+        assert_abs_diff_eq!(sum_of_relative_abundances, 1.0, epsilon = 1e-9);
+    }
+
+    // the total beta is the same as the six group data, 
+    // u235 is 0.0065, so group 5 is 0.3308 * 0.0065
+    let u235_delayed_fraction_array = 
+        FissioningNuclideType::U235.get_eight_group_delayed_fraction_array().unwrap();
+    let u235_total_delayed_fraction: Ratio = u235_delayed_fraction_array.into_iter().sum();
+
+    assert_abs_diff_eq!(u235_total_delayed_fraction.get::<ratio>(), 0.0065, epsilon = 1e-12);
+    assert_abs_diff_eq!(u235_delayed_fraction_array[4].get::<ratio>(), 
+        0.3308 * 0.0065, epsilon = 1e-12);
+
+    let pu239_prke = EightGroupPRKE::new_from_nuclide(FissioningNuclideType::Pu239).unwrap();
+    assert_abs_diff_eq!(pu239_prke.get_total_delayed_fraction().get::<ratio>(), 
+        0.0021, epsilon = 1e-12);
+
+    // not tabulated for the fast fission nuclides
+    assert!(FissioningNuclideType::U238.get_eight_group_relative_abundance_array().is_err());
+    assert!(EightGroupPRKE::new_from_nuclide(FissioningNuclideType::Th232).is_err());
+}
+
+#[test]
+pub fn six_group_decay_constants_are_nuclide_specific(){
+
+    use approx::assert_abs_diff_eq;
+    use uom::si::frequency::hertz;
+
+    let u233_decay_constants = FissioningNuclideType::U233.get_decay_constant_array();
+    let u235_decay_constants = FissioningNuclideType::U235.get_decay_constant_array();
+    let pu239_decay_constants = FissioningNuclideType::Pu239.get_decay_constant_array();
+
+    assert_ne!(u233_decay_constants, u235_decay_constants);
+    assert_ne!(pu239_decay_constants, u235_decay_constants);
+
+    // Keepin's group 1 half life for u233 is 55.0 s 
+    // lambda = ln 2/ 55.0 s = 0.0126 /s
+    assert_abs_diff_eq!(
+        u233_decay_constants[0].get::<hertz>(),
+        0.0126,
+        epsilon = 1e-4);
+    // Keepin's group 6 half life for pu239 is 0.257 s 
+    // lambda = ln 2/ 0.257 s = 2.697 /s
+    assert_abs_diff_eq!(
+        pu239_decay_constants[5].get::<hertz>(),
+        2.697,
+        epsilon = 1e-3);
+}
+
+#[test]
+pub fn eight_group_prke_test_zero_reactivity(){
+    // eight group PRKE goes through the same implicit solver,
+    // with zero reactivity, neutrons and precursors are conserved
+
+    use uom::si::time::{millisecond, nanosecond};
+    use approx::assert_abs_diff_eq;
+    use crate::zero_power_prke::eight_group_precursor_prke::EightGroupPRKE;
+
+    let mut prke_test = EightGroupPRKE::new_from_nuclide(
+        FissioningNuclideType::U235).unwrap();
+
+    let timestep = Time::new::<millisecond>(50.0);
+    let neutron_generation_time = Time::new::<nanosecond>(10.0);
+    let zero_reactivity = Ratio::ZERO;
+    let background_source_rate = VolumetricNumberRate::ZERO;
+
+    let mut precursor_and_neutron_pop_sum = VolumetricNumberDensity::ZERO;
+
+    for _ in 0..1000 {
+
+        let neutron_pop_and_precursor_vector = 
+            prke_test.solve_next_timestep_precursor_concentration_and_neutron_pop_vector_implicit(
+                timestep, 
+                zero_reactivity, 
+                neutron_generation_time, 
+                background_source_rate).unwrap();
+
+        assert_eq!(neutron_pop_and_precursor_vector.len(), 9);
+
+        precursor_and_neutron_pop_sum = 
+            neutron_pop_and_precursor_vector.into_iter().sum();
+    }
+
+    assert_abs_diff_eq!(
+        precursor_and_neutron_pop_sum.get::<per_cubic_meter>(),
+        1.0,
+        epsilon = 1e-9);
+}
+
+#[test]
+pub fn mixed_nuclide_single_nuclide_reproduces_nuclide_data(){
+
+    use approx::assert_relative_eq;
+    use crate::zero_power_prke::six_group_precursor_prke::MixedNuclideDelayedNeutronData;
+
+    let mixed_nuclide_data = MixedNuclideDelayedNeutronData::new(
+        vec![(FissioningNuclideType::Pu239, Ratio::new::<ratio>(1.0))]
+    ).unwrap();
+
+    let prke_mixed = mixed_nuclide_data.new_six_group_prke().unwrap();
+    let prke_pu239 = SixGroupPRKE::new_from_nuclide(FissioningNuclideType::Pu239);
+
+    for i in 0..6 {
+        assert_relative_eq!(
+            prke_mixed.delayed_fraction_array[i].value,
+            prke_pu239.delayed_fraction_array[i].value,
+            max_relative = 1e-12);
+        assert_relative_eq!(
+            prke_mixed.decay_constant_array[i].value,
+            prke_pu239.decay_constant_array[i].value,
+            max_relative = 1e-12);
+    }
+    assert_eq!(prke_mixed.delayed_group_mode, FissioningNuclideType::Pu239);
+}
+
+#[test]
+pub fn mixed_nuclide_delayed_data_is_fission_fraction_weighted(){
+
+    use approx::assert_abs_diff_eq;
+    use crate::zero_power_prke::six_group_precursor_prke::MixedNuclideDelayedNeutronData;
+
+    // fractions need not add up to one
+    let mut mixed_nuclide_data = MixedNuclideDelayedNeutronData::new(
+        vec![
+        (FissioningNuclideType::U235, Ratio::new::<ratio>(0.9)),
+        (FissioningNuclideType::U238, Ratio::new::<ratio>(0.3)),
+        ]
+    ).unwrap();
+
+    // 0.75 * 0.0065 + 0.25 * 0.0148 = 0.008575
+    let mut prke_mixed = mixed_nuclide_data.new_six_group_prke().unwrap();
+    assert_abs_diff_eq!(
+        prke_mixed.get_total_delayed_fraction().get::<ratio>(),
+        0.008575,
+        epsilon = 1e-12);
+    assert_eq!(prke_mixed.delayed_group_mode, FissioningNuclideType::U235);
+
+    // now pu239 builds up during the run
+    prke_mixed.neutron_population_number_density = 
+        VolumetricNumberDensity::new::<per_cubic_meter>(2.0);
+    mixed_nuclide_data.set_fission_fraction(
+        FissioningNuclideType::Pu239, Ratio::new::<ratio>(0.8)).unwrap();
+    mixed_nuclide_data.update_six_group_prke_delayed_data(&mut prke_mixed).unwrap();
+
+    // 0.45 * 0.0065 + 0.15 * 0.0148 + 0.4 * 0.0021 = 0.005985
+    assert_abs_diff_eq!(
+        prke_mixed.get_total_delayed_fraction().get::<ratio>(),
+        0.005985,
+        epsilon = 1e-12);
+    // state is kept
+    assert_eq!(prke_mixed.get_current_neutron_population_density().get::<per_cubic_meter>(), 2.0);
+
+    // effective decay constants lie between those of the nuclides
+    let effective_decay_constant_array = 
+        mixed_nuclide_data.get_effective_decay_constant_array().unwrap();
+    let u238_decay_constant_array = FissioningNuclideType::U238.get_decay_constant_array();
+    let u235_decay_constant_array = FissioningNuclideType::U235.get_decay_constant_array();
+    let pu239_decay_constant_array = FissioningNuclideType::Pu239.get_decay_constant_array();
+
+    for i in 0..6 {
+        let lambda_list = [
+            u238_decay_constant_array[i].value, 
+            u235_decay_constant_array[i].value, 
+            pu239_decay_constant_array[i].value];
+        let lambda_min = lambda_list.iter().cloned().fold(f64::INFINITY, f64::min);
+        let lambda_max = lambda_list.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        assert!(effective_decay_constant_array[i].value >= lambda_min);
+        assert!(effective_decay_constant_array[i].value <= lambda_max);
+    }
+
+    // negative fractions are refused
+    assert!(mixed_nuclide_data.set_fission_fraction(
+        FissioningNuclideType::Th232, Ratio::new::<ratio>(-0.1)).is_err());
+}
+
+#[test]
+pub fn mixed_nuclide_delayed_data_with_pu241(){
+    // a burnt lwr core, where pu241 fissions alongside 
+    // u235, u238 and pu239
+
+    use approx::assert_abs_diff_eq;
+    use uom::si::frequency::hertz;
+    use crate::zero_power_prke::six_group_precursor_prke::MixedNuclideDelayedNeutronData;
+
+    let fission_fraction_list = vec![
+        (FissioningNuclideType::U235, Ratio::new::<ratio>(0.6)),
+        (FissioningNuclideType::U238, Ratio::new::<ratio>(0.1)),
+        (FissioningNuclideType::Pu239, Ratio::new::<ratio>(0.25)),
+        (FissioningNuclideType::Pu241, Ratio::new::<ratio>(0.05)),
+    ];
+
+    let mixed_nuclide_data = MixedNuclideDelayedNeutronData::new(
+        fission_fraction_list.clone()).unwrap();
+
+    // 0.6 * 0.0065 + 0.1 * 0.0148 + 0.25 * 0.0021 + 0.05 * 0.0054 = 0.006175
+    let prke_mixed = mixed_nuclide_data.new_six_group_prke().unwrap();
+    assert_abs_diff_eq!(
+        prke_mixed.get_total_delayed_fraction().get::<ratio>(),
+        0.006175,
+        epsilon = 1e-12);
+
+    // group by group, beta_i (eff) = sum_k f_k beta_ik 
+    // and beta_i (eff)/lambda_i (eff) = sum_k f_k beta_ik/lambda_ik
+    let effective_delayed_fraction_array = 
+        mixed_nuclide_data.get_effective_delayed_fraction_array().unwrap();
+    let effective_decay_constant_array = 
+        mixed_nuclide_data.get_effective_decay_constant_array().unwrap();
+
+    for i in 0..6 {
+        let mut delayed_fraction_reference: f64 = 0.0;
+        let mut precursor_inventory_reference: f64 = 0.0;
+
+        for (nuclide, fission_fraction) in fission_fraction_list.iter() {
+            let beta_ik = nuclide.get_delayed_fraction_array()[i].get::<ratio>();
+            let lambda_ik = nuclide.get_decay_constant_array()[i].get::<hertz>();
+
+            delayed_fraction_reference += fission_fraction.get::<ratio>() * beta_ik;
+            precursor_inventory_reference += fission_fraction.get::<ratio>() * beta_ik/lambda_ik;
+        }
+
+        assert_abs_diff_eq!(
+            effective_delayed_fraction_array[i].get::<ratio>(),
+            delayed_fraction_reference,
+            epsilon = 1e-12);
+        assert_abs_diff_eq!(
+            effective_delayed_fraction_array[i].get::<ratio>()
+            /effective_decay_constant_array[i].get::<hertz>(),
+            precursor_inventory_reference,
+            epsilon = 1e-12);
+    }
+}
+
+#[test]
+pub fn new_nuclides_total_delayed_frac(){
+
+    use approx::assert_abs_diff_eq;
+
+    let reference_list = [
+        (FissioningNuclideType::U238, 0.0148),
+        (FissioningNuclideType::Pu241, 0.0054),
+        (FissioningNuclideType::Th232, 0.0203),
+    ];
+
+    for (nuclide, total_delayed_fraction_reference) in reference_list {
+        let test_delayed_frac: Ratio = 
+            nuclide.get_delayed_fraction_array().into_iter().sum();
+
+        assert_abs_diff_eq!(
+            test_delayed_frac.get::<ratio>(), 
+            total_delayed_fraction_reference, 
+            epsilon = 1e-12);
+    }
+}
+
+#[test]
+pub fn inhour_equation_roots_and_amplitudes(){
+    // every root should satisfy the inhour equation, the amplitudes
+    // should add up to one (n(0)/n(0) = 1), and the stable 
+    // period should give back the same reactivity
+
+    use uom::si::time::{microsecond, second};
+    use uom::si::frequency::hertz;
+    use approx::assert_relative_eq;
+    use approx::assert_abs_diff_eq;
+
+    let prke = SixGroupPRKE::default();
+    let neutron_generation_time = Time::new::<microsecond>(100.0);
+
+    for reactivity_float in [-0.005, -0.001, 0.001, 0.003, 0.0065, 0.008] {
+        let reactivity = Ratio::new::<ratio>(reactivity_float);
+
+        let inhour_solution = prke.solve_inhour_equation(
+            reactivity, neutron_generation_time).unwrap();
+
+        assert_eq!(inhour_solution.root_list.len(), 7);
+
+        for root in inhour_solution.root_list.iter() {
+            assert_abs_diff_eq!(
+                prke.calc_inhour_equation_reactivity(
+                    *root, neutron_generation_time).get::<ratio>(),
+                reactivity_float,
+                epsilon = 1e-10);
+        }
+
+        let amplitude_sum: Ratio = inhour_solution.amplitude_list.iter().copied().sum();
+        assert_relative_eq!(amplitude_sum.get::<ratio>(), 1.0, max_relative = 1e-9);
+
+        // roots are sorted from largest to smallest
+        for root_pair in inhour_solution.root_list.windows(2) {
+            assert!(root_pair[0] > root_pair[1]);
+        }
+
+        let stable_period = inhour_solution.get_stable_period();
+        assert_relative_eq!(
+            prke.get_reactivity_from_stable_period(
+                stable_period, neutron_generation_time).get::<ratio>(),
+            reactivity_float,
+            max_relative = 1e-9);
+
+        // prompt decay root is about -(beta-rho)/Lambda
+        // when far from prompt critical
+        if reactivity_float < 0.003 {
+            let prompt_root_estimate = 
+                -(0.0065 - reactivity_float)/neutron_generation_time.get::<second>();
+            assert_relative_eq!(
+                inhour_solution.get_prompt_decay_root().get::<hertz>(),
+                prompt_root_estimate,
+                max_relative = 0.05);
+        }
+    }
+
+    // zero reactivity has an infinite period
+    let inhour_solution = prke.solve_inhour_equation(
+        Ratio::ZERO, neutron_generation_time).unwrap();
+    assert!(inhour_solution.get_stable_period().get::<second>().is_infinite());
+    assert_eq!(prke.get_reactivity_from_stable_period(
+            Time::new::<second>(f64::INFINITY), neutron_generation_time), 
+        Ratio::ZERO);
+
+    // negative generation times are refused
+    assert!(prke.solve_inhour_equation(
+            Ratio::new::<ratio>(0.001), 
+            Time::new::<second>(-1.0e-4)).is_err());
+}
+
+#[test]
+pub fn inhour_equation_solution_matches_implicit_solver(){
+    // a step reactivity from critical equilibrium should follow 
+    // n(t)/n(0) = sum_j A_j exp(omega_j t)
+
+    use uom::si::time::{microsecond, second};
+    use approx::assert_relative_eq;
+
+    let mut prke = SixGroupPRKE::default();
+    let neutron_generation_time = Time::new::<microsecond>(100.0);
+    let reactivity = Ratio::new::<ratio>(0.001);
+    let background_source_rate = VolumetricNumberRate::ZERO;
+
+    // precursors at equilibrium, C_i = beta_i n/(lambda_i Lambda)
+    let neutron_population_number_density = 
+        prke.get_current_neutron_population_density();
+    for i in 0..6 {
+        prke.precursor_concentration_array[i] = 
+            (prke.delayed_fraction_array[i] * neutron_population_number_density
+            /(prke.decay_constant_array[i] * neutron_generation_time)).into();
+    }
+
+    let inhour_solution = prke.solve_inhour_equation(
+        reactivity, neutron_generation_time).unwrap();
+
+    let timestep = Time::new::<microsecond>(50.0);
+    let simulation_time = Time::new::<second>(1.0);
+    let number_of_timesteps = 
+        (simulation_time/timestep).get::<ratio>().round() as usize;
+
+    for _ in 0..number_of_timesteps {
+        prke.solve_next_timestep_precursor_concentration_and_neutron_pop_vector_implicit(
+            timestep, 
+            reactivity, 
+            neutron_generation_time, 
+            background_source_rate).unwrap();
+    }
+
+    let neutron_population_ratio: Ratio = 
+        prke.get_current_neutron_population_density()
+        /neutron_population_number_density;
+
+    assert_relative_eq!(
+        neutron_population_ratio.get::<ratio>(),
+        inhour_solution.get_neutron_population_ratio(simulation_time).get::<ratio>(),
+        max_relative = 1e-3);
+}
+
+#[test]
+pub fn inverse_kinetics_recovers_step_reactivity_nonuniform_sampling(){
+    // the inhour solution is sampled at uneven times,
+    // the reactivity meter should give back the step reactivity
+
+    use uom::si::time::{microsecond, second};
+    use uom::si::power::megawatt;
+    use approx::assert_relative_eq;
+
+    let prke = SixGroupPRKE::default();
+    let neutron_generation_time = Time::new::<microsecond>(100.0);
+    let reactivity = Ratio::new::<ratio>(0.002);
+
+    let inhour_solution = prke.solve_inhour_equation(
+        reactivity, neutron_generation_time).unwrap();
+
+    // after the prompt jump, sample spacing grows 
+    // from 1 ms to about 3 ms
+    let time_list: Vec<Time> = (0..500).map(|k|{
+        let k = k as f64;
+        Time::new::<second>(0.5 + 1.0e-3 * k + 2.0e-6 * k * k)
+    }).collect();
+
+    let neutron_density_list: Vec<VolumetricNumberDensity> = time_list.iter()
+        .map(|&time|{
+            let neutron_population_ratio = 
+                inhour_solution.get_neutron_population_ratio(time);
+            (neutron_population_ratio 
+             * VolumetricNumberDensity::new::<per_cubic_meter>(1.0e12)).into()
+        }).collect();
+
+    // the meter assumes the trace starts at steady state, 
+    // so the trace starts with a short steady section 
+    // before the step at t = 0
+    let mut full_time_list: Vec<Time> = vec![
+        Time::new::<second>(-0.002),
+        Time::new::<second>(-0.001),
+    ];
+    let mut full_neutron_density_list: Vec<VolumetricNumberDensity> = vec![
+        VolumetricNumberDensity::new::<per_cubic_meter>(1.0e12),
+        VolumetricNumberDensity::new::<per_cubic_meter>(1.0e12),
+    ];
+    // fine sampling through the prompt jump
+    for k in 0..500 {
+        let time = Time::new::<second>(1.0e-3 * k as f64);
+        full_time_list.push(time);
+        full_neutron_density_list.push(
+            (inhour_solution.get_neutron_population_ratio(time) 
+            * VolumetricNumberDensity::new::<per_cubic_meter>(1.0e12)).into());
+    }
+    full_time_list.extend(time_list.iter());
+    full_neutron_density_list.extend(neutron_density_list.iter());
+
+    let reactivity_history = prke.calc_reactivity_history_from_neutron_density(
+        neutron_generation_time, 
+        &full_time_list, 
+        &full_neutron_density_list, 
+        None).unwrap();
+
+    // before the step, reactivity is zero
+    assert!(reactivity_history[0].get::<ratio>().abs() < 1.0e-10);
+
+    // well after the step, we should get the step back
+    for reactivity_calculated in reactivity_history[100..].iter() {
+        assert_relative_eq!(
+            reactivity_calculated.get::<ratio>(),
+            0.002,
+            max_relative = 1e-3);
+    }
+
+    // power traces give the same answer
+    let power_list: Vec<Power> = full_neutron_density_list.iter()
+        .map(|neutron_density|{
+            Power::new::<megawatt>(10.0) 
+                * neutron_density.get::<per_cubic_meter>()/1.0e12
+        }).collect();
+
+    let reactivity_history_from_power = prke.calc_reactivity_history_from_power(
+        neutron_generation_time, 
+        &full_time_list, 
+        &power_list, 
+        None).unwrap();
+
+    for (reactivity_density, reactivity_power) in reactivity_history.iter()
+        .zip(reactivity_history_from_power.iter()) {
+            assert_relative_eq!(
+                reactivity_density.get::<ratio>(),
+                reactivity_power.get::<ratio>(),
+                max_relative = 1e-9,
+                epsilon = 1e-12);
+    }
+
+    // mismatched lengths are refused
+    assert!(prke.calc_reactivity_history_from_power(
+            neutron_generation_time, 
+            &full_time_list[1..], 
+            &power_list, 
+            None).is_err());
+}
+
+#[test]
+pub fn inverse_kinetics_subcritical_with_source(){
+    // a subcritical reactor in steady state with a source 
+    // has n = - S Lambda/rho, the meter must account for the 
+    // source to read the right reactivity
+
+    use uom::si::time::{microsecond, second};
+    use uom::si::frequency::hertz;
+    use uom::si::volumetric_number_rate::per_cubic_meter_second;
+    use approx::assert_relative_eq;
+
+    let prke = SixGroupPRKE::default();
+    let neutron_generation_time = Time::new::<microsecond>(100.0);
+    let reactivity = -0.01;
+    let background_source_rate = 
+        VolumetricNumberRate::new::<per_cubic_meter_second>(1.0e6);
+
+    let steady_neutron_density = VolumetricNumberDensity::new::<per_cubic_meter>(
+        -1.0e6 * 1.0e-4/reactivity);
+
+    // uniform sampling this time
+    let time_list: Vec<Time> = (0..20).map(|k|{
+        Time::new::<second>(0.1 * k as f64)
+    }).collect();
+    let neutron_density_list = vec![steady_neutron_density; 20];
+
+    let reactivity_history = prke.calc_reactivity_history_from_neutron_density(
+        neutron_generation_time, 
+        &time_list, 
+        &neutron_density_list, 
+        Some(background_source_rate)).unwrap();
+
+    for reactivity_calculated in reactivity_history.iter() {
+        assert_relative_eq!(
+            reactivity_calculated.get::<ratio>(),
+            reactivity,
+            max_relative = 1e-9);
+    }
+
+    // without the source, the meter reads zero
+    let reactivity_history = prke.calc_reactivity_history_from_neutron_density(
+        neutron_generation_time, 
+        &time_list, 
+        &neutron_density_list, 
+        None).unwrap();
+
+    assert!(reactivity_history[10].get::<ratio>().abs() < 1e-12);
+
+    // the power version takes the source relative to 
+    // the starting neutron density
+    let power_list = vec![Power::new::<uom::si::power::watt>(5.0); 20];
+    let relative_background_source_rate = Frequency::new::<hertz>(
+        1.0e6/steady_neutron_density.get::<per_cubic_meter>());
+
+    let reactivity_history = prke.calc_reactivity_history_from_power(
+        neutron_generation_time, 
+        &time_list, 
+        &power_list, 
+        Some(relative_background_source_rate)).unwrap();
+
+    assert_relative_eq!(
+        reactivity_history[5].get::<ratio>(),
+        reactivity,
+        max_relative = 1e-9);
+}
+
+#[test]
+pub fn equilibrium_initialisation_stays_at_steady_state(){
+    // a PRKE started at equilibrium should not move 
+    // when the solver runs at the matching reactivity
+
+    use uom::si::time::{microsecond, second};
+    use uom::si::volumetric_number_rate::per_cubic_meter_second;
+    use approx::assert_relative_eq;
+
+    let neutron_generation_time = Time::new::<microsecond>(100.0);
+    let timestep = Time::new::<microsecond>(100.0);
+    let starting_neutron_density = VolumetricNumberDensity::new::<per_cubic_meter>(1.0e10);
+
+    // critical, no source
+    let mut prke_critical = SixGroupPRKE::new_at_equilibrium(
+        FissioningNuclideType::U235, 
+        starting_neutron_density, 
+        neutron_generation_time);
+
+    // subcritical, with a source
+    let reactivity_subcritical = Ratio::new::<ratio>(-0.02);
+    let background_source_rate = 
+        VolumetricNumberRate::new::<per_cubic_meter_second>(1.0e8);
+    let mut prke_subcritical = SixGroupPRKE::new_at_source_equilibrium(
+        FissioningNuclideType::U233, 
+        reactivity_subcritical, 
+        neutron_generation_time, 
+        background_source_rate).unwrap();
+
+    // n = - S Lambda/rho = 1e8 * 1e-4 / 0.02 = 5e5
+    assert_relative_eq!(
+        prke_subcritical.get_current_neutron_population_density().get::<per_cubic_meter>(),
+        5.0e5,
+        max_relative = 1e-12);
+
+    let number_of_timesteps = 
+        (Time::new::<second>(1.0)/timestep).get::<ratio>().round() as usize;
+
+    for _ in 0..number_of_timesteps {
+        prke_critical.solve_next_timestep_precursor_concentration_and_neutron_pop_vector_implicit(
+            timestep, 
+            Ratio::ZERO, 
+            neutron_generation_time, 
+            VolumetricNumberRate::ZERO).unwrap();
+        prke_subcritical.solve_next_timestep_precursor_concentration_and_neutron_pop_vector_implicit(
+            timestep, 
+            reactivity_subcritical, 
+            neutron_generation_time, 
+            background_source_rate).unwrap();
+    }
+
+    assert_relative_eq!(
+        prke_critical.get_current_neutron_population_density().get::<per_cubic_meter>(),
+        1.0e10,
+        max_relative = 1e-9);
+    assert_relative_eq!(
+        prke_subcritical.get_current_neutron_population_density().get::<per_cubic_meter>(),
+        5.0e5,
+        max_relative = 1e-9);
+
+    // no source equilibrium for a critical or supercritical core
+    assert!(SixGroupPRKE::new_at_source_equilibrium(
+            FissioningNuclideType::U235, 
+            Ratio::ZERO, 
+            neutron_generation_time, 
+            background_source_rate).is_err());
+}
+
+#[test]
+pub fn equilibrium_initialisation_from_power(){
+
+    use uom::si::time::microsecond;
+    use uom::si::power::megawatt;
+    use uom::si::velocity::meter_per_second;
+    use uom::si::linear_number_density::per_meter;
+    use uom::si::volume::cubic_meter;
+    use uom::si::energy::megaelectronvolt;
+    use uom::si::volumetric_number_rate::per_cubic_meter_second;
+    use approx::assert_relative_eq;
+    use crate::zero_power_prke::reactor_power::ReactorPowerModel;
+
+    let neutron_generation_time = Time::new::<microsecond>(231.0);
+    let reactor_power = Power::new::<megawatt>(10.0);
+    let neutron_speed = Velocity::new::<meter_per_second>(2200.0);
+    let macroscopic_fission_xs = LinearNumberDensity::new::<per_meter>(1.0);
+    let reactor_volume = Volume::new::<cubic_meter>(0.5);
+    let energy_per_fission = Energy::new::<megaelectronvolt>(200.0);
+
+    let reactor_power_model = ReactorPowerModel::new(
+        energy_per_fission, 
+        macroscopic_fission_xs, 
+        neutron_speed, 
+        reactor_volume).unwrap();
+
+    let prke = SixGroupPRKE::new_at_equilibrium_from_reactor_power(
+        FissioningNuclideType::U235, 
+        reactor_power, 
+        neutron_generation_time, 
+        &reactor_power_model);
+
+    // n = P/(v Sigma_f V E_f)
+    let energy_per_fission_joules = 200.0e6 * 1.602176634e-19;
+    let neutron_density_reference = 10.0e6
+        /(2200.0 * 1.0 * 0.5 * energy_per_fission_joules);
+
+    assert_relative_eq!(
+        prke.get_current_neutron_population_density().get::<per_cubic_meter>(),
+        neutron_density_reference,
+        max_relative = 1e-9);
+
+    // precursors are at equilibrium
+    for i in 0..6 {
+        let precursor_production_rate: VolumetricNumberRate = 
+            (prke.delayed_fraction_array[i] 
+             * prke.get_current_neutron_population_density()
+             /neutron_generation_time).into();
+        let precursor_decay_rate: VolumetricNumberRate = 
+            (prke.decay_constant_array[i] 
+             * prke.precursor_concentration_array[i]).into();
+
+        assert_relative_eq!(
+            precursor_production_rate.get::<per_cubic_meter_second>(), 
+            precursor_decay_rate.get::<per_cubic_meter_second>(), 
+            max_relative = 1e-12);
+    }
+
+    // zero volume is refused
+    assert!(ReactorPowerModel::new(
+            energy_per_fission, 
+            macroscopic_fission_xs, 
+            neutron_speed, 
+            Volume::ZERO).is_err());
+}
+
+#[test]
+pub fn explicit_adaptive_solver_large_reactivity_insertion(){
+    // half a dollar step with one large timestep, the adaptive 
+    // solver should split the step and match the inhour solution
+
+    use uom::si::time::{microsecond, second};
+    use approx::assert_relative_eq;
+    use crate::time_stepping::openfoam_adaptive_solver::AdaptiveSolver;
+
+    let neutron_generation_time = Time::new::<microsecond>(100.0);
+    let reactivity = Ratio::new::<ratio>(0.00325);
+
+    let mut prke = SixGroupPRKE::new_at_equilibrium(
+        FissioningNuclideType::U235, 
+        VolumetricNumberDensity::new::<per_cubic_meter>(1.0), 
+        neutron_generation_time);
+
+    let inhour_solution = prke.solve_inhour_equation(
+        reactivity, neutron_generation_time).unwrap();
+
+    let timestep = Time::new::<second>(1.0);
+
+    let (_neutron_pop_and_precursor_vector, accepted_substep_list) = 
+        prke.solve_next_timestep_precursor_concentration_and_neutron_pop_vector_explicit_adaptive(
+            timestep, 
+            reactivity, 
+            neutron_generation_time, 
+            VolumetricNumberRate::ZERO,
+            &AdaptiveSolver::new(1e-15, 1e-8)).unwrap();
+
+    assert!(accepted_substep_list.len() > 1);
+
+    let total_substep: Time = accepted_substep_list.into_iter().sum();
+    assert_relative_eq!(total_substep.get::<second>(), 1.0, max_relative = 1e-12);
+
+    assert_relative_eq!(
+        prke.get_current_neutron_population_density().get::<per_cubic_meter>(),
+        inhour_solution.get_neutron_population_ratio(timestep).get::<ratio>(),
+        max_relative = 1e-6);
+}
+
+#[test]
+pub fn ode_solver_methods_match_inhour_solution(){
+    // every ODESolver method should reproduce the inhour 
+    // solution for a step reactivity, the implicit methods 
+    // doing so with far fewer substeps than the explicit ones
+
+    use uom::si::time::{microsecond, second};
+    use approx::assert_relative_eq;
+    use crate::time_stepping::openfoam_adaptive_solver::AdaptiveSolver;
+    use crate::time_stepping::openfoam_ode_solver::ODESolverMethod;
+
+    let neutron_generation_time = Time::new::<microsecond>(10.0);
+    let reactivity = Ratio::new::<ratio>(0.003);
+    let timestep = Time::new::<second>(0.5);
+
+    let reference_prke = SixGroupPRKE::new_at_equilibrium(
+        FissioningNuclideType::U235, 
+        VolumetricNumberDensity::new::<per_cubic_meter>(1.0), 
+        neutron_generation_time);
+
+    let reference_neutron_density = reference_prke
+        .solve_inhour_equation(reactivity, neutron_generation_time).unwrap()
+        .get_neutron_population_ratio(timestep).get::<ratio>();
+
+    let adaptive_solver = AdaptiveSolver::new(1e-12, 1e-7);
+
+    let mut number_of_substeps_list: Vec<usize> = vec![];
+
+    for ode_solver_method in [
+        ODESolverMethod::RKF45,
+        ODESolverMethod::Rosenbrock23,
+        ODESolverMethod::Rosenbrock34,
+        ODESolverMethod::Rodas23,
+        ODESolverMethod::Rodas34] {
+
+        let mut prke = reference_prke;
+
+        let (_neutron_pop_and_precursor_vector, accepted_substep_list) = 
+            prke.solve_next_timestep_precursor_concentration_and_neutron_pop_vector_with_ode_solver(
+                timestep, 
+                reactivity, 
+                neutron_generation_time, 
+                VolumetricNumberRate::ZERO, 
+                ode_solver_method, 
+                &adaptive_solver).unwrap();
+
+        assert_relative_eq!(
+            prke.get_current_neutron_population_density().get::<per_cubic_meter>(),
+            reference_neutron_density,
+            max_relative = 1e-5);
+
+        number_of_substeps_list.push(accepted_substep_list.len());
+    }
+
+    // the stiff solvers beat RKF45 
+    let rkf45_substeps = number_of_substeps_list[0];
+    assert!(number_of_substeps_list[4] < rkf45_substeps);
+}
+
+#[test]
+pub fn prke_analytic_jacobian_matches_coefficient_matrix_and_finite_difference(){
+    // the analytic jacobian should agree with a finite difference 
+    // estimate, and with the implicit solver's coefficient matrix
+    // once the timestep is taken out
+
+    use uom::si::time::{microsecond, second};
+    use uom::si::frequency::hertz;
+    use uom::si::volumetric_number_rate::per_cubic_meter_second;
+    use approx::assert_relative_eq;
+    use ndarray::Array2;
+    use crate::time_stepping::openfoam_ode_system::ODESystem;
+
+    let neutron_generation_time = Time::new::<microsecond>(50.0);
+    let reactivity = Ratio::new::<ratio>(0.002);
+    let timestep = Time::new::<second>(0.01);
+
+    let prke = SixGroupPRKE::new_at_equilibrium(
+        FissioningNuclideType::U235, 
+        VolumetricNumberDensity::new::<per_cubic_meter>(1.0e10), 
+        neutron_generation_time);
+
+    let jacobian_matrix = prke.construct_jacobian_matrix(
+        reactivity, neutron_generation_time);
+    let coefficient_matrix = prke.construct_coefficient_matrix(
+        timestep, reactivity, neutron_generation_time);
+
+    let prke_ode_system = prke.construct_ode_system(
+        reactivity, 
+        neutron_generation_time, 
+        VolumetricNumberRate::new::<per_cubic_meter_second>(1.0e5));
+
+    // same derivatives without the analytic jacobian, so that 
+    // the jacobian comes from finite differences
+    let finite_difference_ode_system = {
+        let prke_ode_system = prke_ode_system.clone();
+        ODESystem::new(move |t: f64, y: &[f64], dydt: &mut [f64]| {
+            prke_ode_system.derivatives(t, y, dydt);
+        })
+    };
+
+    let neutron_pop_and_precursor_vector_si_units: Vec<f64> = 
+        prke.get_neutron_pop_and_precursor_vector().iter()
+        .map(|num_density| num_density.get::<per_cubic_meter>())
+        .collect();
+
+    let mut dfdx_finite_difference: Vec<f64> = vec![0.0; 7];
+    let mut dfdy_finite_difference: Array2<f64> = Array2::zeros((7, 7));
+    finite_difference_ode_system.jacobian(0.0, 
+        &neutron_pop_and_precursor_vector_si_units, 
+        &mut dfdx_finite_difference, 
+        &mut dfdy_finite_difference);
+
+    // analytic jacobian, written into the buffers given
+    let mut dfdx: Vec<f64> = vec![0.0; 7];
+    let mut dfdy: Array2<f64> = Array2::zeros((7, 7));
+    prke_ode_system.jacobian(0.0, 
+        &neutron_pop_and_precursor_vector_si_units, 
+        &mut dfdx, 
+        &mut dfdy);
+
+    assert!(dfdx.iter().all(|&dfdx_i| dfdx_i == 0.0));
+    assert!(dfdx_finite_difference.iter().all(|&dfdx_i| dfdx_i == 0.0));
+
+    for i in 0..7 {
+        for j in 0..7 {
+            let identity_entry = if i == j { 1.0 } else { 0.0 };
+
+            // coefficient_matrix = I - dt * jacobian
+            assert_relative_eq!(
+                coefficient_matrix[[i,j]].get::<ratio>(),
+                identity_entry - timestep.get::<second>() * jacobian_matrix[[i,j]].get::<hertz>(),
+                epsilon = 1e-12);
+
+            assert_eq!(dfdy[[i,j]], jacobian_matrix[[i,j]].get::<hertz>());
+
+            // finite differences only keep about half the 
+            // significant figures
+            assert_relative_eq!(
+                dfdy_finite_difference[[i,j]],
+                dfdy[[i,j]],
+                epsilon = 1e-6 * dfdy[[0,0]].abs(),
+                max_relative = 1e-5);
+        }
+    }
+}
+
+#[test]
+pub fn rodas_with_analytic_jacobian_fast_reactor_transient(){
+    // a fast reactor has a generation time of about 1e-7 s, so 
+    // the prompt eigenvalue is of order -(beta - rho)/Lambda = -3e4/s
+    //
+    // RKF45 is limited by stability to steps of a few hundred
+    // microseconds, while the Rosenbrock and Rodas methods with
+    // the analytic jacobian follow the inhour solution over a 
+    // 1 s timestep in far fewer substeps
+
+    use uom::si::time::second;
+    use approx::assert_relative_eq;
+    use crate::time_stepping::openfoam_adaptive_solver::AdaptiveSolver;
+    use crate::time_stepping::openfoam_ode_solver::ODESolverMethod;
+
+    let neutron_generation_time = Time::new::<second>(1.0e-7);
+    let timestep = Time::new::<second>(1.0);
+
+    let reference_prke = SixGroupPRKE::new_at_equilibrium(
+        FissioningNuclideType::Pu239, 
+        VolumetricNumberDensity::new::<per_cubic_meter>(1.0), 
+        neutron_generation_time);
+
+    // half a dollar
+    let reactivity = 0.5 * reference_prke.get_total_delayed_fraction();
+
+    let reference_neutron_density = reference_prke
+        .solve_inhour_equation(reactivity, neutron_generation_time).unwrap()
+        .get_neutron_population_ratio(timestep).get::<ratio>();
+
+    let adaptive_solver = AdaptiveSolver::new(1e-12, 1e-7);
+
+    let mut number_of_substeps_list: Vec<usize> = vec![];
+
+    for ode_solver_method in [
+        ODESolverMethod::RKF45,
+        ODESolverMethod::Rosenbrock34,
+        ODESolverMethod::Rodas23,
+        ODESolverMethod::Rodas34] {
+
+        let mut prke = reference_prke;
+
+        let (_neutron_pop_and_precursor_vector, accepted_substep_list) = 
+            prke.solve_next_timestep_precursor_concentration_and_neutron_pop_vector_with_ode_solver(
+                timestep, 
+                reactivity, 
+                neutron_generation_time, 
+                VolumetricNumberRate::ZERO, 
+                ode_solver_method, 
+                &adaptive_solver).unwrap();
+
+        assert_relative_eq!(
+            prke.get_current_neutron_population_density().get::<per_cubic_meter>(),
+            reference_neutron_density,
+            max_relative = 1e-5);
+
+        number_of_substeps_list.push(accepted_substep_list.len());
+    }
+
+    let rkf45_substeps = number_of_substeps_list[0];
+
+    for number_of_substeps in number_of_substeps_list[1..].iter() {
+        assert!(5 * number_of_substeps < rkf45_substeps,
+            "{} substeps against {} for RKF45", number_of_substeps, rkf45_substeps);
+    }
+}
+
+#[test]
+pub fn prke_ode_system_drives_ode_solver_and_rkf45(){
+    // the PRKE ODESystem carries its own reactivity, generation
+    // time and source, so it can be stepped by an ODESolver or
+    // the RKF45 struct without going through the PRKE struct
+
+    use uom::si::time::{microsecond, second};
+    use uom::si::volumetric_number_rate::per_cubic_meter_second;
+    use approx::assert_relative_eq;
+    use ndarray::Array1;
+    use uom::si::frequency::hertz;
+    use crate::time_stepping::openfoam_ode_solver::{ODESolver, ODESolverMethod};
+    use crate::time_stepping::openfoam_rfk45::RKF45;
+    use crate::time_stepping::openfoam_adaptive_solver::AdaptiveSolver;
+
+    let neutron_generation_time = Time::new::<microsecond>(20.0);
+    let reactivity = Ratio::new::<ratio>(0.002);
+    let background_source_rate = VolumetricNumberRate::new::<per_cubic_meter_second>(1.0e3);
+    let timestep = Time::new::<second>(0.1);
+
+    let prke = SixGroupPRKE::new_at_equilibrium(
+        FissioningNuclideType::U235, 
+        VolumetricNumberDensity::new::<per_cubic_meter>(1.0e6), 
+        neutron_generation_time);
+
+    let prke_ode_system = prke.construct_ode_system(
+        reactivity, neutron_generation_time, background_source_rate);
+
+    assert!(prke_ode_system.has_analytic_jacobian());
+
+    let y0: Vec<f64> = prke.get_neutron_pop_and_precursor_vector().iter()
+        .map(|num_density| num_density.get::<per_cubic_meter>())
+        .collect();
+
+    // derivatives agree with the jacobian matrix times y plus 
+    // the source, as the PRKE is linear
+    let mut dydt = vec![0.0; 7];
+    prke_ode_system.derivatives(0.0, &y0, &mut dydt);
+
+    let mut dydt_reference: Array1<f64> = prke.construct_jacobian_matrix(
+        reactivity, neutron_generation_time)
+        .map(|jacobian_entry| jacobian_entry.get::<hertz>())
+        .dot(&Array1::from(y0.clone()));
+    dydt_reference[0] += background_source_rate.get::<per_cubic_meter_second>();
+
+    for i in 0..7 {
+        assert_relative_eq!(dydt[i], dydt_reference[i], 
+            max_relative = 1e-12, epsilon = 1e-9);
+    }
+
+    // step with the ODESolver and compare with the PRKE method
+    let adaptive_solver = AdaptiveSolver::new(1e-12, 1e-8);
+
+    let mut reference_prke = prke;
+    reference_prke.solve_next_timestep_precursor_concentration_and_neutron_pop_vector_with_ode_solver(
+        timestep, 
+        reactivity, 
+        neutron_generation_time, 
+        background_source_rate, 
+        ODESolverMethod::Rodas34, 
+        &adaptive_solver).unwrap();
+
+    let reference_neutron_density = reference_prke
+        .get_current_neutron_population_density().get::<per_cubic_meter>();
+
+    let mut ode_solver = ODESolver::new(prke_ode_system.clone(), ODESolverMethod::Rodas34);
+    ode_solver.adaptive_solver = adaptive_solver;
+
+    let mut y = y0.clone();
+    let mut dx_try = timestep.get::<second>();
+    ode_solver.solve(0.0, timestep.get::<second>(), &mut y, &mut dx_try).unwrap();
+
+    assert_relative_eq!(y[0], reference_neutron_density, max_relative = 1e-12);
+
+    // the RKF45 struct, fixed small steps
+    let mut rkf45 = RKF45::new(prke_ode_system.clone(), 7);
+    let number_of_steps = 10000;
+    let dt = timestep.get::<second>()/number_of_steps as f64;
+
+    let mut y = y0;
+    let mut dydt = vec![0.0; 7];
+    let mut y_next = vec![0.0; 7];
+    for step in 0..number_of_steps {
+        let t = step as f64 * dt;
+        prke_ode_system.derivatives(t, &y, &mut dydt);
+
+        rkf45.solve(t, &y, &dydt, dt, &mut y_next);
+        std::mem::swap(&mut y, &mut y_next);
+    }
+
+    assert_relative_eq!(y[0], reference_neutron_density, max_relative = 1e-6);
+}
+
+#[test]
+pub fn implicit_arrowhead_solve_matches_dense_lu_solve(){
+    // the closed form implicit step should give the same answer
+    // as a dense LU solve of the coefficient matrix, with a
+    // source and a timestep long enough to make the matrix far 
+    // from the identity
+
+    use uom::si::time::{microsecond, second};
+    use uom::si::volumetric_number_rate::per_cubic_meter_second;
+    use approx::assert_relative_eq;
+    use crate::linear_algebra::solve_linear_system;
+
+    let neutron_generation_time = Time::new::<microsecond>(25.0);
+    let background_source_rate = VolumetricNumberRate::new::<per_cubic_meter_second>(5.0e4);
+    let timestep = Time::new::<second>(0.05);
+
+    let mut prke = SixGroupPRKE::new_at_equilibrium(
+        FissioningNuclideType::Pu239, 
+        VolumetricNumberDensity::new::<per_cubic_meter>(1.0e8), 
+        neutron_generation_time);
+
+    for reactivity_value in [0.001, -0.01, 0.0015, 0.0] {
+
+        let reactivity = Ratio::new::<ratio>(reactivity_value);
+
+        let coefficient_matrix_float = prke.construct_coefficient_matrix(
+            timestep, reactivity, neutron_generation_time)
+            .map(|coefficient| coefficient.get::<ratio>());
+
+        let rhs: Vec<f64> = 
+            SixGroupPRKE::construct_present_timestep_concentration_and_neutron_pop_vector(
+                prke.precursor_concentration_array, 
+                prke.neutron_population_number_density, 
+                background_source_rate, 
+                timestep)
+            .iter()
+            .map(|num_density| num_density.get::<per_cubic_meter>())
+            .collect();
+
+        let dense_solution = solve_linear_system(&coefficient_matrix_float, &rhs).unwrap();
+
+        let arrowhead_solution = prke
+            .solve_next_timestep_precursor_concentration_and_neutron_pop_vector_implicit(
+                timestep, 
+                reactivity, 
+                neutron_generation_time, 
+                background_source_rate).unwrap();
+
+        for (arrowhead_entry, dense_entry) in arrowhead_solution.iter().zip(dense_solution.iter()) {
+            assert_relative_eq!(
+                arrowhead_entry.get::<per_cubic_meter>(), 
+                *dense_entry, 
+                max_relative = 1e-12);
+        }
+    }
+}
+
+#[test]
+pub fn matrix_exponential_stepper_matches_inhour_solution(){
+    // for a step reactivity, the matrix exponential stepper
+    // should reproduce the inhour solution to round off, even
+    // with steps of several seconds, and reuse the cached
+    // matrix exponential while the reactivity is unchanged
+
+    use uom::si::time::{microsecond, second};
+    use uom::si::volumetric_number_rate::per_cubic_meter_second;
+    use approx::assert_relative_eq;
+    use crate::zero_power_prke::multi_group_precursor_prke::MatrixExponentialCache;
+
+    let neutron_generation_time = Time::new::<microsecond>(100.0);
+    let reactivity = Ratio::new::<ratio>(0.003);
+    let timestep = Time::new::<second>(5.0);
+
+    let mut prke = SixGroupPRKE::new_at_equilibrium(
+        FissioningNuclideType::U235, 
+        VolumetricNumberDensity::new::<per_cubic_meter>(1.0), 
+        neutron_generation_time);
+
+    let inhour_equation_solution = prke
+        .solve_inhour_equation(reactivity, neutron_generation_time).unwrap();
+
+    let mut matrix_exponential_cache = MatrixExponentialCache::new();
+
+    assert!(!matrix_exponential_cache.is_cached_for(
+        &prke, timestep, reactivity, neutron_generation_time));
+
+    for step in 1..=4 {
+        prke.solve_next_timestep_precursor_concentration_and_neutron_pop_vector_matrix_exponential(
+            timestep, 
+            reactivity, 
+            neutron_generation_time, 
+            VolumetricNumberRate::ZERO, 
+            &mut matrix_exponential_cache).unwrap();
+
+        let reference_neutron_density = inhour_equation_solution
+            .get_neutron_population_ratio(step as f64 * timestep).get::<ratio>();
+
+        assert_relative_eq!(
+            prke.get_current_neutron_population_density().get::<per_cubic_meter>(),
+            reference_neutron_density,
+            max_relative = 1e-10);
+
+        assert!(matrix_exponential_cache.is_cached_for(
+            &prke, timestep, reactivity, neutron_generation_time));
+    }
+
+    // a subcritical core with a source, started at source 
+    // equilibrium, should not move
+    let subcritical_reactivity = Ratio::new::<ratio>(-0.02);
+    let background_source_rate = VolumetricNumberRate::new::<per_cubic_meter_second>(1.0e6);
+
+    let mut subcritical_prke = SixGroupPRKE::new_at_source_equilibrium(
+        FissioningNuclideType::U235, 
+        subcritical_reactivity, 
+        neutron_generation_time, 
+        background_source_rate).unwrap();
+
+    let equilibrium_neutron_density = subcritical_prke
+        .get_current_neutron_population_density().get::<per_cubic_meter>();
+
+    subcritical_prke.solve_next_timestep_precursor_concentration_and_neutron_pop_vector_matrix_exponential(
+        Time::new::<second>(100.0), 
+        subcritical_reactivity, 
+        neutron_generation_time, 
+        background_source_rate, 
+        &mut matrix_exponential_cache).unwrap();
+
+    assert!(!matrix_exponential_cache.is_cached_for(
+        &prke, timestep, reactivity, neutron_generation_time));
+
+    assert_relative_eq!(
+        subcritical_prke.get_current_neutron_population_density().get::<per_cubic_meter>(),
+        equilibrium_neutron_density,
+        max_relative = 1e-10);
+}
+
+#[test]
+pub fn matrix_exponential_cache_follows_changes_in_delayed_data(){
+    // the cache key includes the delayed neutron data, so changing
+    // the nuclide between steps, or sharing one cache between two
+    // PRKEs with different data, must not reuse a stale exp(A dt)
+
+    use uom::si::time::{microsecond, second};
+    use approx::assert_relative_eq;
+    use crate::zero_power_prke::multi_group_precursor_prke::MatrixExponentialCache;
+    use crate::zero_power_prke::six_group_precursor_prke::MixedNuclideDelayedNeutronData;
+
+    let neutron_generation_time = Time::new::<microsecond>(100.0);
+    let reactivity = Ratio::new::<ratio>(0.001);
+    let timestep = Time::new::<second>(1.0);
+
+    let step_with_fresh_cache = |prke: &mut SixGroupPRKE| {
+        prke.solve_next_timestep_precursor_concentration_and_neutron_pop_vector_matrix_exponential(
+            timestep, 
+            reactivity, 
+            neutron_generation_time, 
+            VolumetricNumberRate::ZERO, 
+            &mut MatrixExponentialCache::new()).unwrap();
+    };
+
+    let mut prke = SixGroupPRKE::new_at_equilibrium(
+        FissioningNuclideType::U235, 
+        VolumetricNumberDensity::new::<per_cubic_meter>(1.0), 
+        neutron_generation_time);
+    let mut reference_prke = prke;
+
+    let mut matrix_exponential_cache = MatrixExponentialCache::new();
+
+    prke.solve_next_timestep_precursor_concentration_and_neutron_pop_vector_matrix_exponential(
+        timestep, 
+        reactivity, 
+        neutron_generation_time, 
+        VolumetricNumberRate::ZERO, 
+        &mut matrix_exponential_cache).unwrap();
+    step_with_fresh_cache(&mut reference_prke);
+
+    assert!(matrix_exponential_cache.is_cached_for(
+        &prke, timestep, reactivity, neutron_generation_time));
+
+    // switch the core over to Pu239 mid run
+    let pu239_delayed_data = MixedNuclideDelayedNeutronData::new(
+        vec![(FissioningNuclideType::Pu239, Ratio::new::<ratio>(1.0))]).unwrap();
+
+    pu239_delayed_data.update_six_group_prke_delayed_data(&mut prke).unwrap();
+    pu239_delayed_data.update_six_group_prke_delayed_data(&mut reference_prke).unwrap();
+
+    assert!(!matrix_exponential_cache.is_cached_for(
+        &prke, timestep, reactivity, neutron_generation_time));
+
+    prke.solve_next_timestep_precursor_concentration_and_neutron_pop_vector_matrix_exponential(
+        timestep, 
+        reactivity, 
+        neutron_generation_time, 
+        VolumetricNumberRate::ZERO, 
+        &mut matrix_exponential_cache).unwrap();
+    step_with_fresh_cache(&mut reference_prke);
+
+    assert_relative_eq!(
+        prke.get_current_neutron_population_density().get::<per_cubic_meter>(),
+        reference_prke.get_current_neutron_population_density().get::<per_cubic_meter>(),
+        max_relative = 1e-12);
+
+    // one cache shared between a U235 and a Pu239 core
+    let mut u235_prke = SixGroupPRKE::new_at_equilibrium(
+        FissioningNuclideType::U235, 
+        VolumetricNumberDensity::new::<per_cubic_meter>(1.0), 
+        neutron_generation_time);
+    let mut u235_reference_prke = u235_prke;
+
+    let mut pu239_prke = SixGroupPRKE::new_at_equilibrium(
+        FissioningNuclideType::Pu239, 
+        VolumetricNumberDensity::new::<per_cubic_meter>(1.0), 
+        neutron_generation_time);
+    let mut pu239_reference_prke = pu239_prke;
+
+    for _ in 0..3 {
+        for prke in [&mut u235_prke, &mut pu239_prke] {
+            prke.solve_next_timestep_precursor_concentration_and_neutron_pop_vector_matrix_exponential(
+                timestep, 
+                reactivity, 
+                neutron_generation_time, 
+                VolumetricNumberRate::ZERO, 
+                &mut matrix_exponential_cache).unwrap();
+        }
+        step_with_fresh_cache(&mut u235_reference_prke);
+        step_with_fresh_cache(&mut pu239_reference_prke);
+    }
+
+    assert_relative_eq!(
+        u235_prke.get_current_neutron_population_density().get::<per_cubic_meter>(),
+        u235_reference_prke.get_current_neutron_population_density().get::<per_cubic_meter>(),
+        max_relative = 1e-12);
+    assert_relative_eq!(
+        pu239_prke.get_current_neutron_population_density().get::<per_cubic_meter>(),
+        pu239_reference_prke.get_current_neutron_population_density().get::<per_cubic_meter>(),
+        max_relative = 1e-12);
+
+    // Pu239 has a smaller beta, so it runs away faster at the
+    // same reactivity, which a stale U235 matrix would hide
+    assert!(pu239_prke.get_current_neutron_population_density()
+        > u235_prke.get_current_neutron_population_density());
+}
+
+#[test]
+pub fn theta_method_matches_dense_solve_of_theta_matrix(){
+    // the closed form theta step should be the dense solve of
+    // construct_coefficient_matrix_theta with the right hand side
+    // from construct_present_timestep_concentration_and_neutron_pop_vector_theta
+
+    use uom::si::time::{microsecond, second};
+    use uom::si::volumetric_number_rate::per_cubic_meter_second;
+    use approx::assert_relative_eq;
+    use crate::linear_algebra::solve_linear_system;
+
+    let neutron_generation_time = Time::new::<microsecond>(40.0);
+    let background_source_rate = VolumetricNumberRate::new::<per_cubic_meter_second>(2.0e4);
+    let timestep = Time::new::<second>(0.02);
+    let reactivity_at_start = Ratio::new::<ratio>(0.001);
+    let reactivity_at_end = Ratio::new::<ratio>(0.0015);
+
+    let reference_prke = SixGroupPRKE::new_at_equilibrium(
+        FissioningNuclideType::U235, 
+        VolumetricNumberDensity::new::<per_cubic_meter>(1.0e7), 
+        neutron_generation_time);
+
+    for theta in [0.0, 0.5, 0.75, 1.0] {
+
+        let mut prke = reference_prke;
+
+        let coefficient_matrix_float = prke.construct_coefficient_matrix_theta(
+            timestep, reactivity_at_end, neutron_generation_time, theta)
+            .map(|coefficient| coefficient.get::<ratio>());
+
+        let rhs: Vec<f64> = prke
+            .construct_present_timestep_concentration_and_neutron_pop_vector_theta(
+                reactivity_at_start, 
+                neutron_generation_time, 
+                background_source_rate, 
+                timestep, 
+                theta)
+            .iter()
+            .map(|num_density| num_density.get::<per_cubic_meter>())
+            .collect();
+
+        let dense_solution = solve_linear_system(&coefficient_matrix_float, &rhs).unwrap();
+
+        let theta_method_solution = prke
+            .solve_next_timestep_precursor_concentration_and_neutron_pop_vector_theta_method(
+                timestep, 
+                reactivity_at_start, 
+                reactivity_at_end, 
+                neutron_generation_time, 
+                background_source_rate, 
+                theta).unwrap();
+
+        for (theta_entry, dense_entry) in theta_method_solution.iter().zip(dense_solution.iter()) {
+            assert_relative_eq!(
+                theta_entry.get::<per_cubic_meter>(), 
+                *dense_entry, 
+                max_relative = 1e-12);
+        }
+    }
+
+    // theta outside [0,1] is refused
+    let mut prke = reference_prke;
+    assert!(prke.solve_next_timestep_precursor_concentration_and_neutron_pop_vector_theta_method(
+        timestep, 
+        reactivity_at_start, 
+        reactivity_at_end, 
+        neutron_generation_time, 
+        background_source_rate, 
+        1.5).is_err());
+}
+
+#[test]
+pub fn crank_nicolson_is_second_order_for_reactivity_ramp(){
+    // reactivity ramps linearly from 0 to 0.002 over 1 s, halving
+    // the timestep should cut the error by about 4 for 
+    // Crank-Nicolson (theta = 0.5) and about 2 for backward Euler
+    // (theta = 1)
+
+    use uom::si::time::{microsecond, second};
+
+    let neutron_generation_time = Time::new::<microsecond>(100.0);
+    let final_reactivity = 0.002;
+    let end_time = 1.0;
+
+    let initial_prke = SixGroupPRKE::new_at_equilibrium(
+        FissioningNuclideType::U235, 
+        VolumetricNumberDensity::new::<per_cubic_meter>(1.0), 
+        neutron_generation_time);
+
+    let neutron_density_at_end = |number_of_steps: usize, theta: f64| -> f64 {
+        let mut prke = initial_prke;
+        let dt = end_time/number_of_steps as f64;
+
+        for step in 0..number_of_steps {
+            let reactivity_at_start = final_reactivity * (step as f64 * dt)/end_time;
+            let reactivity_at_end = final_reactivity * ((step + 1) as f64 * dt)/end_time;
+
+            prke.solve_next_timestep_precursor_concentration_and_neutron_pop_vector_theta_method(
+                Time::new::<second>(dt), 
+                Ratio::new::<ratio>(reactivity_at_start), 
+                Ratio::new::<ratio>(reactivity_at_end), 
+                neutron_generation_time, 
+                VolumetricNumberRate::ZERO, 
+                theta).unwrap();
+        }
+
+        prke.get_current_neutron_population_density().get::<per_cubic_meter>()
+    };
+
+    let reference_neutron_density = neutron_density_at_end(20000, 0.5);
+
+    let error_ratio = |theta: f64| -> f64 {
+        let coarse_error = (neutron_density_at_end(50, theta) - reference_neutron_density).abs();
+        let fine_error = (neutron_density_at_end(100, theta) - reference_neutron_density).abs();
+        coarse_error/fine_error
+    };
+
+    let crank_nicolson_error_ratio = error_ratio(0.5);
+    let backward_euler_error_ratio = error_ratio(1.0);
+
+    assert!((crank_nicolson_error_ratio - 4.0).abs() < 0.3, 
+        "Crank-Nicolson error ratio {}", crank_nicolson_error_ratio);
+    assert!((backward_euler_error_ratio - 2.0).abs() < 0.3, 
+        "backward Euler error ratio {}", backward_euler_error_ratio);
+
+    // Crank-Nicolson is also much more accurate at the same step
+    let crank_nicolson_error = (neutron_density_at_end(50, 0.5) - reference_neutron_density).abs();
+    let backward_euler_error = (neutron_density_at_end(50, 1.0) - reference_neutron_density).abs();
+    assert!(10.0 * crank_nicolson_error < backward_euler_error);
+}
+
+#[test]
+pub fn prompt_jump_solver_follows_inhour_solution_with_long_steps(){
+    // after the prompt transient has died away, the prompt jump 
+    // solver should follow the inhour solution using steps far
+    // longer than the prompt neutron lifetime
+
+    use uom::si::time::{microsecond, second};
+    use approx::assert_relative_eq;
+
+    let neutron_generation_time = Time::new::<microsecond>(10.0);
+
+    let initial_prke = SixGroupPRKE::new_at_equilibrium(
+        FissioningNuclideType::U235, 
+        VolumetricNumberDensity::new::<per_cubic_meter>(1.0), 
+        neutron_generation_time);
+
+    let total_delayed_fraction = initial_prke.get_total_delayed_fraction();
+
+    // at equilibrium, the prompt jump is beta/(beta - rho)
+    let reactivity = Ratio::new::<ratio>(0.002);
+    let prompt_jump_neutron_density = initial_prke
+        .get_prompt_jump_neutron_population_density(
+            reactivity, neutron_generation_time, VolumetricNumberRate::ZERO)
+        .unwrap().get::<per_cubic_meter>();
+
+    assert_relative_eq!(prompt_jump_neutron_density, 
+        (total_delayed_fraction/(total_delayed_fraction - reactivity)).get::<ratio>(), 
+        max_relative = 1e-12);
+
+    // positive and negative steps, 0.05 s timesteps for 20 s
+    for (reactivity_value, max_relative_error) in [(0.002, 0.01), (-0.005, 0.01)] {
+
+        let reactivity = Ratio::new::<ratio>(reactivity_value);
+        let timestep = Time::new::<second>(0.05);
+        let number_of_steps = 400;
+
+        let mut prke = initial_prke;
+
+        for _ in 0..number_of_steps {
+            prke.solve_next_timestep_precursor_concentration_and_neutron_pop_vector_prompt_jump(
+                timestep, 
+                reactivity, 
+                neutron_generation_time, 
+                VolumetricNumberRate::ZERO).unwrap();
+        }
+
+        let reference_neutron_density = initial_prke
+            .solve_inhour_equation(reactivity, neutron_generation_time).unwrap()
+            .get_neutron_population_ratio(number_of_steps as f64 * timestep)
+            .get::<ratio>();
+
+        assert_relative_eq!(
+            prke.get_current_neutron_population_density().get::<per_cubic_meter>(),
+            reference_neutron_density,
+            max_relative = max_relative_error);
+    }
+
+    // prompt critical and above is refused
+    let mut prke = initial_prke;
+    assert!(prke.solve_next_timestep_precursor_concentration_and_neutron_pop_vector_prompt_jump(
+        Time::new::<second>(0.05), 
+        total_delayed_fraction, 
+        neutron_generation_time, 
+        VolumetricNumberRate::ZERO).is_err());
+    assert!(initial_prke.get_prompt_jump_neutron_population_density(
+        1.5 * total_delayed_fraction, 
+        neutron_generation_time, 
+        VolumetricNumberRate::ZERO).is_err());
+}
+
+#[test]
+pub fn prompt_jump_solver_source_equilibrium_with_hour_long_steps(){
+    // a subcritical core with a source started at source 
+    // equilibrium should stay put even with very long steps
+
+    use uom::si::time::{microsecond, second};
+    use uom::si::volumetric_number_rate::per_cubic_meter_second;
+    use approx::assert_relative_eq;
+
+    let neutron_generation_time = Time::new::<microsecond>(50.0);
+    let reactivity = Ratio::new::<ratio>(-0.01);
+    let background_source_rate = VolumetricNumberRate::new::<per_cubic_meter_second>(1.0e5);
+
+    let mut prke = SixGroupPRKE::new_at_source_equilibrium(
+        FissioningNuclideType::U235, 
+        reactivity, 
+        neutron_generation_time, 
+        background_source_rate).unwrap();
+
+    let equilibrium_neutron_density = prke
+        .get_current_neutron_population_density().get::<per_cubic_meter>();
+
+    for _ in 0..10 {
+        prke.solve_next_timestep_precursor_concentration_and_neutron_pop_vector_prompt_jump(
+            Time::new::<second>(3600.0), 
+            reactivity, 
+            neutron_generation_time, 
+            background_source_rate).unwrap();
+    }
+
+    assert_relative_eq!(
+        prke.get_current_neutron_population_density().get::<per_cubic_meter>(),
+        equilibrium_neutron_density,
+        max_relative = 1e-10);
+}
+
+#[test]
+pub fn transient_runner_step_insertion_matches_inhour_solution(){
+    // a 0.003 step run through the transient runner should give 
+    // the inhour solution at each output time, and the period 
+    // should settle to the stable period
+
+    use uom::si::time::{microsecond, second};
+    use approx::assert_relative_eq;
+    use crate::reactivity_profile::ReactivityProfile;
+    use crate::zero_power_prke::transient_runner::*;
+
+    let neutron_generation_time = Time::new::<microsecond>(20.0);
+    let reactivity = Ratio::new::<ratio>(0.003);
+
+    let initial_prke = SixGroupPRKE::new_at_equilibrium(
+        FissioningNuclideType::U235, 
+        VolumetricNumberDensity::new::<per_cubic_meter>(1.0), 
+        neutron_generation_time);
+
+    let mut prke = initial_prke;
+
+    let stepping_policy = SteppingPolicy {
+        timestep: Time::new::<second>(0.01),
+        solver_method: PRKESolverMethod::MatrixExponential,
+    };
+
+    let transient_runner = TransientRunner::new(
+        neutron_generation_time, 
+        Time::new::<second>(10.0), 
+        stepping_policy, 
+        Time::new::<second>(1.0));
+
+    let history = transient_runner.run(
+        &mut prke, 
+        &mut ReactivityProfile::Constant(reactivity)).unwrap();
+
+    assert_eq!(history.stop_reason, TransientStopReason::EndTimeReached);
+
+    // t = 0, 1, 2 ... 10 s
+    assert_eq!(history.snapshot_list.len(), 11);
+
+    let inhour_solution = initial_prke
+        .solve_inhour_equation(reactivity, neutron_generation_time).unwrap();
+
+    for snapshot in history.snapshot_list.iter() {
+        assert_relative_eq!(
+            snapshot.neutron_population_number_density.get::<per_cubic_meter>(),
+            inhour_solution.get_neutron_population_ratio(snapshot.time).get::<ratio>(),
+            max_relative = 1e-9);
+        assert_relative_eq!(snapshot.reactivity.get::<ratio>(), 0.003);
+    }
+
+    // the runner leaves the PRKE at the final state
+    assert_relative_eq!(
+        prke.get_current_neutron_population_density().get::<per_cubic_meter>(),
+        history.get_final_snapshot().unwrap().neutron_population_number_density.get::<per_cubic_meter>());
+    assert_relative_eq!(history.get_final_snapshot().unwrap().time.get::<second>(), 10.0);
+
+    // the period over the last step, the higher inhour modes have 
+    // not quite died out at 10 s so this is not yet the stable period
+    let neutron_density_ratio_over_last_step: f64 = 
+        inhour_solution.get_neutron_population_ratio(Time::new::<second>(10.0)).get::<ratio>()
+        /inhour_solution.get_neutron_population_ratio(Time::new::<second>(9.99)).get::<ratio>();
+
+    assert!(history.snapshot_list[0].period.get::<second>().is_infinite());
+    assert_relative_eq!(
+        history.get_final_snapshot().unwrap().period.get::<second>(),
+        0.01/neutron_density_ratio_over_last_step.ln(),
+        max_relative = 1e-6);
+    assert!(history.get_final_snapshot().unwrap().period 
+        > 0.95 * inhour_solution.get_stable_period());
+
+    // a transient that ends before it starts is an error rather
+    // than an empty history
+    let mut backwards_transient_runner = transient_runner.clone();
+    backwards_transient_runner.start_time = Time::new::<second>(10.0);
+    backwards_transient_runner.end_time = Time::new::<second>(10.0);
+
+    assert!(backwards_transient_runner.run(
+        &mut prke, 
+        &mut ReactivityProfile::Constant(reactivity)).is_err());
+}
+
+#[test]
+pub fn transient_runner_stops_on_neutron_population_threshold(){
+    // a prompt critical step with a trip at 1000 times the initial 
+    // population, the transient should stop soon after crossing 
+    // it, well before the end time
+
+    use uom::si::time::{microsecond, second};
+    use crate::reactivity_profile::ReactivityProfile;
+    use crate::time_stepping::openfoam_adaptive_solver::AdaptiveSolver;
+    use crate::time_stepping::openfoam_ode_solver::ODESolverMethod;
+    use crate::zero_power_prke::transient_runner::*;
+
+    let neutron_generation_time = Time::new::<microsecond>(20.0);
+    let trip_threshold = VolumetricNumberDensity::new::<per_cubic_meter>(1000.0);
+
+    let mut prke = SixGroupPRKE::new_at_equilibrium(
+        FissioningNuclideType::U235, 
+        VolumetricNumberDensity::new::<per_cubic_meter>(1.0), 
+        neutron_generation_time);
+
+    let stepping_policy = SteppingPolicy {
+        timestep: Time::new::<second>(1e-3),
+        solver_method: PRKESolverMethod::ODESolver(
+            ODESolverMethod::Rodas34, AdaptiveSolver::new(1e-12, 1e-8)),
+    };
+
+    let mut transient_runner = TransientRunner::new(
+        neutron_generation_time, 
+        Time::new::<second>(10.0), 
+        stepping_policy, 
+        Time::new::<second>(0.1));
+
+    transient_runner.stop_condition_list.push(
+        StopCondition::NeutronPopulationAbove(trip_threshold));
+
+    let reactivity_profile = ReactivityProfile::Step {
+        step_time: Time::ZERO,
+        reactivity_before: Ratio::ZERO,
+        reactivity_after: prke.get_total_delayed_fraction(),
+    };
+
+    let history = transient_runner.run(
+        &mut prke, 
+        &mut reactivity_profile.clone()).unwrap();
+
+    assert_eq!(history.stop_reason, 
+        TransientStopReason::StopConditionMet(
+            StopCondition::NeutronPopulationAbove(trip_threshold)));
+
+    let final_snapshot = history.get_final_snapshot().unwrap();
+
+    assert!(final_snapshot.neutron_population_number_density > trip_threshold);
+    assert!(final_snapshot.time < Time::new::<second>(1.0));
+
+    // the step before the trip was still below the threshold, 
+    // so the overshoot is at most one step of growth
+    let growth_per_step = (stepping_policy.timestep/final_snapshot.period)
+        .get::<ratio>().exp();
+    assert!(final_snapshot.neutron_population_number_density 
+        <= trip_threshold * growth_per_step);
+
+    // a period trip at 1 s stops the same transient even earlier
+    transient_runner.stop_condition_list = vec![
+        StopCondition::PositivePeriodShorterThan(Time::new::<second>(1.0))];
+
+    let mut prke = SixGroupPRKE::new_at_equilibrium(
+        FissioningNuclideType::U235, 
+        VolumetricNumberDensity::new::<per_cubic_meter>(1.0), 
+        neutron_generation_time);
+
+    let period_trip_history = transient_runner.run(
+        &mut prke, 
+        &mut reactivity_profile.clone()).unwrap();
+
+    assert!(period_trip_history.get_final_snapshot().unwrap().time < final_snapshot.time);
+}
+
+#[test]
+pub fn transient_runner_with_fuel_temperature_feedback_closure(){
+    // a closure holding a fuel temperature is used as the 
+    // reactivity model, with a negative temperature coefficient 
+    // the power rises and then levels off at a new steady state
+    // where the feedback cancels the inserted reactivity
+
+    use uom::si::time::{microsecond, second};
+    use approx::assert_relative_eq;
+    use crate::zero_power_prke::transient_runner::*;
+
+    let neutron_generation_time = Time::new::<microsecond>(20.0);
+    let inserted_reactivity: f64 = 0.001;
+
+    let mut prke = SixGroupPRKE::new_at_equilibrium(
+        FissioningNuclideType::U235, 
+        VolumetricNumberDensity::new::<per_cubic_meter>(1.0), 
+        neutron_generation_time);
+
+    // fuel temperature rise above the initial steady state, in 
+    // kelvin, heated by the extra power and cooled with a 
+    // 5 s time constant
+    let mut fuel_temperature_rise: f64 = 0.0;
+    let heating_rate_per_unit_density: f64 = 2.0;
+    let cooling_time_constant: f64 = 5.0;
+    let temperature_coefficient: f64 = -1.0e-4;
+
+    let mut fuel_temperature_feedback = 
+        |_time: Time, timestep: Time, neutron_density: VolumetricNumberDensity| -> Ratio {
+
+            let dt = timestep.get::<second>();
+            let n = neutron_density.get::<per_cubic_meter>();
+
+            // backward Euler for the temperature
+            fuel_temperature_rise = (fuel_temperature_rise 
+                + dt * heating_rate_per_unit_density * (n - 1.0))
+                /(1.0 + dt/cooling_time_constant);
+
+            Ratio::new::<ratio>(inserted_reactivity 
+                + temperature_coefficient * fuel_temperature_rise)
+    };
+
+    let stepping_policy = SteppingPolicy {
+        timestep: Time::new::<second>(0.1),
+        solver_method: PRKESolverMethod::Implicit,
+    };
+
+    let transient_runner = TransientRunner::new(
+        neutron_generation_time, 
+        Time::new::<second>(1000.0), 
+        stepping_policy, 
+        Time::new::<second>(10.0));
+
+    let history = transient_runner.run(
+        &mut prke, 
+        &mut fuel_temperature_feedback).unwrap();
+
+    let neutron_density_list: Vec<f64> = history.get_neutron_population_list()
+        .iter()
+        .map(|n| n.get::<per_cubic_meter>())
+        .collect();
+
+    // power rose above the initial level
+    assert!(neutron_density_list[1] > 1.0);
+
+    // at the new steady state, reactivity is back to zero and 
+    // the temperature rise balances cooling:
+    // 
+    // heating (n - 1) = temperature rise/time constant
+    // temperature rise = inserted reactivity/|alpha| = 10 K
+    // n = 1 + 10/(2 * 5) = 2
+    let final_snapshot = history.get_final_snapshot().unwrap();
+
+    assert_relative_eq!(final_snapshot.reactivity.get::<ratio>(), 0.0, epsilon = 1e-6);
+    assert_relative_eq!(
+        final_snapshot.neutron_population_number_density.get::<per_cubic_meter>(),
+        2.0,
+        max_relative = 1e-3);
+    assert!(final_snapshot.period.get::<second>().abs() > 1.0e4);
+}
+
+#[test]
+pub fn solvers_accept_reactivity_in_dollars(){
+    // half a dollar given as a Reactivity should step exactly as 
+    // the same reactivity given as a Ratio
+
+    use uom::si::time::{microsecond, second};
+    use approx::assert_relative_eq;
+    use crate::reactivity::Reactivity;
+
+    let neutron_generation_time = Time::new::<microsecond>(20.0);
+    let timestep = Time::new::<second>(0.01);
+
+    let initial_prke = SixGroupPRKE::new_at_equilibrium(
+        FissioningNuclideType::U235, 
+        VolumetricNumberDensity::new::<per_cubic_meter>(1.0), 
+        neutron_generation_time);
+
+    let beta_eff = initial_prke.get_total_delayed_fraction();
+    let half_dollar = Reactivity::new_from_dollars(0.5, beta_eff);
+    let half_dollar_ratio: Ratio = 0.5 * beta_eff;
+
+    let mut prke_reactivity = initial_prke;
+    let mut prke_ratio = initial_prke;
+
+    for _ in 0..100 {
+        prke_reactivity.solve_next_timestep_precursor_concentration_and_neutron_pop_vector_implicit(
+            timestep, 
+            half_dollar, 
+            neutron_generation_time, 
+            VolumetricNumberRate::ZERO).unwrap();
+        prke_ratio.solve_next_timestep_precursor_concentration_and_neutron_pop_vector_implicit(
+            timestep, 
+            half_dollar_ratio, 
+            neutron_generation_time, 
+            VolumetricNumberRate::ZERO).unwrap();
+    }
+
+    assert_eq!(
+        prke_reactivity.get_current_neutron_population_density(),
+        prke_ratio.get_current_neutron_population_density());
+
+    // the stable period of half a dollar, and back again from 
+    // the period
+    let inhour_solution = initial_prke
+        .solve_inhour_equation(half_dollar, neutron_generation_time).unwrap();
+
+    let reactivity_from_period = Reactivity::new(
+        initial_prke.get_reactivity_from_stable_period(
+            inhour_solution.get_stable_period(), 
+            neutron_generation_time));
+
+    assert_relative_eq!(
+        reactivity_from_period.get_cents(beta_eff), 
+        50.0, 
+        max_relative = 1e-9);
+
+    // keff for the subcritical source equilibrium, 
+    // -1000 pcm
+    let keff = Ratio::new::<ratio>(1.0/1.01);
+    let subcritical_reactivity = Reactivity::new_from_keff(keff).unwrap();
+
+    assert_relative_eq!(subcritical_reactivity.get_pcm(), -1000.0, max_relative = 1e-12);
+    assert!(SixGroupPRKE::new_at_source_equilibrium(
+        FissioningNuclideType::U235, 
+        subcritical_reactivity, 
+        neutron_generation_time, 
+        VolumetricNumberRate::new::<uom::si::volumetric_number_rate::per_cubic_meter_second>(1.0e5))
+        .is_ok());
+}
+
+#[test]
+pub fn reactor_power_model_converts_neutron_density_and_power(){
+    // P = n v Sigma_f V E_f, worked out by hand for a thermal 
+    // reactor (2200 m/s, 200 MeV per fission)
+
+    use uom::si::time::microsecond;
+    use uom::si::power::{megawatt, watt};
+    use uom::si::linear_number_density::per_meter;
+    use uom::si::volume::cubic_meter;
+    use uom::si::volumetric_number_rate::per_cubic_meter_second;
+    use approx::assert_relative_eq;
+    use crate::zero_power_prke::reactor_power::ReactorPowerModel;
+
+    let reactor_power_model = ReactorPowerModel::new_thermal_reactor(
+        LinearNumberDensity::new::<per_meter>(1.0), 
+        Volume::new::<cubic_meter>(10.0)).unwrap();
+
+    // watts per neutron per cubic meter
+    let energy_per_fission_joules = 200.0e6 * 1.602176634e-19;
+    let power_per_unit_neutron_density = 2200.0 * 1.0 * 10.0 * energy_per_fission_joules;
+
+    assert_relative_eq!(
+        reactor_power_model.get_power(
+            VolumetricNumberDensity::new::<per_cubic_meter>(1.0)).get::<watt>(),
+        power_per_unit_neutron_density,
+        max_relative = 1e-12);
+
+    // fission rate density is n v Sigma_f, 2200 fissions per m3 per s
+    // for one neutron per m3
+    assert_relative_eq!(
+        reactor_power_model.get_fission_rate_density(
+            VolumetricNumberDensity::new::<per_cubic_meter>(1.0))
+            .get::<per_cubic_meter_second>(),
+        2200.0,
+        max_relative = 1e-12);
+
+    // and back
+    let reactor_power = Power::new::<megawatt>(10.0);
+    let neutron_population_number_density = 
+        reactor_power_model.get_neutron_population_number_density(reactor_power);
+
+    assert_relative_eq!(
+        neutron_population_number_density.get::<per_cubic_meter>(),
+        10.0e6/power_per_unit_neutron_density,
+        max_relative = 1e-12);
+
+    // the PRKE initialised at 10 MW reads back 10 MW, and matches 
+    // a power model built from the quantities given one by one
+    let neutron_generation_time = Time::new::<microsecond>(231.0);
+
+    let prke = SixGroupPRKE::new_at_equilibrium_from_reactor_power(
+        FissioningNuclideType::U235, 
+        reactor_power, 
+        neutron_generation_time, 
+        &reactor_power_model);
+
+    let prke_from_power = SixGroupPRKE::new_at_equilibrium_from_reactor_power(
+        FissioningNuclideType::U235, 
+        reactor_power, 
+        neutron_generation_time, 
+        &ReactorPowerModel::new(
+            reactor_power_model.energy_per_fission, 
+            reactor_power_model.macroscopic_fission_xs, 
+            reactor_power_model.neutron_speed, 
+            reactor_power_model.reactor_volume).unwrap());
+
+    assert_relative_eq!(
+        prke.get_reactor_power(&reactor_power_model).get::<megawatt>(), 
+        10.0, 
+        max_relative = 1e-12);
+    assert_eq!(
+        prke.get_current_neutron_population_density(), 
+        prke_from_power.get_current_neutron_population_density());
+
+    // non positive quantities are refused
+    assert!(ReactorPowerModel::new_thermal_reactor(
+        LinearNumberDensity::new::<per_meter>(-1.0), 
+        Volume::new::<cubic_meter>(10.0)).is_err());
+}
+
+#[test]
+pub fn transient_runner_in_megawatts_with_power_trip(){
+    // start at 10 MW, insert 0.003, and trip at 20 MW, power is 
+    // read out from the history in MW
+    //
+    // for this step, the prompt jump takes n/n0 to about
+    // beta/(beta - rho) = 1.86 almost immediately, so doubling
+    // (and the trip) comes well before 1 s, where n/n0 = 2.2098
+    // (Kinard and Allen)
+
+    use uom::si::time::{microsecond, second};
+    use uom::si::power::megawatt;
+    use uom::si::linear_number_density::per_meter;
+    use uom::si::volume::cubic_meter;
+    use approx::assert_relative_eq;
+    use crate::reactivity_profile::ReactivityProfile;
+    use crate::zero_power_prke::reactor_power::ReactorPowerModel;
+    use crate::zero_power_prke::transient_runner::*;
+
+    let neutron_generation_time = Time::new::<microsecond>(20.0);
+
+    let reactor_power_model = ReactorPowerModel::new_thermal_reactor(
+        LinearNumberDensity::new::<per_meter>(1.0), 
+        Volume::new::<cubic_meter>(10.0)).unwrap();
+
+    let mut prke = SixGroupPRKE::new_at_equilibrium_from_reactor_power(
+        FissioningNuclideType::U235, 
+        Power::new::<megawatt>(10.0), 
+        neutron_generation_time, 
+        &reactor_power_model);
+
+    let stepping_policy = SteppingPolicy {
+        timestep: Time::new::<second>(0.01),
+        solver_method: PRKESolverMethod::MatrixExponential,
+    };
+
+    let mut transient_runner = TransientRunner::new(
+        neutron_generation_time, 
+        Time::new::<second>(60.0), 
+        stepping_policy, 
+        Time::new::<second>(0.1));
+
+    transient_runner.stop_condition_list.push(
+        reactor_power_model.get_power_above_stop_condition(Power::new::<megawatt>(20.0)));
+
+    let history = transient_runner.run(
+        &mut prke, 
+        &mut ReactivityProfile::Constant(Ratio::new::<ratio>(0.003))).unwrap();
+
+    let power_list_megawatts: Vec<f64> = history.get_power_list(&reactor_power_model)
+        .iter()
+        .map(|power| power.get::<megawatt>())
+        .collect();
+
+    assert_relative_eq!(power_list_megawatts[0], 10.0, max_relative = 1e-12);
+
+    let trip_power = power_list_megawatts[power_list_megawatts.len() - 1];
+    let trip_time = history.get_final_snapshot().unwrap().time.get::<second>();
+
+    assert!(matches!(history.stop_reason, TransientStopReason::StopConditionMet(_)));
+    assert!(trip_power > 20.0 && trip_power < 20.5);
+    assert!(trip_time > 0.1 && trip_time < 0.5);
+    assert_relative_eq!(
+        prke.get_reactor_power(&reactor_power_model).get::<megawatt>(), 
+        trip_power);
+}
+
+#[test]
+pub fn approach_to_criticality_with_inverse_count_rate(){
+    // a source driven core is brought towards critical by
+    // withdrawing a rod with an S shaped worth curve, at each
+    // rod position the PRKE is run until the neutron population
+    // settles, and this stands in for the detector count rate
+    //
+    // the core is critical with the rod withdrawn 60 cm, the 1/M
+    // extrapolation should close in on this as the rod comes out
+
+    use uom::si::time::{microsecond, second};
+    use uom::si::frequency::hertz;
+    use uom::si::length::{centimeter, meter};
+    use uom::si::volumetric_number_rate::per_cubic_meter_second;
+    use crate::control_rod_feedback::obtain_rod_worth_cylinder;
+    use crate::reactivity_profile::ReactivityProfile;
+    use crate::subcritical_multiplication::predict_critical_rod_position;
+    use crate::zero_power_prke::transient_runner::*;
+
+    let neutron_generation_time = Time::new::<microsecond>(100.0);
+    let background_source_rate =
+        VolumetricNumberRate::new::<per_cubic_meter_second>(1.0e6);
+    let cylinder_height = Length::new::<meter>(1.0);
+    let rod_worth = Ratio::new::<ratio>(0.02);
+    let critical_rod_withdrawal = Length::new::<centimeter>(60.0);
+
+    // reactivity is zero at the critical withdrawal
+    let excess_reactivity = obtain_rod_worth_cylinder(
+        cylinder_height,
+        cylinder_height - critical_rod_withdrawal,
+        rod_worth).unwrap();
+
+    let get_reactivity = |rod_withdrawal: Length| -> Ratio {
+        excess_reactivity - obtain_rod_worth_cylinder(
+            cylinder_height,
+            cylinder_height - rod_withdrawal,
+            rod_worth).unwrap()
+    };
+
+    let rod_withdrawal_list: Vec<Length> = [20.0, 30.0, 40.0, 50.0, 55.0]
+        .iter()
+        .map(|withdrawal_cm| Length::new::<centimeter>(*withdrawal_cm))
+        .collect();
+
+    let mut prke = SixGroupPRKE::new_at_source_equilibrium(
+        FissioningNuclideType::U235,
+        get_reactivity(rod_withdrawal_list[0]),
+        neutron_generation_time,
+        background_source_rate).unwrap();
+
+    let stepping_policy = SteppingPolicy {
+        timestep: Time::new::<second>(1.0),
+        solver_method: PRKESolverMethod::Implicit,
+    };
+
+    let mut transient_runner = TransientRunner::new(
+        neutron_generation_time,
+        Time::new::<second>(1500.0),
+        stepping_policy,
+        Time::new::<second>(100.0));
+    transient_runner.background_source_rate = background_source_rate;
+
+    let mut count_rate_list: Vec<(Length, Frequency)> = vec![];
+    let mut prediction_error_list: Vec<f64> = vec![];
+
+    for rod_withdrawal in rod_withdrawal_list.iter() {
+
+        transient_runner.run(
+            &mut prke,
+            &mut ReactivityProfile::Constant(get_reactivity(*rod_withdrawal))).unwrap();
+
+        // one count per second for every 1e6 neutrons per m3
+        let count_rate = Frequency::new::<hertz>(
+            prke.get_current_neutron_population_density().get::<per_cubic_meter>()
+            * 1.0e-6);
+
+        count_rate_list.push((*rod_withdrawal, count_rate));
+
+        if count_rate_list.len() >= 2 {
+            let predicted_rod_withdrawal =
+                predict_critical_rod_position(&count_rate_list).unwrap();
+
+            prediction_error_list.push(
+                (predicted_rod_withdrawal - critical_rod_withdrawal)
+                .get::<centimeter>().abs());
+        }
+    }
+
+    // count rate goes up as the rod comes out
+    assert!(count_rate_list.windows(2).all(|pair| pair[1].1 > pair[0].1));
+
+    // the prediction improves, and is within a centimetre at the end
+    let number_of_predictions = prediction_error_list.len();
+    assert!(prediction_error_list[number_of_predictions - 1]
+        < prediction_error_list[0]);
+    assert!(prediction_error_list[number_of_predictions - 1] < 1.0);
+}
+
+#[test]
+pub fn stochastic_prke_is_repeatable_for_a_given_seed(){
+    // source range startup, a few tens of neutrons in the core
+
+    use uom::si::time::{microsecond, second};
+    use uom::si::volume::cubic_meter;
+    use uom::si::volumetric_number_rate::per_cubic_meter_second;
+    use crate::zero_power_prke::stochastic_prke::*;
+
+    let neutron_generation_time = Time::new::<microsecond>(100.0);
+    let reactivity = Ratio::new::<ratio>(-0.02);
+    let background_source_rate =
+        VolumetricNumberRate::new::<per_cubic_meter_second>(100.0);
+
+    let prke = SixGroupPRKE::new_at_source_equilibrium(
+        FissioningNuclideType::U235,
+        reactivity,
+        neutron_generation_time,
+        background_source_rate).unwrap();
+
+    let get_counts = |seed: u64| -> Vec<u64> {
+        let mut stochastic_prke = StochasticPRKE::new_from_prke(
+            &prke,
+            Volume::new::<cubic_meter>(1.0),
+            Ratio::new::<ratio>(0.1),
+            seed).unwrap();
+
+        stochastic_prke.get_counts_per_interval(
+            Time::new::<second>(1.0),
+            10,
+            reactivity,
+            neutron_generation_time,
+            background_source_rate,
+            StochasticMethod::Gillespie).unwrap()
+    };
+
+    assert_eq!(get_counts(42), get_counts(42));
+    assert_ne!(get_counts(42), get_counts(43));
+}
+
+#[test]
+pub fn stochastic_prke_source_range_count_rate(){
+    // subcritical with a source, n = - S Lambda V/rho neutrons on
+    // average, each is removed at a rate 1/Lambda, so the mean
+    // count rate is - epsilon S V/rho = 500 counts per second
+    //
+    // each source neutron starts a chain of births, so counts come
+    // in bunches and spread out more than Poisson counts would
+
+    use uom::si::time::{microsecond, second};
+    use uom::si::volume::cubic_meter;
+    use uom::si::volumetric_number_rate::per_cubic_meter_second;
+    use approx::assert_relative_eq;
+    use crate::zero_power_prke::stochastic_prke::*;
+
+    let neutron_generation_time = Time::new::<microsecond>(100.0);
+    let reactivity = Ratio::new::<ratio>(-0.1);
+    let background_source_rate =
+        VolumetricNumberRate::new::<per_cubic_meter_second>(100.0);
+
+    let prke = SixGroupPRKE::new_at_source_equilibrium(
+        FissioningNuclideType::U235,
+        reactivity,
+        neutron_generation_time,
+        background_source_rate).unwrap();
+
+    let mut stochastic_prke = StochasticPRKE::new_from_prke(
+        &prke,
+        Volume::new::<cubic_meter>(1.0),
+        Ratio::new::<ratio>(0.5),
+        7).unwrap();
+
+    let count_list = stochastic_prke.get_counts_per_interval(
+        Time::new::<second>(1.0),
+        400,
+        reactivity,
+        neutron_generation_time,
+        background_source_rate,
+        StochasticMethod::Gillespie).unwrap();
+
+    let number_of_intervals = count_list.len() as f64;
+    let mean_counts: f64 = count_list.iter()
+        .map(|counts| *counts as f64).sum::<f64>()/number_of_intervals;
+    let variance_of_counts: f64 = count_list.iter()
+        .map(|counts| (*counts as f64 - mean_counts).powi(2))
+        .sum::<f64>()/(number_of_intervals - 1.0);
+
+    assert_relative_eq!(mean_counts, 500.0, max_relative = 0.05);
+    assert!(variance_of_counts/mean_counts > 1.0);
+}
+
+#[test]
+pub fn stochastic_prke_converges_to_deterministic_at_high_population(){
+    // 1e8 neutrons, step insertion of 0.003, the tau leaping
+    // solution should lie on the deterministic one, since the
+    // relative fluctuations go as 1/sqrt(N)
+
+    use uom::si::time::{microsecond, millisecond, second};
+    use uom::si::volume::cubic_meter;
+    use approx::assert_relative_eq;
+    use crate::zero_power_prke::multi_group_precursor_prke::MatrixExponentialCache;
+    use crate::zero_power_prke::stochastic_prke::*;
+
+    let neutron_generation_time = Time::new::<microsecond>(100.0);
+    let reactivity = Ratio::new::<ratio>(0.003);
+    let background_source_rate = VolumetricNumberRate::ZERO;
+
+    let mut prke = SixGroupPRKE::default();
+    prke.set_equilibrium_from_neutron_population_density(
+        VolumetricNumberDensity::new::<per_cubic_meter>(1.0e8),
+        neutron_generation_time);
+
+    let mut stochastic_prke = StochasticPRKE::new_from_prke(
+        &prke,
+        Volume::new::<cubic_meter>(1.0),
+        Ratio::new::<ratio>(1.0e-3),
+        2024).unwrap();
+
+    let mut matrix_exponential_cache = MatrixExponentialCache::new();
+    let timestep = Time::new::<second>(0.5);
+
+    for _ in 0..4 {
+        stochastic_prke.advance(
+            timestep,
+            reactivity,
+            neutron_generation_time,
+            background_source_rate,
+            StochasticMethod::TauLeaping(Time::new::<millisecond>(1.0))).unwrap();
+
+        prke.solve_next_timestep_precursor_concentration_and_neutron_pop_vector_matrix_exponential(
+            timestep,
+            reactivity,
+            neutron_generation_time,
+            background_source_rate,
+            &mut matrix_exponential_cache).unwrap();
+
+        assert_relative_eq!(
+            stochastic_prke.get_current_neutron_population_density()
+            .get::<per_cubic_meter>(),
+            prke.get_current_neutron_population_density()
+            .get::<per_cubic_meter>(),
+            max_relative = 0.01);
+    }
+
+    // precursors follow as well
+    let stochastic_precursor_array =
+        stochastic_prke.get_current_precursor_concentration_array();
+    let precursor_array = prke.get_current_precursor_concentration_array();
+
+    for (stochastic_precursor, precursor) in
+        stochastic_precursor_array.iter().zip(precursor_array.iter()) {
+            assert_relative_eq!(
+                stochastic_precursor.get::<per_cubic_meter>(),
+                precursor.get::<per_cubic_meter>(),
+                max_relative = 0.01);
+    }
+}
+
+#[test]
+pub fn stochastic_prke_tau_leaping_mean_is_unbiased_at_low_population(){
+    // 200 neutrons, deeply subcritical with no source, so the
+    // population drops to a few neutrons within a millisecond,
+    // and leaps of 1 ms where each would remove several times
+    // the population. The mean over many runs should still follow
+    // the deterministic PRKE, which it did not when the neutron
+    // population was clamped at zero instead
+
+    use uom::si::time::{microsecond, millisecond};
+    use uom::si::volume::cubic_meter;
+    use approx::assert_relative_eq;
+    use crate::zero_power_prke::multi_group_precursor_prke::MatrixExponentialCache;
+    use crate::zero_power_prke::stochastic_prke::*;
+
+    let neutron_generation_time = Time::new::<microsecond>(100.0);
+    let reactivity = Ratio::new::<ratio>(-0.5);
+    let background_source_rate = VolumetricNumberRate::ZERO;
+    let timestep = Time::new::<millisecond>(20.0);
+    let number_of_runs = 400;
+
+    let mut prke = SixGroupPRKE::default();
+    prke.set_equilibrium_from_neutron_population_density(
+        VolumetricNumberDensity::new::<per_cubic_meter>(200.0),
+        neutron_generation_time);
+
+    let mut total_neutron_population: u64 = 0;
+
+    for seed in 0..number_of_runs {
+        let mut stochastic_prke = StochasticPRKE::new_from_prke(
+            &prke,
+            Volume::new::<cubic_meter>(1.0),
+            Ratio::new::<ratio>(1.0e-3),
+            seed).unwrap();
+
+        stochastic_prke.advance(
+            timestep,
+            reactivity,
+            neutron_generation_time,
+            background_source_rate,
+            StochasticMethod::TauLeaping(Time::new::<millisecond>(1.0))).unwrap();
+
+        total_neutron_population += stochastic_prke.neutron_population;
+    }
+
+    // start the deterministic PRKE from the same rounded populations
+    let stochastic_prke = StochasticPRKE::new_from_prke(
+        &prke,
+        Volume::new::<cubic_meter>(1.0),
+        Ratio::new::<ratio>(1.0e-3),
+        0).unwrap();
+    prke.neutron_population_number_density =
+        stochastic_prke.get_current_neutron_population_density();
+    prke.precursor_concentration_array =
+        stochastic_prke.get_current_precursor_concentration_array();
+
+    prke.solve_next_timestep_precursor_concentration_and_neutron_pop_vector_matrix_exponential(
+        timestep,
+        reactivity,
+        neutron_generation_time,
+        background_source_rate,
+        &mut MatrixExponentialCache::new()).unwrap();
+
+    // the variance of the population is about 6, so the standard
+    // error of the mean over 400 runs is about 5 %. Clamping gave
+    // a mean of about 12 here
+    assert_relative_eq!(
+        total_neutron_population as f64/number_of_runs as f64,
+        prke.get_current_neutron_population_density().get::<per_cubic_meter>(),
+        max_relative = 0.15);
+}
+
+#[test]
+pub fn stochastic_prke_gillespie_stops_at_the_event_cap(){
+    // a million neutrons with a 100 microsecond generation time
+    // is about 2e10 events a second, far too many for Gillespie,
+    // so the cap gives an error and leaves the populations alone,
+    // while tau leaping gets through the same interval
+
+    use uom::si::time::{microsecond, millisecond, second};
+    use uom::si::volume::cubic_meter;
+    use crate::zero_power_prke::stochastic_prke::*;
+
+    let neutron_generation_time = Time::new::<microsecond>(100.0);
+    let reactivity = Ratio::new::<ratio>(0.0);
+    let background_source_rate = VolumetricNumberRate::ZERO;
+
+    let mut prke = SixGroupPRKE::default();
+    prke.set_equilibrium_from_neutron_population_density(
+        VolumetricNumberDensity::new::<per_cubic_meter>(1.0e6),
+        neutron_generation_time);
+
+    let mut stochastic_prke = StochasticPRKE::new_from_prke(
+        &prke,
+        Volume::new::<cubic_meter>(1.0),
+        Ratio::new::<ratio>(1.0e-3),
+        5).unwrap();
+    stochastic_prke.max_events_per_interval = 100_000;
+
+    let neutron_population = stochastic_prke.neutron_population;
+    let precursor_population_array = stochastic_prke.precursor_population_array;
+
+    assert!(stochastic_prke.advance(
+        Time::new::<second>(1.0),
+        reactivity,
+        neutron_generation_time,
+        background_source_rate,
+        StochasticMethod::Gillespie).is_err());
+
+    assert_eq!(stochastic_prke.neutron_population, neutron_population);
+    assert_eq!(stochastic_prke.precursor_population_array,
+        precursor_population_array);
+
+    assert!(stochastic_prke.advance(
+        Time::new::<second>(1.0),
+        reactivity,
+        neutron_generation_time,
+        background_source_rate,
+        StochasticMethod::TauLeaping(Time::new::<millisecond>(1.0))).is_ok());
+}
+
+#[test]
+pub fn stochastic_prke_tau_leaping_matches_gillespie_at_low_population(){
+    // 2000 neutrons, subcritical with a source, where the leaps
+    // come out at about a generation time, so a millisecond takes
+    // around ten of them. The mean and variance of the neutron population
+    // over many runs should match those from Gillespie alone, so
+    // the leaps are not conditioned on anything
+
+    use uom::si::time::{microsecond, millisecond};
+    use uom::si::volume::cubic_meter;
+    use uom::si::volumetric_number_rate::per_cubic_meter_second;
+    use approx::assert_relative_eq;
+    use crate::zero_power_prke::stochastic_prke::*;
+
+    let neutron_generation_time = Time::new::<microsecond>(100.0);
+    let reactivity = Ratio::new::<ratio>(-0.2);
+    let background_source_rate =
+        VolumetricNumberRate::new::<per_cubic_meter_second>(4.0e6);
+    let timestep = Time::new::<millisecond>(1.0);
+    let number_of_runs = 400;
+
+    let prke = SixGroupPRKE::new_at_source_equilibrium(
+        FissioningNuclideType::U235,
+        reactivity,
+        neutron_generation_time,
+        background_source_rate).unwrap();
+
+    let get_mean_and_variance = |stochastic_method: StochasticMethod| -> (f64, f64) {
+        let neutron_population_list: Vec<f64> = (0..number_of_runs).map(|seed| {
+            let mut stochastic_prke = StochasticPRKE::new_from_prke(
+                &prke,
+                Volume::new::<cubic_meter>(1.0),
+                Ratio::new::<ratio>(1.0e-3),
+                seed).unwrap();
+
+            stochastic_prke.advance(
+                timestep,
+                reactivity,
+                neutron_generation_time,
+                background_source_rate,
+                stochastic_method).unwrap();
+
+            stochastic_prke.neutron_population as f64
+        }).collect();
+
+        let mean = neutron_population_list.iter().sum::<f64>()
+            /number_of_runs as f64;
+        let variance = neutron_population_list.iter()
+            .map(|neutron_population| (neutron_population - mean).powi(2))
+            .sum::<f64>()/(number_of_runs as f64 - 1.0);
+
+        (mean, variance)
+    };
+
+    let (gillespie_mean, gillespie_variance) =
+        get_mean_and_variance(StochasticMethod::Gillespie);
+    let (tau_leaping_mean, tau_leaping_variance) =
+        get_mean_and_variance(StochasticMethod::TauLeaping(
+                Time::new::<millisecond>(1.0)));
+
+    // source equilibrium is - S Lambda/rho = 2000 neutrons
+    assert_relative_eq!(gillespie_mean, 2000.0, max_relative = 0.05);
+    assert_relative_eq!(tau_leaping_mean, gillespie_mean, max_relative = 0.05);
+    assert_relative_eq!(tau_leaping_variance, gillespie_variance, max_relative = 0.2);
+}
+
+#[test]
+pub fn stochastic_prke_rejects_populations_that_are_not_whole_numbers(){
+    use uom::si::volume::cubic_meter;
+    use crate::zero_power_prke::stochastic_prke::*;
+
+    let mut prke = SixGroupPRKE {
+        neutron_population_number_density:
+            VolumetricNumberDensity::new::<per_cubic_meter>(-1.0),
+        ..Default::default()
+    };
+    assert!(StochasticPRKE::new_from_prke(
+        &prke,
+        Volume::new::<cubic_meter>(1.0),
+        Ratio::new::<ratio>(1.0e-3),
+        0).is_err());
+
+    prke.neutron_population_number_density =
+        VolumetricNumberDensity::new::<per_cubic_meter>(f64::NAN);
+    assert!(StochasticPRKE::new_from_prke(
+        &prke,
+        Volume::new::<cubic_meter>(1.0),
+        Ratio::new::<ratio>(1.0e-3),
+        0).is_err());
+
+    prke.neutron_population_number_density =
+        VolumetricNumberDensity::new::<per_cubic_meter>(10.0);
+    prke.precursor_concentration_array[2] =
+        VolumetricNumberDensity::new::<per_cubic_meter>(f64::INFINITY);
+    assert!(StochasticPRKE::new_from_prke(
+        &prke,
+        Volume::new::<cubic_meter>(1.0),
+        Ratio::new::<ratio>(1.0e-3),
+        0).is_err());
+}
+
+#[test]
+pub fn transfer_function_matches_small_amplitude_sinusoidal_runs(){
+    // the PRKE is driven by rho = rho_0 sin(omega t), with rho_0
+    // small so the response is linear. Once the precursor 
+    // transients die out, projecting dn/n0 onto sin and cos over
+    // whole periods gives |G| and its phase
+    //
+    // Crank-Nicolson is used, with the feedback (if any) lagging 
+    // the power by one timestep
+
+    use std::f64::consts::PI;
+    use uom::si::time::{microsecond, second};
+    use uom::si::angle::degree;
+    use uom::si::angular_velocity::radian_per_second;
+    use num_complex::Complex;
+    use approx::assert_relative_eq;
+    use crate::zero_power_prke::transfer_function::*;
+    use uom::si::thermodynamic_temperature::kelvin;
+
+    let neutron_generation_time = Time::new::<microsecond>(100.0);
+    let reactivity_amplitude = 1.0e-5;
+
+    let initial_prke = SixGroupPRKE::new_at_equilibrium(
+        FissioningNuclideType::U235, 
+        VolumetricNumberDensity::new::<per_cubic_meter>(1.0), 
+        neutron_generation_time);
+
+    let measure_transfer_function = |angular_frequency: f64,
+        feedback_model: Option<(f64, f64)>| -> Complex<f64> {
+
+        let mut prke = initial_prke;
+        let period = 2.0 * PI/angular_frequency;
+        let steps_per_period: usize = 400;
+        let dt = period/steps_per_period as f64;
+        let warm_up_periods = (600.0/period).ceil() as usize;
+        let measured_periods: usize = 10;
+
+        // feedback reactivity, with a first order lag
+        let mut feedback_reactivity = 0.0;
+        let mut reactivity_at_start = 0.0;
+        let mut sine_projection = 0.0;
+        let mut cosine_projection = 0.0;
+
+        for step in 0..(warm_up_periods + measured_periods) * steps_per_period {
+            let time_at_end = (step + 1) as f64 * dt;
+            let relative_power_change = 
+                prke.get_current_neutron_population_density().get::<per_cubic_meter>() - 1.0;
+
+            if let Some((reactivity_per_relative_power, time_constant)) = feedback_model {
+                let decay: f64 = (-dt/time_constant).exp();
+                feedback_reactivity = feedback_reactivity * decay
+                    + reactivity_per_relative_power * relative_power_change * (1.0 - decay);
+            }
+
+            let reactivity_at_end = reactivity_amplitude * (angular_frequency * time_at_end).sin()
+                + feedback_reactivity;
+
+            prke.solve_next_timestep_precursor_concentration_and_neutron_pop_vector_theta_method(
+                Time::new::<second>(dt), 
+                Ratio::new::<ratio>(reactivity_at_start), 
+                Ratio::new::<ratio>(reactivity_at_end), 
+                neutron_generation_time, 
+                VolumetricNumberRate::ZERO, 
+                0.5).unwrap();
+
+            reactivity_at_start = reactivity_at_end;
+
+            if step >= warm_up_periods * steps_per_period {
+                let relative_power_change = 
+                    prke.get_current_neutron_population_density().get::<per_cubic_meter>() - 1.0;
+                sine_projection += relative_power_change * (angular_frequency * time_at_end).sin();
+                cosine_projection += relative_power_change * (angular_frequency * time_at_end).cos();
+            }
+        }
+
+        let number_of_samples = (measured_periods * steps_per_period) as f64;
+
+        // a sin(wt) + b cos(wt) = |G| rho_0 sin(wt + phi)
+        Complex::new(sine_projection, cosine_projection) 
+            * 2.0/number_of_samples/reactivity_amplitude
+    };
+
+    // zero power, from the delayed plateau into the prompt region
+    for angular_frequency in [0.05, 0.5, 5.0] {
+        let omega = AngularVelocity::new::<radian_per_second>(angular_frequency);
+        let transfer_function = initial_prke.get_zero_power_transfer_function(
+            omega, neutron_generation_time);
+        let measured_transfer_function = measure_transfer_function(angular_frequency, None);
+
+        assert_relative_eq!(measured_transfer_function.norm(), transfer_function.norm(),
+            max_relative = 0.01);
+        assert!((measured_transfer_function.arg() - transfer_function.arg()).abs() < 0.01);
+    }
+
+    // with a lumped fuel temperature type feedback,
+    // K = -0.002 per unit relative power and tau = 5 s
+    let feedback_model = LinearFeedbackModel::FirstOrderLag {
+        reactivity_per_relative_power: Ratio::new::<ratio>(-0.002),
+        time_constant: Time::new::<second>(5.0),
+    };
+
+    for angular_frequency in [0.05, 0.5] {
+        let omega = AngularVelocity::new::<radian_per_second>(angular_frequency);
+        let transfer_function = initial_prke.get_closed_loop_transfer_function(
+            omega, neutron_generation_time, &feedback_model);
+        let measured_transfer_function = 
+            measure_transfer_function(angular_frequency, Some((-0.002, 5.0)));
+
+        assert_relative_eq!(measured_transfer_function.norm(), transfer_function.norm(),
+            max_relative = 0.01);
+        assert!((measured_transfer_function.arg() - transfer_function.arg()).abs() < 0.01);
+    }
+
+    // limits of the zero power transfer function: 
+    // G -> 1/(s sum_i beta_i/lambda_i) at low frequency,
+    // about 1/beta on the plateau (lambda_i << omega << beta/Lambda) 
+    // and 1/(s Lambda) at high frequency
+    let frequency_response = initial_prke.get_frequency_response(
+        &get_log_spaced_angular_frequency_list(
+            AngularVelocity::new::<radian_per_second>(1.0e-4),
+            AngularVelocity::new::<radian_per_second>(1.0e5),
+            10).unwrap(),
+        neutron_generation_time,
+        None);
+
+    assert_eq!(frequency_response.len(), 91);
+
+    let beta = initial_prke.get_total_delayed_fraction().get::<ratio>();
+    let mean_delayed_lifetime: f64 = initial_prke.delayed_fraction_array.iter()
+        .zip(initial_prke.decay_constant_array.iter())
+        .map(|(beta_i, lambda_i)| beta_i.get::<ratio>()/lambda_i.get::<uom::si::frequency::hertz>())
+        .sum();
+
+    let low_frequency_point = frequency_response[0];
+    let plateau_point = frequency_response[50];
+    let high_frequency_point = frequency_response[90];
+
+    assert_relative_eq!(low_frequency_point.gain, 1.0/(1.0e-4 * mean_delayed_lifetime),
+        max_relative = 0.01);
+    assert_relative_eq!(low_frequency_point.phase.get::<degree>(), -90.0, epsilon = 1.0);
+    assert_relative_eq!(plateau_point.gain, 1.0/beta, max_relative = 0.02);
+    assert_relative_eq!(high_frequency_point.gain, 1.0/(1.0e5 * 1.0e-4),
+        max_relative = 0.01);
+    assert_relative_eq!(high_frequency_point.get_gain_in_decibels(), -20.0,
+        epsilon = 0.1);
+
+    // negative feedback cuts the low frequency gain down to -1/K
+    let closed_loop_response = initial_prke.get_frequency_response(
+        &[AngularVelocity::new::<radian_per_second>(1.0e-5)],
+        neutron_generation_time,
+        Some(&feedback_model));
+
+    assert_relative_eq!(closed_loop_response[0].gain, 500.0, max_relative = 0.01);
+
+    // linearised fuel temperature feedback, K = alpha P0/(hA) and
+    // tau = m c_p/(hA)
+    let fuel_temperature_feedback = 
+        crate::fuel_temperature_feedback::SimpleFuelTemperatureFeedback::default();
+    let steady_state_power = Power::new::<uom::si::power::watt>(1000.0);
+
+    let steady_state_fuel_temperature = ThermodynamicTemperature::new::<kelvin>(400.0);
+
+    let linearised_feedback_model = LinearFeedbackModel::new_from_fuel_temperature_feedback(
+        &fuel_temperature_feedback, steady_state_power, steady_state_fuel_temperature).unwrap();
+
+    // alpha is taken at the operating point given, not the fuel
+    // temperature held in the struct (300 K)
+    let heat_transfer_conductance = fuel_temperature_feedback.convection_heat_trf_coeff
+        * fuel_temperature_feedback.convection_heat_trf_area;
+    let alpha = -fuel_temperature_feedback.alpha_coefficient.get::<ratio>()/400.0_f64.sqrt();
+
+    match linearised_feedback_model {
+        LinearFeedbackModel::FirstOrderLag { reactivity_per_relative_power, time_constant } => {
+            assert_relative_eq!(reactivity_per_relative_power.get::<ratio>(),
+                alpha * 1000.0/heat_transfer_conductance.value, max_relative = 1e-12);
+            assert_relative_eq!(time_constant.get::<second>(),
+                (fuel_temperature_feedback.fuel_volume 
+                 * fuel_temperature_feedback.fuel_density
+                 * fuel_temperature_feedback.fuel_specific_heat_capacity).value
+                /heat_transfer_conductance.value, max_relative = 1e-12);
+        },
+        _ => panic!("fuel temperature feedback should be a first order lag"),
+    }
+
+    assert!(LinearFeedbackModel::new_from_fuel_temperature_feedback(
+        &fuel_temperature_feedback, 
+        steady_state_power, 
+        ThermodynamicTemperature::new::<kelvin>(0.0)).is_err());
+}
+
+#[test]
+pub fn multipoint_prke_reduces_to_point_kinetics_and_tilts_with_loose_coupling(){
+    // one region is the point kinetics, and so are identical
+    // regions at the same reactivity whatever the coupling. With
+    // tight coupling, two halves at different reactivity behave as
+    // one core at the mean reactivity, and loosening the coupling
+    // tilts the shape towards the more reactive half
+
+    use uom::si::time::{microsecond, second};
+    use uom::si::frequency::hertz;
+    use uom::si::volumetric_number_rate::per_cubic_meter_second;
+    use approx::assert_relative_eq;
+    use ndarray::Array2;
+    use crate::zero_power_prke::multipoint_prke::MultipointPRKE;
+    use crate::zero_power_prke::multi_group_precursor_prke::MatrixExponentialCache;
+    use crate::reactivity::Reactivity;
+
+    let neutron_generation_time = Time::new::<microsecond>(100.0);
+    let timestep = Time::new::<second>(0.1);
+    let number_of_timesteps = 10;
+    let background_source_rate = VolumetricNumberRate::new::<per_cubic_meter_second>(0.0);
+
+    let initial_prke = SixGroupPRKE::new_at_equilibrium(
+        FissioningNuclideType::U235, 
+        VolumetricNumberDensity::new::<per_cubic_meter>(1.0), 
+        neutron_generation_time);
+
+    let run_point_kinetics = |reactivity: Ratio| -> VolumetricNumberDensity {
+        let mut prke = initial_prke;
+        let mut matrix_exponential_cache = MatrixExponentialCache::new();
+
+        for _ in 0..number_of_timesteps {
+            prke.solve_next_timestep_precursor_concentration_and_neutron_pop_vector_matrix_exponential(
+                timestep, 
+                reactivity, 
+                neutron_generation_time, 
+                background_source_rate, 
+                &mut matrix_exponential_cache).unwrap();
+        }
+
+        prke.get_current_neutron_population_density()
+    };
+
+    let run_two_region = |coupling_coefficient: Frequency, 
+        reactivity_list: [Ratio;2]| -> MultipointPRKE<6> {
+        let mut multipoint_prke = MultipointPRKE::new_two_region(
+            initial_prke, 
+            initial_prke, 
+            coupling_coefficient).unwrap();
+
+        for _ in 0..number_of_timesteps {
+            multipoint_prke.solve_next_timestep_precursor_concentration_and_neutron_pop_vector_matrix_exponential(
+                timestep, 
+                &reactivity_list, 
+                &[neutron_generation_time, neutron_generation_time], 
+                &[background_source_rate, background_source_rate]).unwrap();
+        }
+
+        multipoint_prke
+    };
+
+    // one region
+    let reactivity = Ratio::new::<ratio>(0.002);
+    let point_neutron_population_density = run_point_kinetics(reactivity);
+
+    let mut single_region_prke = MultipointPRKE::new(
+        vec![initial_prke], 
+        Array2::zeros((1, 1))).unwrap();
+
+    for _ in 0..number_of_timesteps {
+        single_region_prke.solve_next_timestep_precursor_concentration_and_neutron_pop_vector_matrix_exponential(
+            timestep, 
+            &[reactivity], 
+            &[neutron_generation_time], 
+            &[background_source_rate]).unwrap();
+    }
+
+    assert_relative_eq!(
+        single_region_prke.get_mean_neutron_population_density().get::<per_cubic_meter>(),
+        point_neutron_population_density.get::<per_cubic_meter>(),
+        max_relative = 1e-10);
+
+    // identical regions
+    let symmetric_prke = run_two_region(Frequency::new::<hertz>(1.0), [reactivity, reactivity]);
+
+    for neutron_population_density in symmetric_prke.get_neutron_population_density_list() {
+        assert_relative_eq!(neutron_population_density.get::<per_cubic_meter>(),
+            point_neutron_population_density.get::<per_cubic_meter>(),
+            max_relative = 1e-10);
+    }
+
+    // halves at 0.001 and 0.003, tightly coupled
+    let reactivity_list = [Ratio::new::<ratio>(0.001), Ratio::new::<ratio>(0.003)];
+    let tightly_coupled_prke = run_two_region(Frequency::new::<hertz>(1.0e4), reactivity_list);
+
+    assert_relative_eq!(
+        MultipointPRKE::<6>::get_tightly_coupled_reactivity(&reactivity_list).get::<ratio>(),
+        0.002, max_relative = 1e-12);
+    assert_relative_eq!(
+        tightly_coupled_prke.get_mean_neutron_population_density().get::<per_cubic_meter>(),
+        point_neutron_population_density.get::<per_cubic_meter>(),
+        max_relative = 1e-3);
+    assert!(tightly_coupled_prke.get_peak_to_mean_ratio().get::<ratio>() < 1.001);
+
+    // loosely coupled, the more reactive half runs ahead
+    let loosely_coupled_prke = run_two_region(Frequency::new::<hertz>(1.0), reactivity_list);
+    let neutron_population_density_list = loosely_coupled_prke.get_neutron_population_density_list();
+
+    assert!(neutron_population_density_list[1] > 1.5 * neutron_population_density_list[0]);
+    assert!(loosely_coupled_prke.get_peak_to_mean_ratio().get::<ratio>() > 1.2);
+
+    // reactivities can be given as Reactivity, eg. in dollars
+    let beta = initial_prke.get_total_delayed_fraction();
+    let dollar_reactivity_list = reactivity_list
+        .map(|reactivity| Reactivity::new_from_dollars(
+                Reactivity::new(reactivity).get_dollars(beta), beta));
+    let mut dollar_prke = MultipointPRKE::new_two_region(
+        initial_prke, 
+        initial_prke, 
+        Frequency::new::<hertz>(1.0)).unwrap();
+    let mut ratio_prke = dollar_prke.clone();
+
+    let dollar_neutron_population_density_list = dollar_prke
+        .solve_next_timestep_precursor_concentration_and_neutron_pop_vector_matrix_exponential(
+            timestep, 
+            &dollar_reactivity_list, 
+            &[neutron_generation_time, neutron_generation_time], 
+            &[background_source_rate, background_source_rate]).unwrap();
+    let ratio_neutron_population_density_list = ratio_prke
+        .solve_next_timestep_precursor_concentration_and_neutron_pop_vector_matrix_exponential(
+            timestep, 
+            &reactivity_list, 
+            &[neutron_generation_time, neutron_generation_time], 
+            &[background_source_rate, background_source_rate]).unwrap();
+
+    for (dollar_neutron_population_density, ratio_neutron_population_density) in
+        dollar_neutron_population_density_list.iter()
+        .zip(ratio_neutron_population_density_list.iter()) {
+            assert_relative_eq!(
+                dollar_neutron_population_density.get::<per_cubic_meter>(),
+                ratio_neutron_population_density.get::<per_cubic_meter>(),
+                max_relative = 1e-12);
+    }
+    assert_relative_eq!(
+        MultipointPRKE::<6>::get_tightly_coupled_reactivity(&dollar_reactivity_list)
+        .get::<ratio>(),
+        0.002, max_relative = 1e-12);
+
+    // mismatched inputs are errors
+    assert!(MultipointPRKE::new(vec![initial_prke], Array2::zeros((2, 2))).is_err());
+    assert!(MultipointPRKE::new_two_region(initial_prke, initial_prke, 
+        Frequency::new::<hertz>(-1.0)).is_err());
+    assert!(symmetric_prke.clone()
+        .solve_next_timestep_precursor_concentration_and_neutron_pop_vector_matrix_exponential(
+            timestep, 
+            &[reactivity], 
+            &[neutron_generation_time], 
+            &[background_source_rate]).is_err());
+}
+
+#[test]
+pub fn circulating_fuel_precursor_drift_and_pump_trip(){
+    // MSRE like transit times, 8.46 s in the core and 16.73 s in
+    // the loop. Holding the reactivity lost to precursor drift
+    // keeps the core steady while the salt flows, and a pump trip
+    // gives that reactivity back, so the power then grows on the
+    // stable period for rho_loss
+
+    use uom::si::time::{microsecond, second};
+    use uom::si::volume_rate::cubic_meter_per_second;
+    use uom::si::volumetric_number_rate::per_cubic_meter_second;
+    use uom::si::frequency::hertz;
+    use approx::assert_relative_eq;
+    use crate::zero_power_prke::circulating_fuel_prke::CirculatingFuelPRKE;
+
+    let neutron_generation_time = Time::new::<microsecond>(400.0);
+    let core_transit_time = Time::new::<second>(8.46);
+    let loop_transit_time = Time::new::<second>(16.73);
+    let timestep = Time::new::<second>(0.05);
+    let background_source_rate = VolumetricNumberRate::new::<per_cubic_meter_second>(0.0);
+
+    let initial_prke = SixGroupPRKE::new_at_equilibrium(
+        FissioningNuclideType::U235, 
+        VolumetricNumberDensity::new::<per_cubic_meter>(1.0), 
+        neutron_generation_time);
+
+    let mut circulating_fuel_prke = CirculatingFuelPRKE::new_from_transit_times(
+        initial_prke, 
+        core_transit_time, 
+        loop_transit_time, 
+        neutron_generation_time).unwrap();
+
+    assert_relative_eq!(circulating_fuel_prke.get_core_transit_time().get::<second>(), 8.46,
+        max_relative = 1e-12);
+    assert_relative_eq!(circulating_fuel_prke.get_loop_transit_time().get::<second>(), 16.73,
+        max_relative = 1e-12);
+
+    // rho_loss = beta - sum_i beta_i lambda_i
+    // /(lambda_i + (1 - exp(-lambda_i tau_L))/tau_c)
+    let reactivity_loss_by_hand: f64 = initial_prke.get_total_delayed_fraction().get::<ratio>()
+        - initial_prke.delayed_fraction_array.iter()
+        .zip(initial_prke.decay_constant_array.iter())
+        .map(|(beta_i, lambda_i)| {
+            let lambda_i = lambda_i.get::<hertz>();
+            beta_i.get::<ratio>() * lambda_i
+                /(lambda_i + (1.0 - (-lambda_i * 16.73).exp())/8.46)
+        })
+        .sum::<f64>();
+
+    let reactivity_loss = circulating_fuel_prke.get_reactivity_loss_from_precursor_drift();
+
+    assert_relative_eq!(reactivity_loss.get::<ratio>(), reactivity_loss_by_hand,
+        max_relative = 1e-12);
+
+    // about a third of beta, the MSRE measured 0.21 % for U235 
+    // (which also has the spatial weighting we leave out)
+    assert!(reactivity_loss.get::<ratio>() > 0.0020);
+    assert!(reactivity_loss.get::<ratio>() < 0.0026);
+
+    // steady with the salt flowing, through many loop transits
+    for _ in 0..4000 {
+        circulating_fuel_prke
+            .solve_next_timestep_precursor_concentration_and_neutron_pop_vector_circulating_fuel(
+                timestep, 
+                reactivity_loss, 
+                neutron_generation_time, 
+                background_source_rate).unwrap();
+    }
+
+    assert_relative_eq!(
+        circulating_fuel_prke.prke.get_current_neutron_population_density()
+        .get::<per_cubic_meter>(), 1.0, max_relative = 1e-9);
+
+    // slower flow loses fewer precursors, and no flow loses none
+    let mut coastdown_prke = circulating_fuel_prke.clone();
+    coastdown_prke.salt_volumetric_flowrate = VolumeRate::new::<cubic_meter_per_second>(0.5);
+    assert!(coastdown_prke.get_reactivity_loss_from_precursor_drift() < reactivity_loss);
+
+    circulating_fuel_prke.salt_volumetric_flowrate = VolumeRate::new::<cubic_meter_per_second>(0.0);
+    assert_eq!(circulating_fuel_prke.get_reactivity_loss_from_precursor_drift().get::<ratio>(), 0.0);
+
+    // pump trip, once the precursors settle the growth rate is
+    // the inhour stable root for rho_loss
+    let inhour_solution = initial_prke.solve_inhour_equation(
+        reactivity_loss, 
+        neutron_generation_time).unwrap();
+
+    let mut run_for = |number_of_timesteps: usize| -> f64 {
+        for _ in 0..number_of_timesteps {
+            circulating_fuel_prke
+                .solve_next_timestep_precursor_concentration_and_neutron_pop_vector_circulating_fuel(
+                    timestep, 
+                    reactivity_loss, 
+                    neutron_generation_time, 
+                    background_source_rate).unwrap();
+        }
+        circulating_fuel_prke.prke.get_current_neutron_population_density()
+            .get::<per_cubic_meter>()
+    };
+
+    let neutron_population_after_trip = run_for(200);
+    assert!(neutron_population_after_trip > 2.0);
+
+    let neutron_population_at_200_seconds = run_for(3800);
+    let neutron_population_at_220_seconds = run_for(400);
+
+    assert_relative_eq!(
+        neutron_population_at_220_seconds/neutron_population_at_200_seconds,
+        (inhour_solution.get_stable_root().get::<hertz>() * 20.0).exp(),
+        max_relative = 0.02);
+
+    // timesteps longer than the loop transit are refused
+    let mut long_step_prke = coastdown_prke;
+    assert!(long_step_prke
+        .solve_next_timestep_precursor_concentration_and_neutron_pop_vector_circulating_fuel(
+            Time::new::<second>(60.0), 
+            reactivity_loss, 
+            neutron_generation_time, 
+            background_source_rate).is_err());
+}
+
+#[test]
+pub fn circulating_fuel_pump_coastdown_settles_at_the_drift_for_the_final_flow(){
+    // MSRE like transit times at full flow, then the pump coasts
+    // down linearly to a quarter flow over 20 s while the
+    // reactivity follows rho_loss for the flow at that moment.
+    // Holding rho_loss for a quarter flow afterwards, the core
+    // should settle, with the precursors at the circulating fuel
+    // equilibrium for a quarter flow
+
+    use uom::si::time::{microsecond, second};
+    use uom::si::volume_rate::cubic_meter_per_second;
+    use uom::si::volumetric_number_rate::per_cubic_meter_second;
+    use approx::assert_relative_eq;
+    use crate::zero_power_prke::circulating_fuel_prke::CirculatingFuelPRKE;
+
+    let neutron_generation_time = Time::new::<microsecond>(400.0);
+    let timestep = Time::new::<second>(0.05);
+    let background_source_rate = VolumetricNumberRate::new::<per_cubic_meter_second>(0.0);
+    let full_flow = 1.0;
+    let final_flow = 0.25;
+    let coastdown_time = 20.0;
+
+    let initial_prke = SixGroupPRKE::new_at_equilibrium(
+        FissioningNuclideType::U235, 
+        VolumetricNumberDensity::new::<per_cubic_meter>(1.0), 
+        neutron_generation_time);
+
+    let mut coastdown_prke = CirculatingFuelPRKE::new_from_transit_times(
+        initial_prke, 
+        Time::new::<second>(8.46), 
+        Time::new::<second>(16.73), 
+        neutron_generation_time).unwrap();
+
+    let full_flow_reactivity_loss = coastdown_prke.get_reactivity_loss_from_precursor_drift();
+
+    let mut final_flow_prke = coastdown_prke.clone();
+    final_flow_prke.salt_volumetric_flowrate =
+        VolumeRate::new::<cubic_meter_per_second>(final_flow);
+    let final_flow_reactivity_loss = final_flow_prke.get_reactivity_loss_from_precursor_drift();
+
+    assert!(final_flow_reactivity_loss < full_flow_reactivity_loss);
+    assert!(final_flow_reactivity_loss.get::<ratio>() > 0.0);
+
+    let mut current_time = 0.0;
+
+    let mut run_until = |end_time: f64,
+        coastdown_prke: &mut CirculatingFuelPRKE<6>| -> f64 {
+        while current_time < end_time - 1e-9 {
+            current_time += timestep.get::<second>();
+
+            let flowrate = if current_time < coastdown_time {
+                full_flow - (full_flow - final_flow) * current_time/coastdown_time
+            } else {
+                final_flow
+            };
+
+            coastdown_prke.salt_volumetric_flowrate =
+                VolumeRate::new::<cubic_meter_per_second>(flowrate);
+            let reactivity_loss = coastdown_prke.get_reactivity_loss_from_precursor_drift();
+
+            coastdown_prke
+                .solve_next_timestep_precursor_concentration_and_neutron_pop_vector_circulating_fuel(
+                    timestep, 
+                    reactivity_loss, 
+                    neutron_generation_time, 
+                    background_source_rate).unwrap();
+        }
+
+        coastdown_prke.prke.get_current_neutron_population_density()
+            .get::<per_cubic_meter>()
+    };
+
+    // the reactivity lags the precursors during the ramp, so the
+    // power dips, but it must not run away either way
+    let neutron_population_after_ramp = run_until(coastdown_time, &mut coastdown_prke);
+    assert!(neutron_population_after_ramp < 1.0);
+    assert!(neutron_population_after_ramp > 0.5);
+
+    // many loop transits (67 s each at a quarter flow) later,
+    // the power has settled
+    let neutron_population_at_1000_seconds = run_until(1000.0, &mut coastdown_prke);
+    let neutron_population_at_1100_seconds = run_until(1100.0, &mut coastdown_prke);
+
+    assert_relative_eq!(
+        neutron_population_at_1100_seconds,
+        neutron_population_at_1000_seconds,
+        max_relative = 1e-4);
+
+    assert_relative_eq!(
+        coastdown_prke.get_reactivity_loss_from_precursor_drift().get::<ratio>(),
+        final_flow_reactivity_loss.get::<ratio>(),
+        max_relative = 1e-12);
+
+    let equilibrium_precursor_concentration_array =
+        coastdown_prke.get_equilibrium_precursor_concentration_array(
+            coastdown_prke.prke.get_current_neutron_population_density(),
+            neutron_generation_time);
+
+    for (precursor_concentration, equilibrium_precursor_concentration) in
+        coastdown_prke.prke.precursor_concentration_array.iter()
+        .zip(equilibrium_precursor_concentration_array.iter()) {
+            assert_relative_eq!(
+                precursor_concentration.get::<per_cubic_meter>(),
+                equilibrium_precursor_concentration.get::<per_cubic_meter>(),
+                max_relative = 1e-3);
+    }
+
+    // zero and negative timesteps are refused, and leave the
+    // state alone
+    let neutron_population = coastdown_prke.prke.get_current_neutron_population_density();
+    for bad_timestep in [0.0, -0.05] {
+        assert!(coastdown_prke
+            .solve_next_timestep_precursor_concentration_and_neutron_pop_vector_circulating_fuel(
+                Time::new::<second>(bad_timestep), 
+                final_flow_reactivity_loss, 
+                neutron_generation_time, 
+                background_source_rate).is_err());
+    }
+    assert_eq!(coastdown_prke.prke.get_current_neutron_population_density(),
+        neutron_population);
+}
@@ -0,0 +1,251 @@
+use num_complex::Complex;
+use uom::si::angle::radian;
+use uom::si::angular_velocity::radian_per_second;
+use uom::si::f64::*;
+use uom::si::frequency::hertz;
+use uom::si::ratio::ratio;
+use uom::si::thermodynamic_temperature::kelvin;
+use uom::si::temperature_interval;
+use uom::si::time::second;
+
+use crate::fuel_temperature_feedback::{obtain_fuel_temperature_feedback_coeff_thermal_spectrum, SimpleFuelTemperatureFeedback};
+use crate::teh_o_prke_error::TehOPrkeError;
+use crate::zero_power_prke::multi_group_precursor_prke::MultiGroupPRKE;
+
+/// a linear reactivity feedback model, giving the feedback
+/// reactivity per unit relative power change, dn/n0
+///
+/// H(s) = rho_feedback(s)/(dn/n0)(s)
+///
+/// negative feedback has a negative reactivity_per_relative_power
+#[derive(Debug,Clone,PartialEq)]
+pub enum LinearFeedbackModel {
+    /// instantaneous feedback, H(s) = K
+    Proportional {
+        /// steady state reactivity per unit relative power, K
+        reactivity_per_relative_power: Ratio,
+    },
+
+    /// feedback lagging power by a first order lag, such as
+    /// a lumped fuel temperature, H(s) = K/(1 + s tau)
+    FirstOrderLag {
+        /// steady state reactivity per unit relative power, K
+        reactivity_per_relative_power: Ratio,
+        /// time constant of the lag, tau
+        time_constant: Time,
+    },
+
+    /// several feedback mechanisms acting together, the
+    /// transfer functions add
+    Sum(Vec<LinearFeedbackModel>),
+}
+
+impl LinearFeedbackModel {
+
+    /// linearises a SimpleFuelTemperatureFeedback about an operating
+    /// point, the steady state power P0 and fuel temperature T0
+    ///
+    /// m c_p dT/dt = P0 dn/n0 - hA dT, rho_feedback = alpha(T0) dT
+    ///
+    /// so K = alpha(T0) P0/(hA) and tau = m c_p/(hA)
+    ///
+    /// the current fuel_temperature of the struct is not used, at
+    /// steady state T0 = T_coolant + P0/(hA) (see
+    /// CoupledStabilityAnalysis::add_fuel_temperature_feedback)
+    pub fn new_from_fuel_temperature_feedback(
+        fuel_temperature_feedback: &SimpleFuelTemperatureFeedback,
+        steady_state_power: Power,
+        steady_state_fuel_temperature: ThermodynamicTemperature) -> Result<Self, TehOPrkeError> {
+
+        // the temperature coefficient goes as 1/sqrt(T)
+        if steady_state_fuel_temperature.get::<kelvin>() <= 0.0 {
+            return Err(TehOPrkeError::GenericStringError(
+                "steady state fuel temperature must be positive".to_string()
+            ));
+        }
+
+        let fuel_temperature_coefficient = obtain_fuel_temperature_feedback_coeff_thermal_spectrum(
+            fuel_temperature_feedback.alpha_coefficient,
+            steady_state_fuel_temperature)?;
+
+        let heat_transfer_conductance = fuel_temperature_feedback.convection_heat_trf_coeff
+            * fuel_temperature_feedback.convection_heat_trf_area;
+
+        let m_cp = fuel_temperature_feedback.fuel_volume
+            * fuel_temperature_feedback.fuel_density
+            * fuel_temperature_feedback.fuel_specific_heat_capacity;
+
+        let steady_state_temperature_rise: TemperatureInterval =
+            steady_state_power/heat_transfer_conductance;
+
+        // alpha is per kelvin
+        let reactivity_per_relative_power = fuel_temperature_coefficient
+            * steady_state_temperature_rise.get::<temperature_interval::kelvin>();
+
+        Ok(Self::FirstOrderLag {
+            reactivity_per_relative_power,
+            time_constant: m_cp/heat_transfer_conductance,
+        })
+    }
+
+    /// returns the feedback transfer function H(j omega)
+    pub fn get_transfer_function(&self,
+        angular_frequency: AngularVelocity) -> Complex<f64> {
+
+        let s = Complex::new(0.0, angular_frequency.get::<radian_per_second>());
+
+        match self {
+            Self::Proportional { reactivity_per_relative_power } => {
+                Complex::new(reactivity_per_relative_power.get::<ratio>(), 0.0)
+            },
+            Self::FirstOrderLag { reactivity_per_relative_power, time_constant } => {
+                reactivity_per_relative_power.get::<ratio>()
+                    /(1.0 + s * time_constant.get::<second>())
+            },
+            Self::Sum(feedback_model_list) => {
+                feedback_model_list.iter()
+                    .map(|feedback_model| feedback_model.get_transfer_function(angular_frequency))
+                    .sum()
+            },
+        }
+    }
+}
+
+/// gain and phase of a transfer function at one frequency,
+/// for Bode plots
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub struct FrequencyResponsePoint {
+    /// angular frequency, omega
+    pub angular_frequency: AngularVelocity,
+    /// the transfer function, G(j omega)
+    pub transfer_function: Complex<f64>,
+    /// |G(j omega)|, relative power change per unit reactivity
+    pub gain: f64,
+    /// arg G(j omega)
+    pub phase: Angle,
+}
+
+impl FrequencyResponsePoint {
+
+    /// constructs the point from the transfer function value
+    pub fn new(angular_frequency: AngularVelocity,
+        transfer_function: Complex<f64>) -> Self {
+
+        Self {
+            angular_frequency,
+            transfer_function,
+            gain: transfer_function.norm(),
+            phase: Angle::new::<radian>(transfer_function.arg()),
+        }
+    }
+
+    /// returns the gain in decibels, 20 log10 |G|
+    pub fn get_gain_in_decibels(&self) -> f64 {
+        20.0 * self.gain.log10()
+    }
+}
+
+/// returns a log spaced frequency grid from min to max (inclusive)
+/// with the given number of points per decade, for Bode plots
+pub fn get_log_spaced_angular_frequency_list(
+    min_angular_frequency: AngularVelocity,
+    max_angular_frequency: AngularVelocity,
+    points_per_decade: usize) -> Result<Vec<AngularVelocity>, TehOPrkeError> {
+
+    let log_min = min_angular_frequency.get::<radian_per_second>().log10();
+    let log_max = max_angular_frequency.get::<radian_per_second>().log10();
+
+    if !log_min.is_finite() || !log_max.is_finite() || log_max < log_min
+        || points_per_decade == 0 {
+            return Err(TehOPrkeError::GenericStringError(
+                "need 0 < min frequency <= max frequency, and at least \
+                one point per decade".to_string()
+            ));
+    }
+
+    let number_of_intervals = ((log_max - log_min) * points_per_decade as f64).ceil() as usize;
+
+    if number_of_intervals == 0 {
+        return Ok(vec![min_angular_frequency]);
+    }
+
+    let angular_frequency_list = (0..=number_of_intervals).map(|i| {
+        let log_omega = log_min
+            + (log_max - log_min) * i as f64/number_of_intervals as f64;
+        AngularVelocity::new::<radian_per_second>(10.0_f64.powf(log_omega))
+    }).collect();
+
+    Ok(angular_frequency_list)
+}
+
+impl<const G: usize> MultiGroupPRKE<G> {
+
+    /// returns the zero power reactor transfer function,
+    ///
+    /// G(s) = (dn/n0)(s)/rho(s) = 1/(s [Lambda + sum_i beta_i/(s + lambda_i)])
+    ///
+    /// at s = j omega
+    ///
+    /// this is the linearised response about critical, so it only
+    /// holds for small reactivity changes
+    pub fn get_zero_power_transfer_function(&self,
+        angular_frequency: AngularVelocity,
+        neutron_generation_time: Time) -> Complex<f64> {
+
+        let s = Complex::new(0.0, angular_frequency.get::<radian_per_second>());
+
+        let delayed_neutron_term: Complex<f64> = self.delayed_fraction_array.iter()
+            .zip(self.decay_constant_array.iter())
+            .map(|(beta_i, lambda_i)| {
+                beta_i.get::<ratio>()/(s + lambda_i.get::<hertz>())
+            })
+            .sum();
+
+        1.0/(s * (neutron_generation_time.get::<second>() + delayed_neutron_term))
+    }
+
+    /// returns the closed loop transfer function with linear
+    /// feedback attached,
+    ///
+    /// G_cl(s) = G(s)/(1 - G(s) H(s))
+    ///
+    /// where rho = rho_external + H(s) dn/n0, so negative feedback
+    /// (H < 0) brings the gain down at low frequencies
+    pub fn get_closed_loop_transfer_function(&self,
+        angular_frequency: AngularVelocity,
+        neutron_generation_time: Time,
+        feedback_model: &LinearFeedbackModel) -> Complex<f64> {
+
+        let zero_power_transfer_function = self.get_zero_power_transfer_function(
+            angular_frequency,
+            neutron_generation_time);
+
+        let feedback_transfer_function =
+            feedback_model.get_transfer_function(angular_frequency);
+
+        zero_power_transfer_function
+            /(1.0 - zero_power_transfer_function * feedback_transfer_function)
+    }
+
+    /// returns gain and phase over a frequency grid, zero power
+    /// if feedback_model is None, closed loop otherwise
+    pub fn get_frequency_response(&self,
+        angular_frequency_list: &[AngularVelocity],
+        neutron_generation_time: Time,
+        feedback_model: Option<&LinearFeedbackModel>) -> Vec<FrequencyResponsePoint> {
+
+        angular_frequency_list.iter().map(|&angular_frequency| {
+            let transfer_function = match feedback_model {
+                Some(feedback_model) => self.get_closed_loop_transfer_function(
+                    angular_frequency,
+                    neutron_generation_time,
+                    feedback_model),
+                None => self.get_zero_power_transfer_function(
+                    angular_frequency,
+                    neutron_generation_time),
+            };
+
+            FrequencyResponsePoint::new(angular_frequency, transfer_function)
+        }).collect()
+    }
+}
@@ -0,0 +1,437 @@
+use uom::ConstZero;
+use uom::si::f64::*;
+use uom::si::ratio::ratio;
+use uom::si::time::second;
+use uom::si::volumetric_number_density::per_cubic_meter;
+
+use crate::reactivity_profile::ReactivityProfile;
+use crate::teh_o_prke_error::TehOPrkeError;
+use crate::time_stepping::openfoam_adaptive_solver::AdaptiveSolver;
+use crate::time_stepping::openfoam_ode_solver::ODESolverMethod;
+use crate::zero_power_prke::multi_group_precursor_prke::{MatrixExponentialCache, MultiGroupPRKE};
+
+/// anything which can supply the reactivity for each timestep of
+/// a transient, eg. a prescribed ReactivityProfile or a feedback
+/// model which responds to the neutron population
+pub trait ReactivityModel {
+
+    /// returns the reactivity to use over [time, time + timestep],
+    /// given the neutron population at the start of the step
+    ///
+    /// this is called once per step, so feedback models may
+    /// update their own state (eg. fuel temperature) here
+    fn get_reactivity(&mut self,
+        time: Time,
+        timestep: Time,
+        neutron_population_number_density: VolumetricNumberDensity)
+        -> Result<Ratio, TehOPrkeError>;
+}
+
+/// prescribed reactivity, evaluated at the middle of each step
+impl ReactivityModel for ReactivityProfile {
+    fn get_reactivity(&mut self,
+        time: Time,
+        timestep: Time,
+        _neutron_population_number_density: VolumetricNumberDensity)
+        -> Result<Ratio, TehOPrkeError> {
+        self.validate()?;
+        Ok(self.get_mid_step_reactivity(time, timestep))
+    }
+}
+
+/// closures taking (time, timestep, neutron population) can be
+/// used directly, so feedback can be written inline
+impl<F> ReactivityModel for F
+where F: FnMut(Time, Time, VolumetricNumberDensity) -> Ratio {
+    fn get_reactivity(&mut self,
+        time: Time,
+        timestep: Time,
+        neutron_population_number_density: VolumetricNumberDensity)
+        -> Result<Ratio, TehOPrkeError> {
+        Ok(self(time, timestep, neutron_population_number_density))
+    }
+}
+
+/// which of the MultiGroupPRKE solvers takes each step
+#[derive(Debug,Clone,Copy)]
+pub enum PRKESolverMethod {
+    /// backward Euler
+    Implicit,
+    /// theta method, 0.5 is Crank-Nicolson and 1.0 is backward Euler
+    ThetaMethod(f64),
+    /// fixed step RKF45
+    Explicit,
+    /// RKF45 with adaptive substeps
+    ExplicitAdaptive(AdaptiveSolver),
+    /// any of the ODESolver methods with adaptive substeps
+    ODESolver(ODESolverMethod, AdaptiveSolver),
+    /// exact for constant reactivity over each step
+    MatrixExponential,
+    /// prompt jump approximation, for slow transients below
+    /// prompt critical
+    PromptJump,
+}
+
+/// how the transient is stepped, a fixed timestep with
+/// one of the solvers
+///
+/// the adaptive solvers still substep within each timestep
+/// as needed, the reactivity is updated once per timestep
+#[derive(Debug,Clone,Copy)]
+pub struct SteppingPolicy {
+    /// timestep between reactivity updates
+    pub timestep: Time,
+    /// solver used for every step
+    pub solver_method: PRKESolverMethod,
+}
+
+/// conditions on which a transient is stopped before the end time
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub enum StopCondition {
+    /// neutron population density rises above this value
+    NeutronPopulationAbove(VolumetricNumberDensity),
+    /// neutron population density falls below this value
+    NeutronPopulationBelow(VolumetricNumberDensity),
+    /// the period is positive and shorter than this, eg. a
+    /// period trip
+    PositivePeriodShorterThan(Time),
+}
+
+impl StopCondition {
+
+    /// returns true if the snapshot meets the stop condition
+    pub fn is_met<const G: usize>(&self, snapshot: &TransientSnapshot<G>) -> bool {
+        match self {
+            Self::NeutronPopulationAbove(threshold) => {
+                snapshot.neutron_population_number_density > *threshold
+            },
+            Self::NeutronPopulationBelow(threshold) => {
+                snapshot.neutron_population_number_density < *threshold
+            },
+            Self::PositivePeriodShorterThan(threshold) => {
+                snapshot.period > Time::ZERO && snapshot.period < *threshold
+            },
+        }
+    }
+}
+
+/// why the transient ended
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub enum TransientStopReason {
+    /// ran all the way to the end time
+    EndTimeReached,
+    /// stopped early, the last snapshot is where the condition
+    /// was first met
+    StopConditionMet(StopCondition),
+}
+
+/// state of the PRKE at one output time
+#[derive(Debug,Clone,Copy)]
+pub struct TransientSnapshot<const G: usize> {
+    /// time of the snapshot
+    pub time: Time,
+    /// neutron population number density
+    pub neutron_population_number_density: VolumetricNumberDensity,
+    /// delayed neutron precursor concentrations
+    pub precursor_concentration_array: [VolumetricNumberDensity;G],
+    /// reactivity used over the step ending at this time
+    /// (for the first snapshot, the step starting at this time)
+    pub reactivity: Ratio,
+    /// period n/(dn/dt) over the last step, infinite at steady
+    /// state and negative for a falling population
+    pub period: Time,
+}
+
+/// time history of a transient
+#[derive(Debug,Clone)]
+pub struct TransientHistory<const G: usize> {
+    /// snapshots at the start, every output interval and at
+    /// the end (or when a stop condition was met)
+    pub snapshot_list: Vec<TransientSnapshot<G>>,
+    /// whether the end time was reached or a stop condition met
+    pub stop_reason: TransientStopReason,
+}
+
+impl<const G: usize> TransientHistory<G> {
+
+    /// returns the last snapshot, None if the history is empty
+    pub fn get_final_snapshot(&self) -> Option<&TransientSnapshot<G>> {
+        self.snapshot_list.last()
+    }
+
+    /// returns the output times
+    pub fn get_time_list(&self) -> Vec<Time> {
+        self.snapshot_list.iter().map(|snapshot| snapshot.time).collect()
+    }
+
+    /// returns the neutron population at each output time
+    pub fn get_neutron_population_list(&self) -> Vec<VolumetricNumberDensity> {
+        self.snapshot_list.iter()
+            .map(|snapshot| snapshot.neutron_population_number_density)
+            .collect()
+    }
+
+    /// returns the reactivity at each output time
+    pub fn get_reactivity_list(&self) -> Vec<Ratio> {
+        self.snapshot_list.iter().map(|snapshot| snapshot.reactivity).collect()
+    }
+
+    /// returns the period at each output time
+    pub fn get_period_list(&self) -> Vec<Time> {
+        self.snapshot_list.iter().map(|snapshot| snapshot.period).collect()
+    }
+}
+
+/// runs a PRKE transient from start_time to end_time, so the
+/// loop calling solve_next_timestep_* does not need to be written
+/// by hand
+///
+/// the fields are public, new() fills in a zero background
+/// source, a start time of zero and no stop conditions
+#[derive(Debug,Clone)]
+pub struct TransientRunner {
+    /// neutron generation time, Lambda
+    pub neutron_generation_time: Time,
+    /// background neutron source, S
+    pub background_source_rate: VolumetricNumberRate,
+    /// time at the start of the transient, this is the time
+    /// given to the reactivity model
+    pub start_time: Time,
+    /// time at which the transient ends
+    pub end_time: Time,
+    /// timestep and solver
+    pub stepping_policy: SteppingPolicy,
+    /// snapshots are recorded at this interval, it should be a
+    /// multiple of the timestep
+    pub output_interval: Time,
+    /// the transient stops as soon as any of these is met
+    pub stop_condition_list: Vec<StopCondition>,
+}
+
+impl TransientRunner {
+
+    /// constructs a runner starting at time zero with no background
+    /// source and no stop conditions
+    pub fn new(neutron_generation_time: Time,
+        end_time: Time,
+        stepping_policy: SteppingPolicy,
+        output_interval: Time) -> Self {
+        Self {
+            neutron_generation_time,
+            background_source_rate: VolumetricNumberRate::ZERO,
+            start_time: Time::ZERO,
+            end_time,
+            stepping_policy,
+            output_interval,
+            stop_condition_list: vec![],
+        }
+    }
+
+    /// runs the transient, the PRKE is left at the final state
+    ///
+    /// the last timestep is shortened so that the transient ends
+    /// exactly at end_time, which must be after start_time
+    pub fn run<const G: usize>(&self,
+        prke: &mut MultiGroupPRKE<G>,
+        reactivity_model: &mut impl ReactivityModel)
+        -> Result<TransientHistory<G>, TehOPrkeError> {
+
+            let timestep = self.stepping_policy.timestep;
+
+            if timestep <= Time::ZERO || self.output_interval <= Time::ZERO {
+                return Err(TehOPrkeError::GenericStringError(
+                    "timestep and output interval must be positive".to_string()
+                ));
+            }
+
+            if self.end_time <= self.start_time {
+                return Err(TehOPrkeError::GenericStringError(
+                    "end time must be after start time".to_string()
+                ));
+            }
+
+            let mut matrix_exponential_cache = MatrixExponentialCache::new();
+            let mut snapshot_list: Vec<TransientSnapshot<G>> = vec![];
+
+            // small allowance so that round off in the accumulated
+            // time does not cause an extra sliver of a step
+            let time_tolerance: Time = 1e-9 * timestep;
+
+            let mut time: Time = self.start_time;
+            let mut next_output_time: Time = self.start_time + self.output_interval;
+            let mut number_of_steps_taken: usize = 0;
+
+            while time < self.end_time - time_tolerance {
+
+                // time is counted in whole steps from the start
+                // rather than accumulated, so it does not drift
+                let mut time_after_step: Time = self.start_time
+                    + (number_of_steps_taken + 1) as f64 * timestep;
+
+                if time_after_step > self.end_time - time_tolerance {
+                    time_after_step = self.end_time;
+                }
+
+                let current_timestep: Time = time_after_step - time;
+
+                let neutron_population_before_step =
+                    prke.get_current_neutron_population_density();
+
+                let reactivity: Ratio = reactivity_model.get_reactivity(
+                    time,
+                    current_timestep,
+                    neutron_population_before_step)?;
+
+                if snapshot_list.is_empty() {
+                    snapshot_list.push(Self::take_snapshot(
+                        prke, time, reactivity,
+                        Time::new::<second>(f64::INFINITY)));
+                }
+
+                self.solve_next_timestep(prke,
+                    current_timestep,
+                    reactivity,
+                    &mut matrix_exponential_cache)?;
+
+                time = time_after_step;
+                number_of_steps_taken += 1;
+
+                let period: Time = Self::calc_period(
+                    neutron_population_before_step,
+                    prke.get_current_neutron_population_density(),
+                    current_timestep);
+
+                let snapshot = Self::take_snapshot(prke, time, reactivity, period);
+
+                if let Some(stop_condition) = self.stop_condition_list.iter()
+                    .find(|stop_condition| stop_condition.is_met(&snapshot)) {
+
+                        snapshot_list.push(snapshot);
+
+                        return Ok(TransientHistory {
+                            snapshot_list,
+                            stop_reason: TransientStopReason::StopConditionMet(*stop_condition),
+                        });
+                }
+
+                if time >= next_output_time - time_tolerance || time == self.end_time {
+                    snapshot_list.push(snapshot);
+
+                    while next_output_time <= time + time_tolerance {
+                        next_output_time += self.output_interval;
+                    }
+                }
+            }
+
+            Ok(TransientHistory {
+                snapshot_list,
+                stop_reason: TransientStopReason::EndTimeReached,
+            })
+    }
+
+    /// takes one step with the solver in the stepping policy
+    fn solve_next_timestep<const G: usize>(&self,
+        prke: &mut MultiGroupPRKE<G>,
+        timestep: Time,
+        reactivity: Ratio,
+        matrix_exponential_cache: &mut MatrixExponentialCache)
+        -> Result<(), TehOPrkeError> {
+
+            let neutron_generation_time = self.neutron_generation_time;
+            let background_source_rate = self.background_source_rate;
+
+            match self.stepping_policy.solver_method {
+                PRKESolverMethod::Implicit => {
+                    prke.solve_next_timestep_precursor_concentration_and_neutron_pop_vector_implicit(
+                        timestep,
+                        reactivity,
+                        neutron_generation_time,
+                        background_source_rate)?;
+                },
+                PRKESolverMethod::ThetaMethod(theta) => {
+                    prke.solve_next_timestep_precursor_concentration_and_neutron_pop_vector_theta_method(
+                        timestep,
+                        reactivity,
+                        reactivity,
+                        neutron_generation_time,
+                        background_source_rate,
+                        theta)?;
+                },
+                PRKESolverMethod::Explicit => {
+                    prke.solve_next_timestep_precursor_concentration_and_neutron_pop_vector_explicit(
+                        timestep,
+                        reactivity,
+                        neutron_generation_time,
+                        background_source_rate)?;
+                },
+                PRKESolverMethod::ExplicitAdaptive(adaptive_solver) => {
+                    prke.solve_next_timestep_precursor_concentration_and_neutron_pop_vector_explicit_adaptive(
+                        timestep,
+                        reactivity,
+                        neutron_generation_time,
+                        background_source_rate,
+                        &adaptive_solver)?;
+                },
+                PRKESolverMethod::ODESolver(ode_solver_method, adaptive_solver) => {
+                    prke.solve_next_timestep_precursor_concentration_and_neutron_pop_vector_with_ode_solver(
+                        timestep,
+                        reactivity,
+                        neutron_generation_time,
+                        background_source_rate,
+                        ode_solver_method,
+                        &adaptive_solver)?;
+                },
+                PRKESolverMethod::MatrixExponential => {
+                    prke.solve_next_timestep_precursor_concentration_and_neutron_pop_vector_matrix_exponential(
+                        timestep,
+                        reactivity,
+                        neutron_generation_time,
+                        background_source_rate,
+                        matrix_exponential_cache)?;
+                },
+                PRKESolverMethod::PromptJump => {
+                    prke.solve_next_timestep_precursor_concentration_and_neutron_pop_vector_prompt_jump(
+                        timestep,
+                        reactivity,
+                        neutron_generation_time,
+                        background_source_rate)?;
+                },
+            }
+
+            Ok(())
+    }
+
+    fn take_snapshot<const G: usize>(prke: &MultiGroupPRKE<G>,
+        time: Time,
+        reactivity: Ratio,
+        period: Time) -> TransientSnapshot<G> {
+        TransientSnapshot {
+            time,
+            neutron_population_number_density: prke.get_current_neutron_population_density(),
+            precursor_concentration_array: prke.precursor_concentration_array,
+            reactivity,
+            period,
+        }
+    }
+
+    /// period over a step, assuming exponential change:
+    ///
+    /// n_new = n_old exp(dt/period)
+    ///
+    /// infinite if n does not change (or is not positive)
+    fn calc_period(neutron_population_before_step: VolumetricNumberDensity,
+        neutron_population_after_step: VolumetricNumberDensity,
+        timestep: Time) -> Time {
+
+        let n_old: f64 = neutron_population_before_step.get::<per_cubic_meter>();
+        let n_new: f64 = neutron_population_after_step.get::<per_cubic_meter>();
+
+        let log_ratio: Ratio = Ratio::new::<ratio>((n_new/n_old).ln());
+
+        if n_old <= 0.0 || n_new <= 0.0 || log_ratio.get::<ratio>() == 0.0 {
+            return Time::new::<second>(f64::INFINITY);
+        }
+
+        timestep/log_ratio
+    }
+}